@@ -1286,6 +1286,63 @@ fn atoms_are_equivalent_with_bindings<'a, 'b: 'a>(left: &'b Atom, right: &'b Ato
     }
 }
 
+/// Computes the least general generalization (anti-unification) of two
+/// atoms. Returns the generalized atom and two bindings: applying the first
+/// bindings to the generalized atom gives the `left` atom, applying the
+/// second bindings gives the `right` atom. Each pair of different subatoms is
+/// replaced by a fresh variable, the same pair is replaced by the same
+/// variable.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::atom::matcher::{anti_unify, apply_bindings_to_atom_move, atoms_are_equivalent};
+///
+/// let left = expr!("f" "a" "b" "a");
+/// let right = expr!("f" "c" "d" "c");
+///
+/// let (general, left_bindings, right_bindings) = anti_unify(&left, &right);
+///
+/// assert!(atoms_are_equivalent(&general, &expr!("f" x y x)));
+/// assert_eq!(apply_bindings_to_atom_move(general.clone(), &left_bindings), left);
+/// assert_eq!(apply_bindings_to_atom_move(general, &right_bindings), right);
+/// ```
+pub fn anti_unify(left: &Atom, right: &Atom) -> (Atom, Bindings, Bindings) {
+    let mut pairs: Vec<(&Atom, &Atom, VariableAtom)> = Vec::new();
+    let general = anti_unify_recursive(left, right, &mut pairs);
+    let mut left_bindings = Bindings::new();
+    let mut right_bindings = Bindings::new();
+    for (left, right, var) in pairs {
+        left_bindings.add_var_binding(&var, left);
+        right_bindings.add_var_binding(&var, right);
+    }
+    (general, left_bindings, right_bindings)
+}
+
+fn anti_unify_recursive<'a>(left: &'a Atom, right: &'a Atom, pairs: &mut Vec<(&'a Atom, &'a Atom, VariableAtom)>) -> Atom {
+    match (left, right) {
+        _ if left == right => left.clone(),
+        (Atom::Expression(left), Atom::Expression(right))
+            if left.children().len() == right.children().len() => {
+            Atom::expr(left.children().iter().zip(right.children().iter())
+                .map(|(left, right)| anti_unify_recursive(left, right, pairs))
+                .collect::<Vec<Atom>>())
+        },
+        _ => {
+            let var = match pairs.iter().find(|(l, r, _)| *l == left && *r == right) {
+                Some((_, _, var)) => var.clone(),
+                None => {
+                    let var = VariableAtom::new(format!("v{}", pairs.len())).make_unique();
+                    pairs.push((left, right, var.clone()));
+                    var
+                },
+            };
+            Atom::Variable(var)
+        },
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::assert_eq_no_order;
@@ -1352,6 +1409,36 @@ mod test {
         assert!(!atoms_are_equivalent(&expr!(a b), &expr!(b b)));
     }
 
+    #[test]
+    fn anti_unify_similar_expressions() {
+        let left = expr!("f" {1} ("g" "a") {2});
+        let right = expr!("f" {3} ("g" "a") {4});
+
+        let (general, left_bindings, right_bindings) = anti_unify(&left, &right);
+
+        assert!(atoms_are_equivalent(&general, &expr!("f" x ("g" "a") y)));
+        assert_eq!(apply_bindings_to_atom_move(general.clone(), &left_bindings), left);
+        assert_eq!(apply_bindings_to_atom_move(general, &right_bindings), right);
+    }
+
+    #[test]
+    fn anti_unify_different_atoms() {
+        let left = expr!("f" "a");
+        let right = expr!("g" "b" "c");
+
+        let (general, left_bindings, right_bindings) = anti_unify(&left, &right);
+
+        assert!(matches!(general, Atom::Variable(_)));
+        assert_eq!(apply_bindings_to_atom_move(general.clone(), &left_bindings), left);
+        assert_eq!(apply_bindings_to_atom_move(general, &right_bindings), right);
+    }
+
+    #[test]
+    fn anti_unify_equal_atoms() {
+        let atom = expr!("f" x "a");
+        assert_eq!(anti_unify(&atom, &atom), (atom, Bindings::new(), Bindings::new()));
+    }
+
     #[test]
     fn match_spread_value_via_left_variable() {
         assert_match(
@@ -182,6 +182,24 @@ impl ExpressionAtom {
     pub fn into_children(self) -> Vec<Atom> {
         self.children
     }
+
+    /// Returns number of sub-atoms after the head. Empty expression has
+    /// zero arity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::expr;
+    /// use hyperon::atom::ExpressionAtom;
+    ///
+    /// let atom = expr!("f" "a" "b");
+    /// let expr = <&ExpressionAtom>::try_from(&atom).unwrap();
+    ///
+    /// assert_eq!(expr.arity(), 2);
+    /// ```
+    pub fn arity(&self) -> usize {
+        self.children.len().saturating_sub(1)
+    }
 }
 
 impl Display for ExpressionAtom {
@@ -524,6 +542,22 @@ pub trait CustomExecute {
     /// Executes grounded function on passed `args` and returns list of
     /// results as `Vec<Atom>` or [ExecError].
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError>;
+
+    /// Executes grounded function on passed `args` knowing the `bindings`
+    /// of the variables at the moment of the call. Interpreter calls this
+    /// method instead of [CustomExecute::execute]. Default implementation
+    /// ignores `bindings` and calls [CustomExecute::execute].
+    fn execute_bindings(&self, args: &[Atom], _bindings: &matcher::Bindings) -> Result<Vec<Atom>, ExecError> {
+        self.execute(args)
+    }
+
+    /// Returns `true` when the grounded function has no side effects and
+    /// returns the same results for the same arguments. Default
+    /// implementation returns `false` because purity cannot be guaranteed
+    /// for an arbitrary function.
+    fn is_pure(&self) -> bool {
+        false
+    }
 }
 
 /// Trait for implementing custom matching logic. In order to make it work
@@ -928,6 +962,38 @@ impl Atom {
             _ => None,
         }
     }
+
+    /// Returns true if atom doesn't contain variables at any level of
+    /// nesting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::expr;
+    ///
+    /// assert!(expr!("A" ("B" "C")).is_ground());
+    /// assert!(!expr!("A" ("B" x)).is_ground());
+    /// ```
+    pub fn is_ground(&self) -> bool {
+        !self.iter().any(|atom| matches!(atom, Atom::Variable(_)))
+    }
+
+    /// Returns true if atom contains the variable `var` at any level of
+    /// nesting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::{expr, VariableAtom};
+    ///
+    /// let atom = expr!("A" ("B" x));
+    ///
+    /// assert!(atom.contains_variable(&VariableAtom::new("x")));
+    /// assert!(!atom.contains_variable(&VariableAtom::new("y")));
+    /// ```
+    pub fn contains_variable(&self, var: &VariableAtom) -> bool {
+        self.iter().filter_type::<&VariableAtom>().any(|v| v == var)
+    }
 }
 
 impl PartialEq for Atom {
@@ -1243,6 +1309,29 @@ mod test {
                 expression(vec![symbol("fact"), variable("n")])]));
     }
 
+    #[test]
+    fn test_value_atom_custom_struct_in_space() {
+        #[derive(PartialEq, Clone, Debug)]
+        struct Handle {
+            id: u64,
+            name: String,
+        }
+        let handle = Handle{ id: 7, name: "tensor".into() };
+        let space = crate::space::grounding::GroundingSpace::from_vec(vec![
+            Atom::expr([Atom::sym("handle"), Atom::value(handle.clone())]),
+        ]);
+
+        let stored = space.iter().next().and_then(|atom| match atom {
+            Atom::Expression(expr) => expr.children().get(1).cloned(),
+            _ => None,
+        }).expect("Stored value is expected");
+
+        assert_eq!(stored.as_gnd::<Handle>(), Some(&handle));
+        assert_eq!(stored, Atom::value(handle));
+        assert_ne!(stored, Atom::value(Handle{ id: 8, name: "tensor".into() }));
+        assert_eq!(stored.to_string(), "Handle { id: 7, name: \"tensor\" }");
+    }
+
     #[test]
     fn test_custom_type() {
         let atom = Atom::value(42);
@@ -1291,4 +1380,21 @@ mod test {
             Err("Atom is not an ExpressionAtom"));
     }
 
+    #[test]
+    fn test_is_ground() {
+        assert!(sym!("A").is_ground());
+        assert!(expr!("A" ({1}) ("B" "C")).is_ground());
+        assert!(!expr!(x).is_ground());
+        assert!(!expr!("A" ("B" ("C" x))).is_ground());
+    }
+
+    #[test]
+    fn test_contains_variable() {
+        let atom = expr!("A" ("B" ("C" x)) y);
+        assert!(atom.contains_variable(&VariableAtom::new("x")));
+        assert!(atom.contains_variable(&VariableAtom::new("y")));
+        assert!(!atom.contains_variable(&VariableAtom::new("z")));
+        assert!(!sym!("x").contains_variable(&VariableAtom::new("x")));
+    }
+
 }
@@ -0,0 +1,37 @@
+//! Generator of the variables which are used by interpreter internally.
+//!
+//! All generated variables have [INTERNAL_VARIABLE_PREFIX] prefix in their
+//! names and unique ids taken from the same monotonic counter as
+//! [VariableAtom::make_unique] uses. Thus they are easy to distinguish in
+//! interpreter traces and never clash with variables written by user.
+
+use crate::atom::VariableAtom;
+
+/// Prefix of the names of the interpreter internal variables.
+pub const INTERNAL_VARIABLE_PREFIX: &str = "__tmp_";
+
+/// Returns new unique internal variable. Name of the variable consists of
+/// [INTERNAL_VARIABLE_PREFIX] and `name` passed.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::common::gensym::{gensym, is_internal_variable};
+/// use hyperon::VariableAtom;
+///
+/// let x1 = gensym("X");
+/// let x2 = gensym("X");
+///
+/// assert!(x1.name().starts_with("__tmp_X#"));
+/// assert_ne!(x1, x2);
+/// assert!(is_internal_variable(&x1));
+/// assert!(!is_internal_variable(&VariableAtom::new("X")));
+/// ```
+pub fn gensym(name: &str) -> VariableAtom {
+    VariableAtom::new(format!("{}{}", INTERNAL_VARIABLE_PREFIX, name)).make_unique()
+}
+
+/// Returns true if variable was created by [gensym].
+pub fn is_internal_variable(var: &VariableAtom) -> bool {
+    var.name().starts_with(INTERNAL_VARIABLE_PREFIX)
+}
@@ -8,6 +8,7 @@ pub mod reformove;
 pub mod multitrie;
 pub mod holeyvec;
 pub mod owned_or_borrowed;
+pub mod gensym;
 
 mod flex_ref;
 pub use flex_ref::FlexRef;
@@ -1,4 +1,43 @@
+//! Generic infrastructure to describe stepwise calculations.
+//!
+//! A calculation is described by a [Plan] which consumes an input value and
+//! makes a single step of the calculation. Each step returns a [StepResult]
+//! which is either a next plan to execute, a final result or an error. Thus
+//! the caller controls the execution and can stop, log or interleave
+//! calculations between steps. Plans are composed using [SequencePlan],
+//! [ParallelPlan], [OrPlan], [ApplyPlan], [PartialApplyPlan] and others.
+//! [execute_plan] runs a plan until it returns a result or an error.
+//!
+//! # Examples
+//!
+//! Custom plan which adds two numbers in two steps:
+//!
+//! ```
+//! use hyperon::common::plan::*;
+//!
+//! #[derive(Debug)]
+//! enum AddPlan {
+//!     Start(i64, i64),
+//!     Sum(i64),
+//! }
+//!
+//! impl<'a> Plan<'a, (), i64, String> for AddPlan {
+//!     fn step(self: Box<Self>, _: ()) -> StepResult<'a, i64, String> {
+//!         match *self {
+//!             AddPlan::Start(a, b) => StepResult::execute(AddPlan::Sum(a + b)),
+//!             AddPlan::Sum(sum) => StepResult::ret(sum),
+//!         }
+//!     }
+//! }
+//!
+//! let plan = SequencePlan::new(AddPlan::Start(2, 3),
+//!     OperatorPlan::new(|sum: i64| StepResult::ret(sum * 10), "* 10"));
+//!
+//! assert_eq!(execute_plan(plan, ()), Ok::<i64, String>(50));
+//! ```
+
 use std::fmt::{Debug, Formatter};
+use std::collections::VecDeque;
 
 // Generic plan infrastructure
 
@@ -48,6 +87,20 @@ pub trait Plan<'a, T, R: 'a, E: 'a> : Debug {
     fn step(self: Box<Self>, arg: T) -> StepResult<'a, R, E>;
 }
 
+/// Execute the plan using given input value until it returns a result or
+/// an error.
+pub fn execute_plan<'a, T: Debug + 'a, R: 'a, E: Debug + 'a, P>(plan: P, arg: T) -> Result<R, E> where P: 'a + Plan<'a, T, R, E> {
+    let mut step: Box<dyn Plan<'_, (), R, E>> = Box::new(ApplyPlan::new(plan, arg));
+    loop {
+        log::debug!("current plan:\n{:?}", step);
+        match step.step(()) {
+            StepResult::Execute(next) => step = next,
+            StepResult::Return(result) => return Ok(result),
+            StepResult::Error(error) => return Err(error),
+        }
+    }
+}
+
 // Specific plans to form calculations graph
 
 /// Boxed plan is a plan
@@ -111,6 +164,7 @@ pub struct OperatorPlan<'a, T, R: 'a, E: 'a> {
 }
 
 impl<'a, T, R: 'a, E: 'a> OperatorPlan<'a, T, R, E> {
+    /// New plan from the operator and its name which is used for debugging
     pub fn new<F: 'a + FnOnce(T) -> StepResult<'a, R, E>, N: Into<String>>(operator: F, name: N) -> Self {
         Self { operator: Box::new(operator), name: name.into() }
     }
@@ -136,6 +190,7 @@ pub struct ApplyPlan<'a, T: 'a, R: 'a, E: 'a> {
 }
 
 impl<'a, T: 'a, R: 'a, E: 'a> ApplyPlan<'a, T, R, E> {
+    /// New plan which applies `arg` to the `plan`
     pub fn new<P>(plan: P, arg: T) -> Self where P: 'a + Plan<'a, T, R, E> {
         ApplyPlan{ arg, plan: Box::new(plan) }
     }
@@ -162,6 +217,7 @@ pub struct PartialApplyPlan<'a, T1: 'a, T2, R: 'a, E: 'a> {
 }
 
 impl<'a, T1: 'a, T2, R: 'a, E: 'a> PartialApplyPlan<'a, T1, T2, R, E> {
+    /// New plan which applies `arg` as a first argument to the `plan`
     pub fn new<P>(plan: P, arg: T1) -> Self where P: 'a + Plan<'a, (T1, T2), R, E> {
         PartialApplyPlan{ arg, plan: Box::new(plan) }
     }
@@ -186,6 +242,7 @@ pub struct SequencePlan<'a, T1, T2: 'a, R: 'a, E: 'a> {
 }
 
 impl<'a, T1, T2: 'a, R: 'a, E: 'a> SequencePlan<'a, T1, T2, R, E> {
+    /// New plan which executes `first` and passes its result to `second`
     pub fn new<P1, P2>(first: P1, second: P2) -> Self
         where P1: 'a + Plan<'a, T1, T2, E>,
               P2: 'a + Plan<'a, T2, R, E> {
@@ -223,6 +280,7 @@ pub struct ParallelPlan<'a, T1: 'a, T2: 'a, E: 'a> {
 }
 
 impl<'a, T1: 'a, T2: 'a, E: 'a> ParallelPlan<'a, T1, T2, E> {
+    /// New plan which executes `first` and `second` and returns pair of results
     pub fn new<P1, P2>(first: P1, second: P2) -> Self
         where P1: 'a + Plan<'a, (), T1, E>,
               P2: 'a + Plan<'a, (), T2, E> {
@@ -261,6 +319,20 @@ impl<T1, T2, E> Debug for ParallelPlan<'_, T1, T2, E> {
     }
 }
 
+/// Order in which results of the sub-plans are merged by the plan returned
+/// from [FoldIntoParallelPlan::into_parallel_plan_with_order].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultOrder {
+    /// Sub-plans are executed one after another and results are merged in
+    /// the order of the original sequence (depth-first).
+    #[default]
+    Evaluation,
+    /// Sub-plans are executed step by step in a round-robin manner and
+    /// results are merged as soon as sub-plan returns, thus results of the
+    /// shallower sub-plans go first (breadth-first).
+    Completion,
+}
+
 /// Trait to fold the value (typically sequence of sub-values) to the plan
 /// which processes each sub-value in parallel and merges the results.
 pub trait FoldIntoParallelPlan<'a, I, T, R, E>
@@ -271,6 +343,16 @@ pub trait FoldIntoParallelPlan<'a, I, T, R, E>
     /// a plan to calculate result. It applies `merge` to plan result and
     /// step result to calculate the final result. Resulting plan is returned.
     fn into_parallel_plan<S, M>(self, empty: R, step: S, merge: M) -> Box<dyn Plan<'a, (), R, E> + 'a>
+        where
+          S: FnMut(I::Item) -> Box<dyn Plan<'a, (), T, E> + 'a>,
+          M: 'a + FnMut(R, T) -> R + Clone,
+          Self: Sized {
+        self.into_parallel_plan_with_order(empty, step, merge, ResultOrder::default())
+    }
+
+    /// The same as [FoldIntoParallelPlan::into_parallel_plan] but allows
+    /// choosing the order in which step results are merged, see [ResultOrder].
+    fn into_parallel_plan_with_order<S, M>(self, empty: R, step: S, merge: M, order: ResultOrder) -> Box<dyn Plan<'a, (), R, E> + 'a>
         where
           S: FnMut(I::Item) -> Box<dyn Plan<'a, (), T, E> + 'a>,
           M: 'a + FnMut(R, T) -> R + Clone;
@@ -281,26 +363,68 @@ impl<'a, I: 'a, T: 'a, R, E> FoldIntoParallelPlan<'a, I, T, R, E> for I
           T: 'a + Debug,
           R: 'a + Debug,
           E: 'a + Debug {
-    fn into_parallel_plan<S, M>(self, empty: R, mut step: S, merge: M) -> Box<dyn Plan<'a, (), R, E> + 'a>
+    fn into_parallel_plan_with_order<S, M>(self, empty: R, mut step: S, merge: M, order: ResultOrder) -> Box<dyn Plan<'a, (), R, E> + 'a>
         where
           S: FnMut(I::Item) -> Box<dyn Plan<'a, (), T, E> + 'a>,
           M: 'a + FnMut(R, T) -> R + Clone {
-        let plan: Box<dyn Plan<'a, (), R, E> + 'a> = self
-            .fold(Box::new(StepResult::ret(empty)),
-                |plan, step_result| {
-                    let mut merge = merge.clone();
-                    Box::new(SequencePlan::new(
-                        ParallelPlan {
-                            first: plan,
-                            second: step(step_result),
-                        },
-                        OperatorPlan::new(move |(plan_res, step_res)|
-                            StepResult::ret(merge(plan_res, step_res)),
-                            "merge_results"),
-                    ))
+        match order {
+            ResultOrder::Evaluation => self
+                .fold(Box::new(StepResult::ret(empty)),
+                    |plan, step_result| {
+                        let mut merge = merge.clone();
+                        Box::new(SequencePlan::new(
+                            ParallelPlan {
+                                first: plan,
+                                second: step(step_result),
+                            },
+                            OperatorPlan::new(move |(plan_res, step_res)|
+                                StepResult::ret(merge(plan_res, step_res)),
+                                "merge_results"),
+                        ))
+                    }
+                ),
+            ResultOrder::Completion => Box::new(RoundRobinPlan {
+                result: empty,
+                plans: self.map(step).collect(),
+                merge: Box::new(merge),
+            }),
+        }
+    }
+}
+
+/// Plan which executes sub-plans step by step in a round-robin manner and
+/// merges each sub-plan result as soon as it is returned.
+struct RoundRobinPlan<'a, T, R, E> {
+    result: R,
+    plans: VecDeque<Box<dyn Plan<'a, (), T, E> + 'a>>,
+    merge: Box<dyn FnMut(R, T) -> R + 'a>,
+}
+
+/// Return error if any of sub-plans returned error
+impl<'a, T: 'a, R: 'a + Debug, E: 'a> Plan<'a, (), R, E> for RoundRobinPlan<'a, T, R, E> {
+    fn step(mut self: Box<Self>, _: ()) -> StepResult<'a, R, E> {
+        match self.plans.pop_front() {
+            None => StepResult::ret(self.result),
+            Some(plan) => {
+                match plan.step(()) {
+                    StepResult::Execute(next) => self.plans.push_back(next),
+                    StepResult::Return(step_res) => {
+                        let RoundRobinPlan{ result, plans, mut merge } = *self;
+                        let result = merge(result, step_res);
+                        self = Box::new(RoundRobinPlan{ result, plans, merge });
+                    },
+                    StepResult::Error(err) => return StepResult::Error(err),
                 }
-            );
-        plan
+                StepResult::Execute(self)
+            },
+        }
+    }
+}
+
+impl<T, R: Debug, E> Debug for RoundRobinPlan<'_, T, R, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "round robin {:?}", self.result)?;
+        self.plans.iter().try_for_each(|plan| write!(f, "\n{:?}", plan))
     }
 }
 
@@ -310,6 +434,7 @@ pub struct NoErrorPlan<'a, T, R, E> {
 }
 
 impl<'a, T, R, E> NoErrorPlan<'a, T, R, E> {
+    /// New plan which converts error of the `delegate` into `None`
     pub fn new<P>(delegate: P) -> Self
         where P: 'a + Plan<'a, T, R, E> {
         Self{ delegate: Box::new(delegate) }
@@ -340,6 +465,7 @@ pub struct OrPlan<'a, R, E> {
 }
 
 impl<'a, R, E> OrPlan<'a, R, E> {
+    /// New plan which executes `second` when `first` returns error
     pub fn new<P1, P2>(first: P1, second: P2) -> Self
         where P1: 'a + Plan<'a, (), R, E>,
               P2: 'a + Plan<'a, (), R, E> {
@@ -376,24 +502,40 @@ impl<R, E> Debug for OrPlan<'_, R, E> {
 mod tests {
     use super::*;
 
-    /// Execute the plan using given input value and return result
-    fn execute_plan<'a, T: Debug + 'a, R: 'a, P>(plan: P, arg: T) -> Result<R, String> where P: 'a + Plan<'a, T, R, String> {
-        let mut step: Box<dyn Plan<'_, (), R, String>>  = Box::new(ApplyPlan::new(plan, arg));
-        loop {
-            log::debug!("current plan:\n{:?}", step);
-            match step.step(()) {
-                StepResult::Execute(next) => step = next,
-                StepResult::Return(result) => return Ok(result),
-                StepResult::Error(error) => return Err(error),
+    #[derive(Debug)]
+    enum AddPlan {
+        First(i64),
+        Second(i64),
+    }
+
+    impl<'a> Plan<'a, i64, i64, String> for AddPlan {
+        fn step(self: Box<Self>, arg: i64) -> StepResult<'a, i64, String> {
+            match *self {
+                AddPlan::First(a) => StepResult::execute(ApplyPlan::new(AddPlan::Second(a + arg), 0)),
+                AddPlan::Second(sum) => StepResult::ret(sum + arg),
             }
         }
     }
 
+    #[test]
+    fn custom_plan_adds_two_numbers() {
+        let mut step = StepResult::execute(ApplyPlan::new(AddPlan::First(2), 3));
+        let mut steps = 0;
+        while step.has_next() {
+            step = Box::new(step).step(());
+            steps += 1;
+        }
+        assert_eq!(steps, 2);
+        assert!(matches!(step, StepResult::Return(5)));
+
+        assert_eq!(execute_plan(AddPlan::First(2), 3), Ok(5));
+    }
+
     #[test]
     fn parallel_plan() {
         let mul = SequencePlan::new(
             ParallelPlan::new(
-                StepResult::ret(7),
+                StepResult::<_, String>::ret(7),
                 StepResult::ret(6)),
             OperatorPlan::new(|(a, b)| StepResult::ret(a * b), "*"),
         );
@@ -407,13 +549,37 @@ mod tests {
         let plan = args.iter().into_parallel_plan(Vec::new(),
             |n| {
                 *step_counter += 1;
-                Box::new(ApplyPlan::new(OperatorPlan::new(|n: &str| StepResult::ret(n.parse::<u32>().unwrap() + 1), format!("* {}", n)), *n))
+                Box::new(ApplyPlan::new(OperatorPlan::new(|n: &str| StepResult::<_, String>::ret(n.parse::<u32>().unwrap() + 1), format!("* {}", n)), *n))
             },
             |mut a, b| {a.push(b); a});
         assert_eq!(execute_plan(StepResult::Execute(plan), ()), Ok(vec![2, 3, 4, 5]));
         assert_eq!(*step_counter, 4);
     }
 
+    fn delayed_plan(steps: u32, value: &'static str) -> Box<dyn Plan<'static, (), &'static str, String>> {
+        if steps == 0 {
+            Box::new(StepResult::ret(value))
+        } else {
+            Box::new(OperatorPlan::new(move |_| StepResult::Execute(delayed_plan(steps - 1, value)), format!("delay {}", value)))
+        }
+    }
+
+    #[test]
+    fn iterator_into_parallel_plan_result_order() {
+        // branches of different depth: "deep" needs three steps, "shallow"
+        // one step and "middle" two steps to be calculated
+        let branches = [(3, "deep"), (1, "shallow"), (2, "middle")];
+        let merge = |mut a: Vec<&'static str>, b| {a.push(b); a};
+
+        let plan = branches.into_iter().into_parallel_plan(Vec::new(),
+            |(steps, value)| delayed_plan(steps, value), merge);
+        assert_eq!(execute_plan(StepResult::Execute(plan), ()), Ok(vec!["deep", "shallow", "middle"]));
+
+        let plan = branches.into_iter().into_parallel_plan_with_order(Vec::new(),
+            |(steps, value)| delayed_plan(steps, value), merge, ResultOrder::Completion);
+        assert_eq!(execute_plan(StepResult::Execute(plan), ()), Ok(vec!["shallow", "middle", "deep"]));
+    }
+
     #[test]
     fn step_result_plan() {
         let plan = Box::new(StepResult::execute(OperatorPlan::new(
@@ -7,12 +7,15 @@ use crate::space::*;
 use crate::metta::*;
 use crate::metta::types::*;
 use crate::metta::runner::stdlib_minimal::IfEqualOp;
+use crate::metta::runner::string::Str;
+use crate::common::gensym::gensym;
 
 use std::fmt::{Debug, Display, Formatter};
 use std::convert::TryFrom;
 use std::rc::Rc;
 use std::fmt::Write;
 use std::cell::RefCell;
+use std::collections::HashMap;
 
 macro_rules! match_atom {
     ($atom:tt ~ $pattern:tt => $succ:tt , _ => $error:tt) => {
@@ -143,14 +146,45 @@ impl Display for InterpretedAtom {
     }
 }
 
-#[derive(Debug)]
-struct InterpreterContext<T: Space> {
+/// Callback which returns grounded operation by its name. It is used by
+/// [interpret_with_resolver] to bind operations on demand.
+pub type OperationResolver<'a> = &'a dyn Fn(&str) -> Option<Box<dyn GroundedAtom>>;
+
+struct InterpreterContext<'a, T: Space> {
     space: T,
+    resolver: Option<OperationResolver<'a>>,
+    resolved: RefCell<HashMap<String, Option<Atom>>>,
+    /// Errors which are dropped by `check_alternatives` because other
+    /// alternatives are successful, collected only when `Some`.
+    dropped_errors: Option<RefCell<Vec<Atom>>>,
 }
 
-impl<T: Space> InterpreterContext<T> {
+impl<'a, T: Space> InterpreterContext<'a, T> {
     fn new(space: T) -> Self {
-        Self{ space }
+        Self{ space, resolver: None, resolved: RefCell::new(HashMap::new()), dropped_errors: None }
+    }
+
+    fn with_resolver(space: T, resolver: OperationResolver<'a>) -> Self {
+        Self{ space, resolver: Some(resolver), resolved: RefCell::new(HashMap::new()), dropped_errors: None }
+    }
+
+    /// Returns grounded operation for the symbol using resolver. Resolver is
+    /// called once per symbol name, the result (including absence of the
+    /// operation) is cached.
+    fn resolve(&self, name: &SymbolAtom) -> Option<Atom> {
+        let resolver = self.resolver?;
+        self.resolved.borrow_mut().entry(name.name().to_string())
+            .or_insert_with(|| resolver(name.name()).map(Atom::Grounded))
+            .clone()
+    }
+}
+
+impl<T: Space + Debug> Debug for InterpreterContext<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterpreterContext")
+            .field("space", &self.space)
+            .field("resolved", &self.resolved)
+            .finish()
     }
 }
 
@@ -168,7 +202,7 @@ pub struct InterpreterState<'a, T: SpaceRef<'a>> {
     /// List of the completely evaluated results to be returned.
     finished: Vec<Atom>,
     /// Evaluation context.
-    context: InterpreterContext<T>,
+    context: InterpreterContext<'a, T>,
     phantom: std::marker::PhantomData<dyn SpaceRef<'a>>,
 }
 
@@ -280,6 +314,86 @@ pub fn interpret<T: Space>(space: T, expr: &Atom) -> Result<Vec<Atom>, String> {
     state.into_result()
 }
 
+/// Interpret passed atom the same way as [interpret] does but perform at most
+/// `step_limit` interpretation steps. Returns an error when the limit is
+/// exceeded before interpretation is finished.
+/// # Arguments
+/// * `space` - atomspace to query for interpretation
+/// * `expr` - atom to interpret
+/// * `step_limit` - maximal number of steps
+pub fn interpret_with_limit<T: Space>(space: T, expr: &Atom, step_limit: usize) -> Result<Vec<Atom>, String> {
+    let mut state = interpret_init(space, expr);
+    let mut steps = 0;
+    while state.has_next() {
+        if steps == step_limit {
+            return Err(format!("Step limit {} is exceeded while interpreting {}", step_limit, expr));
+        }
+        steps += 1;
+        state = interpret_step(state);
+    }
+    state.into_result()
+}
+
+/// Interpret passed atom the same way as [interpret] does but resolve
+/// operations which are not defined in the space using `resolver`. When
+/// the expression headed by a symbol has no `(= <lhs> <rhs>)` definitions
+/// in the space and the symbol is not a data constructor of the space the
+/// resolver is asked for the grounded operation with the symbol's name. If operation is returned it is executed on the arguments
+/// of the expression. Results of the resolver are cached for the whole
+/// interpretation, thus the resolver is called at most once for each name.
+/// # Arguments
+/// * `space` - atomspace to query for interpretation
+/// * `expr` - atom to interpret
+/// * `resolver` - callback which returns grounded operation by its name
+pub fn interpret_with_resolver<T: Space>(space: T, expr: &Atom, resolver: OperationResolver) -> Result<Vec<Atom>, String> {
+    let mut state = interpret_init(space, expr);
+    state.context = InterpreterContext::with_resolver(state.context.space, resolver);
+    while state.has_next() {
+        state = interpret_step(state);
+    }
+    state.into_result()
+}
+
+/// Interpret passed atom and return successful results and error messages
+/// separately. Error expressions returned by some branches of the
+/// interpretation don't prevent other branches from returning results, thus
+/// partial success is possible. Errors which are dropped by `metta` because
+/// other alternatives of the same expression are successful are included
+/// into the list of error messages as well. Error returned by [interpret]
+/// itself is placed into the list of error messages.
+/// # Arguments
+/// * `space` - atomspace to query for interpretation
+/// * `expr` - atom to interpret
+pub fn interpret_partial<T: Space>(space: T, expr: &Atom) -> (Vec<Atom>, Vec<String>) {
+    let mut state = interpret_init(space, expr);
+    state.context.dropped_errors = Some(RefCell::new(vec![]));
+    while state.has_next() {
+        state = interpret_step(state);
+    }
+    let dropped = state.context.dropped_errors.take().unwrap_or_default().into_inner();
+    match state.into_result() {
+        Ok(results) => {
+            let (errors, results): (Vec<Atom>, Vec<Atom>) = results.into_iter()
+                .partition(atom_is_error);
+            (results, dropped.iter().chain(errors.iter()).map(error_message).collect())
+        },
+        Err(message) => (vec![], vec![message]),
+    }
+}
+
+/// Returns message of the `(Error <atom> <message>)` expression. Symbol and
+/// string messages are returned as is, other atoms are converted to string.
+pub(crate) fn error_message(error: &Atom) -> String {
+    match atom_as_slice(error).and_then(|children| children.last()) {
+        Some(Atom::Symbol(message)) => message.name().to_string(),
+        Some(message) => match message.as_gnd::<Str>() {
+            Some(message) => message.as_str().to_string(),
+            None => message.to_string(),
+        },
+        None => error.to_string(),
+    }
+}
+
 fn is_embedded_op(atom: &Atom) -> bool {
     let expr = atom_as_slice(&atom);
     match expr {
@@ -357,7 +471,7 @@ impl Display for Variables {
     }
 }
 
-fn interpret_stack<'a, T: Space>(context: &InterpreterContext<T>, stack: Stack, mut bindings: Bindings) -> Vec<InterpretedAtom> {
+fn interpret_stack<'a, T: Space>(context: &InterpreterContext<'_, T>, stack: Stack, mut bindings: Bindings) -> Vec<InterpretedAtom> {
     if stack.finished {
         // first executed minimal operation returned error
         if stack.prev.is_none() {
@@ -406,7 +520,7 @@ fn interpret_stack<'a, T: Space>(context: &InterpreterContext<T>, stack: Stack,
                 metta_sym(stack, bindings)
             },
             Some([op, ..]) if *op == CALL_NATIVE_SYMBOL => {
-                call_native_symbol(stack, bindings)
+                call_native_symbol(context, stack, bindings)
             },
             _ => {
                 let stack = Stack::finished(stack.prev, stack.atom);
@@ -433,7 +547,7 @@ fn finished_result(atom: Atom, bindings: Bindings, prev: Option<Rc<RefCell<Stack
     vec![InterpretedAtom(Stack::finished(prev, atom), bindings)]
 }
 
-fn eval<'a, T: Space>(context: &InterpreterContext<T>, stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom> {
+fn eval<'a, T: Space>(context: &InterpreterContext<'_, T>, stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom> {
     let Stack{ prev, atom: eval, ret: _, finished: _, vars } = stack;
     let to_eval = match_atom!{
         eval ~ [_op, to_eval] => to_eval,
@@ -445,44 +559,60 @@ fn eval<'a, T: Space>(context: &InterpreterContext<T>, stack: Stack, bindings: B
     let to_eval = apply_bindings_to_atom_move(to_eval, &bindings);
     log::debug!("eval: to_eval: {}", to_eval);
     match atom_as_slice(&to_eval) {
-        Some([Atom::Grounded(op), args @ ..]) => {
-            match op.as_grounded().as_execute() {
-                None => finished_result(return_not_reducible(), bindings, prev),
-                Some(executable) => {
-                    let exec_res = executable.execute(args);
-                    log::debug!("eval: execution results: {:?}", exec_res);
-                    match exec_res {
-                        Ok(results) => {
-                            if results.is_empty() {
-                                // There is no valid reason to return empty result from
-                                // the grounded function. If alternative should be removed
-                                // from the plan then EMPTY_SYMBOL is a proper result.
-                                // If grounded atom returns no value then UNIT_ATOM()
-                                // should be returned. NotReducible or Exec::NoReduce
-                                // can be returned to let a caller know that function
-                                // is not defined on a passed input data. Thus we can
-                                // interpreter empty result by any way we like.
-                                finished_result(EMPTY_SYMBOL, bindings, prev)
-                            } else {
-                                let call_stack = call_to_stack(to_eval, vars, prev.clone());
-                                results.into_iter()
-                                    .map(|res| eval_result(prev.clone(), res, &call_stack, bindings.clone()))
-                                    .collect()
-                            }
-                        },
-                        Err(ExecError::Runtime(err)) =>
-                            finished_result(error_msg(to_eval, err), bindings, prev),
-                        Err(ExecError::NoReduce) =>
-                            // TODO: we could remove ExecError::NoReduce and explicitly
-                            // return NOT_REDUCIBLE_SYMBOL from the grounded function instead.
-                            finished_result(return_not_reducible(), bindings, prev),
+        Some([Atom::Grounded(_), ..]) => execute_op(to_eval, vars, bindings, prev),
+        _ if is_embedded_op(&to_eval) =>
+            vec![InterpretedAtom(atom_to_stack(to_eval, prev), bindings)],
+        _ => query(context, prev, to_eval, bindings, vars),
+    }
+}
+
+fn execute_op(to_eval: Atom, vars: Variables, bindings: Bindings, prev: Option<Rc<RefCell<Stack>>>) -> Vec<InterpretedAtom> {
+    let (op, args) = match atom_as_slice(&to_eval) {
+        Some([Atom::Grounded(op), args @ ..]) => (op, args),
+        _ => panic!("Grounded operation call is expected, found: {}", to_eval),
+    };
+    match op.as_grounded().as_execute() {
+        None => finished_result(return_not_reducible(), bindings, prev),
+        Some(executable) => {
+            let exec_res = executable.execute_bindings(args, &bindings);
+            log::debug!("eval: execution results: {:?}", exec_res);
+            match exec_res {
+                Ok(results) => {
+                    if results.is_empty() {
+                        // There is no valid reason to return empty result from
+                        // the grounded function. If alternative should be removed
+                        // from the plan then EMPTY_SYMBOL is a proper result.
+                        // If grounded atom returns no value then UNIT_ATOM()
+                        // should be returned. NotReducible or Exec::NoReduce
+                        // can be returned to let a caller know that function
+                        // is not defined on a passed input data. Thus we can
+                        // interpreter empty result by any way we like.
+                        finished_result(EMPTY_SYMBOL, bindings, prev)
+                    } else {
+                        let call_stack = call_to_stack(to_eval, vars, prev.clone());
+                        results.into_iter()
+                            .map(|res| eval_result(prev.clone(), res, &call_stack, bindings.clone()))
+                            .collect()
                     }
                 },
+                Err(ExecError::Runtime(err)) =>
+                    finished_result(error_msg(to_eval, err), bindings, prev),
+                Err(ExecError::NoReduce) =>
+                    // TODO: we could remove ExecError::NoReduce and explicitly
+                    // return NOT_REDUCIBLE_SYMBOL from the grounded function instead.
+                    finished_result(return_not_reducible(), bindings, prev),
             }
         },
-        _ if is_embedded_op(&to_eval) =>
-            vec![InterpretedAtom(atom_to_stack(to_eval, prev), bindings)],
-        _ => query(&context.space, prev, to_eval, bindings, vars),
+    }
+}
+
+/// Returns grounded operation resolved by the name of the expression's head
+/// symbol. Returns `None` when there is no resolver in the context. Should be
+/// called only when the expression has no definitions in the space.
+fn resolve_undefined_op<T: Space>(context: &InterpreterContext<'_, T>, atom: &Atom) -> Option<Atom> {
+    match atom_as_slice(atom) {
+        Some([Atom::Symbol(name), ..]) => context.resolve(name),
+        _ => None,
     }
 }
 
@@ -531,7 +661,16 @@ fn is_variable_op_expr(expr: &ExpressionAtom) -> bool {
     }
 }
 
-fn query<'a, T: Space>(space: T, prev: Option<Rc<RefCell<Stack>>>, to_eval: Atom, bindings: Bindings, vars: Variables) -> Vec<InterpretedAtom> {
+fn is_data_constructor_expr<T: Space>(space: &T, atom: &Atom) -> bool {
+    match atom {
+        Atom::Expression(expr) => expr.children().first()
+            .is_some_and(|head| space.is_data_constructor(head)),
+        _ => false,
+    }
+}
+
+fn query<'a, T: Space>(context: &InterpreterContext<'_, T>, prev: Option<Rc<RefCell<Stack>>>, to_eval: Atom, bindings: Bindings, vars: Variables) -> Vec<InterpretedAtom> {
+    let space = &context.space;
     #[cfg(not(feature = "variable_operation"))]
     if is_variable_op(&to_eval) {
         // TODO: This is a hotfix. Better way of doing this is adding
@@ -539,9 +678,21 @@ fn query<'a, T: Space>(space: T, prev: Option<Rc<RefCell<Stack>>>, to_eval: Atom
         // in order to skip such evaluations in metta-call function.
         return finished_result(return_not_reducible(), bindings, prev)
     }
-    let var_x = &VariableAtom::new("X").make_unique();
+    if is_data_constructor_expr(&space, &to_eval) {
+        return finished_result(return_not_reducible(), bindings, prev)
+    }
+    let var_x = &gensym("X");
     let query = Atom::expr([EQUAL_SYMBOL, to_eval.clone(), Atom::Variable(var_x.clone())]);
     let results = space.query(&query);
+    if results.is_empty() {
+        if let Some(op) = resolve_undefined_op(context, &to_eval) {
+            let mut call = to_eval;
+            if let Some(head) = atom_as_slice_mut(&mut call).and_then(|call| call.first_mut()) {
+                *head = op;
+            }
+            return execute_op(call, vars, bindings, prev);
+        }
+    }
     log::debug!("interpreter_minimal::query: query: {}", query);
     log::debug!("interpreter_minimal::query: results.len(): {}, bindings.len(): {}, results: {} bindings: {}",
         results.len(), bindings.len(), results, bindings);
@@ -627,7 +778,12 @@ fn chain(stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom> {
     vec![InterpretedAtom(atom_to_stack(templ, prev), bindings)]
 }
 
-fn function_to_stack(mut atom: Atom, prev: Option<Rc<RefCell<Stack>>>) -> Stack {
+fn function_to_stack(atom: Atom, prev: Option<Rc<RefCell<Stack>>>) -> Stack {
+    let vars = Stack::vars_copy(&prev);
+    function_to_stack_with_vars(atom, prev, vars)
+}
+
+fn function_to_stack_with_vars(mut atom: Atom, prev: Option<Rc<RefCell<Stack>>>, vars: Variables) -> Stack {
     let mut nested = Atom::sym("%Nested%");
     let nested_arg = match atom_as_slice_mut(&mut atom) {
         Some([_op, nested @ Atom::Expression(_)]) => nested,
@@ -637,7 +793,7 @@ fn function_to_stack(mut atom: Atom, prev: Option<Rc<RefCell<Stack>>>) -> Stack
         },
     };
     std::mem::swap(nested_arg, &mut nested);
-    let cur = Stack::from_prev_keep_vars(prev, atom, function_ret);
+    let cur = Stack::from_prev_with_vars(prev, atom, vars, function_ret);
     atom_to_stack(nested, Some(Rc::new(RefCell::new(cur))))
 }
 
@@ -832,7 +988,7 @@ fn superpose_bind(stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom> {
 
 type NativeFunc = fn(Atom, Bindings) -> MettaResult;
 
-fn call_native_symbol(stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom> {
+fn call_native_symbol<T: Space>(context: &InterpreterContext<'_, T>, stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom> {
     let Stack{ prev, atom: call, ret: _, finished: _, vars } = stack;
     let (name, func, args) = match_atom!{
         call ~ [_op, name, func, args]
@@ -842,6 +998,11 @@ fn call_native_symbol(stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom>
             return finished_result(error_msg(call, error), bindings, prev);
         }
     };
+    if let Some(dropped) = &context.dropped_errors {
+        if name == Atom::sym("check_alternatives") {
+            dropped.borrow_mut().extend(dropped_alternatives(&args));
+        }
+    }
 
     let call_stack = Some(call_to_stack(Atom::expr([name, args.clone()]), vars, prev));
     let func = func.as_gnd::<NativeFunc>().expect("Unexpected state");
@@ -861,9 +1022,86 @@ fn metta_sym(stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom> {
         }
     };
 
+    if get_meta_type(&atom) == ATOM_TYPE_EXPRESSION && typ != ATOM_TYPE_ATOM && typ != ATOM_TYPE_EXPRESSION {
+        if let Some(collapse) = tail_call_collapse_bind(&prev) {
+            log::debug!("metta_sym: tail call: {}", atom);
+            // variables of the skipped frames are kept to not lose their
+            // bindings before the result is returned
+            let vars = Stack::vars_copy(&prev);
+            let call = call_native!(interpret_expression, Atom::expr([atom, typ, space]));
+            return vec![InterpretedAtom(function_to_stack_with_vars(call, Some(collapse), vars), bindings)]
+        }
+    }
     vec![InterpretedAtom(atom_to_stack(call_native!(metta_impl, Atom::expr([atom, typ, space])), prev), bindings)]
 }
 
+/// Returns the collapse-bind frame of the enclosing `metta` call when the
+/// `metta` call on top of `prev` is in a tail position: frames between the
+/// call and the collapse-bind frame only pass the results up. Results of
+/// such call can be collected by the enclosing collapse-bind directly
+/// because applying check_alternatives to the union of the results gives
+/// the same results as applying it to the nested results first. Thus tail
+/// recursive function is evaluated using the bounded stack.
+fn tail_call_collapse_bind(prev: &Option<Rc<RefCell<Stack>>>) -> Option<Rc<RefCell<Stack>>> {
+    // true when the result is wrapped into (return <result>)
+    let mut wrapped = false;
+    let mut frame = prev.clone();
+    while let Some(stack) = frame {
+        let next = {
+            let stack = stack.borrow();
+            if std::ptr::fn_addr_eq(stack.ret, call_ret as ReturnHandler) {
+                stack.prev.clone()
+            } else if std::ptr::fn_addr_eq(stack.ret, function_ret as ReturnHandler) && wrapped {
+                wrapped = false;
+                stack.prev.clone()
+            } else if std::ptr::fn_addr_eq(stack.ret, chain_ret as ReturnHandler) && !wrapped && is_chain_returning_result(&stack.atom) {
+                wrapped = true;
+                stack.prev.clone()
+            } else if std::ptr::fn_addr_eq(stack.ret, collapse_bind_ret as ReturnHandler) && !wrapped && is_metta_collapse_bind(&stack) {
+                None
+            } else {
+                return None;
+            }
+        };
+        match next {
+            Some(next) => frame = Some(next),
+            None => return Some(stack),
+        }
+    }
+    None
+}
+
+/// Returns true for (chain <nested> $x (return $x)) atom.
+fn is_chain_returning_result(chain: &Atom) -> bool {
+    match atom_as_slice(chain) {
+        Some([_op, _nested, Atom::Variable(var), templ]) => match atom_as_slice(templ) {
+            Some([op, Atom::Variable(res)]) => *op == RETURN_SYMBOL && res == var,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Returns true for collapse-bind frame which is created by [metta_impl],
+/// i.e. when its results are passed to check_alternatives.
+fn is_metta_collapse_bind(collapse: &Stack) -> bool {
+    collapse.prev.as_ref().is_some_and(|prev| {
+        match atom_as_slice(&prev.borrow().atom) {
+            Some([op, _nested, _var, templ]) if *op == CHAIN_SYMBOL => match atom_as_slice(templ) {
+                Some([op, call, ..]) if *op == CHAIN_SYMBOL => match atom_as_slice(call) {
+                    Some([op, native]) if *op == FUNCTION_SYMBOL => match atom_as_slice(native) {
+                        Some([op, name, ..]) => *op == CALL_NATIVE_SYMBOL && *name == Atom::sym("check_alternatives"),
+                        _ => false,
+                    },
+                    _ => false,
+                },
+                _ => false,
+            },
+            _ => false,
+        }
+    })
+}
+
 type MettaResult = Box<dyn Iterator<Item=(Atom, Bindings)>>;
 
 #[inline]
@@ -914,8 +1152,8 @@ fn metta_impl(args: Atom, bindings: Bindings) -> MettaResult {
         } else if meta == ATOM_TYPE_GROUNDED {
             type_cast(space, atom, typ, bindings)
         } else {
-            let var = Atom::Variable(VariableAtom::new("x").make_unique());
-            let res = Atom::Variable(VariableAtom::new("res").make_unique());
+            let var = Atom::Variable(gensym("x"));
+            let res = Atom::Variable(gensym("res"));
             once((Atom::expr([CHAIN_SYMBOL, Atom::expr([COLLAPSE_BIND_SYMBOL, call_native!(interpret_expression, Atom::expr([atom, typ, space]))]), var.clone(),
                 Atom::expr([CHAIN_SYMBOL, call_native!(check_alternatives, Atom::expr([var])), res.clone(),
                     return_atom(res)
@@ -1006,6 +1244,22 @@ fn check_alternatives(args: Atom, bindings: Bindings) -> MettaResult {
     }
 }
 
+/// Returns errors which are dropped by [check_alternatives] called on
+/// `args`, i.e. all errors when at least one alternative is successful.
+fn dropped_alternatives(args: &Atom) -> Vec<Atom> {
+    let alternatives = match atom_as_slice(args) {
+        Some([Atom::Expression(expr)]) => expr.children().iter()
+            .filter_map(|pair| atom_as_slice(pair).and_then(<[Atom]>::first)),
+        _ => return vec![],
+    };
+    let (errors, succ): (Vec<&Atom>, Vec<&Atom>) = alternatives.partition(|atom| atom_is_error(atom));
+    if succ.is_empty() {
+        vec![]
+    } else {
+        errors.into_iter().cloned().collect()
+    }
+}
+
 fn interpret_expression(args: Atom, bindings: Bindings) -> MettaResult {
     let (expr, expr_typ, space) = match_atom!{
         args ~ [expr, expr_typ, space]
@@ -1022,8 +1276,8 @@ fn interpret_expression(args: Atom, bindings: Bindings) -> MettaResult {
 
             let has_tuple_type = actual_types.iter().filter(|typ| !is_func(typ)).next().is_some();
             let tuple = if has_tuple_type {
-                let reduced = Atom::Variable(VariableAtom::new("reduced").make_unique());
-                let result = Atom::Variable(VariableAtom::new("result").make_unique());
+                let reduced = Atom::Variable(gensym("reduced"));
+                let result = Atom::Variable(gensym("result"));
                 once((
                     Atom::expr([CHAIN_SYMBOL, call_native!(interpret_tuple, Atom::expr([expr.clone(), space.clone()])), reduced.clone(),
                         Atom::expr([CHAIN_SYMBOL, call_native!(metta_call, Atom::expr([reduced, expr_typ.clone(), space.clone()])), result.clone(),
@@ -1043,8 +1297,8 @@ fn interpret_expression(args: Atom, bindings: Bindings) -> MettaResult {
                     log::debug!("interpret_expression: function type check: expr: {} type: {:?}", expr, res);
                     match res {
                         (Ok(op_type), bindings) => {
-                            let reduced = Atom::Variable(VariableAtom::new("reduced").make_unique());
-                            let result = Atom::Variable(VariableAtom::new("result").make_unique());
+                            let reduced = Atom::Variable(gensym("reduced"));
+                            let result = Atom::Variable(gensym("result"));
                             return once((Atom::expr([CHAIN_SYMBOL, call_native!(interpret_function, Atom::expr([expr.clone(), op_type, expr_typ.clone(), space.clone()])), reduced.clone(),
                                 Atom::expr([CHAIN_SYMBOL, call_native!(metta_call, Atom::expr([reduced, expr_typ, space.clone()])), result.clone(),
                                     return_atom(result)
@@ -1081,9 +1335,9 @@ fn interpret_tuple(args: Atom, bindings: Bindings) -> MettaResult {
         let mut tuple = expr.into_children();
         let head = tuple.remove(0);
         let tail = tuple;
-        let rhead = Atom::Variable(VariableAtom::new("rhead").make_unique());
-        let rtail = Atom::Variable(VariableAtom::new("rtail").make_unique());
-        let result = Atom::Variable(VariableAtom::new("result").make_unique());
+        let rhead = Atom::Variable(gensym("rhead"));
+        let rtail = Atom::Variable(gensym("rtail"));
+        let result = Atom::Variable(gensym("result"));
         once((
             Atom::expr([CHAIN_SYMBOL, Atom::expr([METTA_SYMBOL, head, ATOM_TYPE_UNDEFINED, space.clone()]), rhead.clone(),
                 Atom::expr([EVAL_SYMBOL, Atom::expr([Atom::gnd(IfEqualOp{}), rhead.clone(), EMPTY_SYMBOL, return_atom(EMPTY_SYMBOL),
@@ -1115,10 +1369,10 @@ fn interpret_function(args: Atom, bindings: Bindings) -> MettaResult {
     let mut arg_types = op_type.clone();
     arg_types.children_mut().remove(0);
     let arg_types = Atom::Expression(arg_types);
-    let rop = Atom::Variable(VariableAtom::new("rop").make_unique());
-    let rargs = Atom::Variable(VariableAtom::new("rargs").make_unique());
-    let result = Atom::Variable(VariableAtom::new("result").make_unique());
-    let unpacked_args = Atom::Variable(VariableAtom::new("unpacked_args").make_unique());
+    let rop = Atom::Variable(gensym("rop"));
+    let rargs = Atom::Variable(gensym("rargs"));
+    let result = Atom::Variable(gensym("result"));
+    let unpacked_args = Atom::Variable(gensym("unpacked_args"));
     let call_interpret_args = call_native!(interpret_args, Atom::expr([Atom::Expression(atom), Atom::expr(args), arg_types, ret_type, space.clone()]));
     once((
         Atom::expr([CHAIN_SYMBOL, Atom::expr([METTA_SYMBOL, head, Atom::Expression(op_type), space.clone()]), rop.clone(),
@@ -1250,10 +1504,10 @@ fn interpret_args(args_: Atom, bindings: Bindings) -> MettaResult {
         let mut args = args.into_children();
         let args_head = args.remove(0);
         let args_tail = args;
-        let rhead = Atom::Variable(VariableAtom::new("rhead").make_unique());
-        let rtail = Atom::Variable(VariableAtom::new("rtail").make_unique());
-        let result = Atom::Variable(VariableAtom::new("result").make_unique());
-        let tail = Atom::Variable(VariableAtom::new("tail").make_unique());
+        let rhead = Atom::Variable(gensym("rhead"));
+        let rtail = Atom::Variable(gensym("rtail"));
+        let result = Atom::Variable(gensym("result"));
+        let tail = Atom::Variable(gensym("tail"));
         let call_self = call_native!(interpret_args, Atom::expr([atom, Atom::expr(args_tail), Atom::expr(types_tail), ret_type, space.clone()]));
         let recursion = Atom::expr([CHAIN_SYMBOL, call_self.clone(), rtail.clone(),
             Atom::expr([UNIFY_SYMBOL, Atom::expr([Atom::sym("Ok"), tail.clone()]), rtail.clone(),
@@ -1304,8 +1558,8 @@ fn metta_call(args: Atom, bindings: Bindings) -> MettaResult {
     if atom_is_error(&atom) {
         once((return_atom(atom), bindings))
     } else {
-        let result = Atom::Variable(VariableAtom::new("result").make_unique());
-        let ret = Atom::Variable(VariableAtom::new("ret").make_unique());
+        let result = Atom::Variable(gensym("result"));
+        let ret = Atom::Variable(gensym("ret"));
         once((
             // TODO: At the moment metta_call() is called we already know
             // should we call atom as a tuple or as a function.
@@ -1340,7 +1594,7 @@ fn metta_call_return(args: Atom, bindings: Bindings) -> MettaResult {
     } else if atom_is_error(&result) {
         once((return_atom(result), bindings))
     } else {
-        let ret = Atom::Variable(VariableAtom::new("ret").make_unique());
+        let ret = Atom::Variable(gensym("ret"));
         once((
             Atom::expr([CHAIN_SYMBOL, Atom::expr([METTA_SYMBOL, result, typ, space]), ret.clone(),
                 return_atom(ret)
@@ -1352,6 +1606,8 @@ fn metta_call_return(args: Atom, bindings: Bindings) -> MettaResult {
 mod tests {
     use super::*;
     use crate::common::test_utils::{metta_atom, metta_space};
+    use crate::metta::runner::arithmetics::*;
+    use crate::metta::runner::stdlib::EqualOp;
 
     #[test]
     fn interpret_atom_evaluate_incorrect_args() {
@@ -1393,6 +1649,28 @@ mod tests {
         assert_eq!(result, vec![metta_atom("A")]);
     }
 
+    #[test]
+    fn interpret_atom_evaluate_user_variable_does_not_clash_with_internal() {
+        let space = space("(= (foo $X) ($X B))");
+        let result = call_interpret(&space, &metta_atom("(eval (foo $X))"));
+        assert_eq!(result, vec![metta_atom("($X B)")]);
+
+        let result = call_interpret(&space, &metta_atom("(eval (foo A))"));
+        assert_eq!(result, vec![metta_atom("(A B)")]);
+    }
+
+    #[test]
+    fn interpret_atom_evaluate_internal_variables_have_reserved_prefix() {
+        let space = space("(= (foo $X) ($X B))");
+        let result = call_interpret(&space, &metta_atom("(function (chain (eval (foo $X)) $r (return $r)))"));
+        let vars: Vec<&VariableAtom> = result.iter().flat_map(|a| a.iter().filter_type::<&VariableAtom>()).collect();
+        assert!(vars.iter().all(|v| !crate::common::gensym::is_internal_variable(v)), "{:?}", vars);
+
+        let var = gensym("X");
+        assert!(var.name().starts_with(crate::common::gensym::INTERNAL_VARIABLE_PREFIX));
+        assert_ne!(var, VariableAtom::new("X"));
+    }
+
     #[test]
     fn interpret_atom_evaluate_pure_expression_non_determinism() {
         let space = space("
@@ -1414,6 +1692,101 @@ mod tests {
         assert_eq!(result, vec![metta_atom("NotReducible")]);
     }
 
+    #[test]
+    fn interpret_atom_evaluate_data_constructor() {
+        let mut space = space("
+            (= (Point $x $y) (Vector $x $y))
+            (= (Vector $x $y) ($x $y))
+        ");
+        space.add_data_constructor(SymbolAtom::new("Point".into()));
+
+        let result = call_interpret(&space, &metta_atom("(eval (Point 1 2))"));
+        assert_eq!(result, vec![metta_atom("NotReducible")]);
+        let result = call_interpret(&space, &metta_atom("(eval (Vector 1 2))"));
+        assert_eq!(result, vec![metta_atom("(1 2)")]);
+    }
+
+    #[test]
+    fn interpret_partial_returns_results_and_errors() {
+        let space = space("
+            (= (f) A)
+            (= (f) B)
+            (= (f) (Error (f) NotImplemented))
+        ");
+
+        let (results, errors) = interpret_partial(&space, &metta_atom("(eval (f))"));
+
+        assert_eq_no_order!(results, vec![metta_atom("A"), metta_atom("B")]);
+        assert_eq!(errors, vec!["NotImplemented".to_string()]);
+    }
+
+    #[test]
+    fn interpret_partial_returns_errors_dropped_by_metta() {
+        let space = DynSpace::new(space("
+            (= (f) A)
+            (= (f) (Error (f) NotImplemented))
+        "));
+        let atom = Atom::expr([METTA_SYMBOL, expr!(("f")), ATOM_TYPE_UNDEFINED, Atom::gnd(space.clone())]);
+
+        assert_eq!(interpret(&space, &atom), Ok(vec![metta_atom("A")]));
+        let (results, errors) = interpret_partial(&space, &atom);
+
+        assert_eq!(results, vec![metta_atom("A")]);
+        assert_eq!(errors, vec!["NotImplemented".to_string()]);
+    }
+
+    #[test]
+    fn interpret_with_limit_stops_after_limit() {
+        let space = space("(= (f) A)");
+        let atom = metta_atom("(chain (eval (f)) $x ($x $x))");
+
+        assert_eq!(interpret_with_limit(&space, &atom, 100), Ok(vec![metta_atom("(A A)")]));
+        assert_eq!(interpret_with_limit(&space, &atom, 1),
+            Err("Step limit 1 is exceeded while interpreting (chain (eval (f)) $x ($x $x))".into()));
+    }
+
+    #[test]
+    fn interpret_with_resolver_executes_resolved_operation() {
+        let calls = std::cell::Cell::new(0);
+        let resolver = |name: &str| -> Option<Box<dyn GroundedAtom>> {
+            calls.set(calls.get() + 1);
+            match (name, Atom::gnd(SumOp{})) {
+                ("+", Atom::Grounded(op)) => Some(op),
+                _ => None,
+            }
+        };
+        let space = space("(= (foo) (bar))");
+
+        let result = interpret_with_resolver(&space,
+            &expr!("chain" ("eval" ("+" {Number::Integer(1)} {Number::Integer(2)})) x
+                ("eval" ("+" x {Number::Integer(3)}))),
+            &resolver);
+        assert_eq!(result, Ok(vec![expr!({Number::Integer(6)})]));
+        assert_eq!(calls.get(), 1);
+
+        let result = interpret_with_resolver(&space, &expr!("eval" ("foo")), &resolver);
+        assert_eq!(result, Ok(vec![expr!(("bar"))]));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn interpret_with_resolver_skips_data_constructors() {
+        let calls = std::cell::Cell::new(0);
+        let resolver = |_name: &str| -> Option<Box<dyn GroundedAtom>> {
+            calls.set(calls.get() + 1);
+            match Atom::gnd(SumOp{}) {
+                Atom::Grounded(op) => Some(op),
+                _ => None,
+            }
+        };
+        let mut space = GroundingSpace::new();
+        space.add_data_constructor(SymbolAtom::new("Point".into()));
+
+        let result = interpret_with_resolver(&space, &expr!("eval" ("Point" {Number::Integer(1)} {Number::Integer(2)})), &resolver);
+        assert_eq!(result, Ok(vec![NOT_REDUCIBLE_SYMBOL]));
+        assert_eq!(calls.get(), 0);
+    }
+
     #[test]
     fn interpret_atom_evaluate_pure_expression_variable_in_space() {
         let space = space("$t (= (foo $a B) $a)");
@@ -1858,6 +2231,32 @@ mod tests {
         }
     }
 
+    fn interpret_max_stack_len<T: Space>(space: T, atom: &Atom) -> (Vec<Atom>, usize) {
+        let mut state = interpret_init(space, atom);
+        let mut max_len = 0;
+        while state.has_next() {
+            max_len = state.plan.iter().map(|atom| atom.0.len()).fold(max_len, usize::max);
+            state = interpret_step(state);
+        }
+        (state.into_result().unwrap(), max_len)
+    }
+
+    #[test]
+    fn interpret_tail_recursion_bounded_stack() {
+        let space = DynSpace::new(GroundingSpace::from_vec(vec![
+            expr!("=" ("count" n) ("count-next" ({EqualOp{}} n {Number::Integer(0)}) n)),
+            expr!("=" ("count-next" {Bool(true)} n) "done"),
+            expr!("=" ("count-next" {Bool(false)} n) ("count" ({SubOp{}} n {Number::Integer(1)}))),
+        ]));
+        let count = |n| Atom::expr([METTA_SYMBOL, expr!("count" {Number::Integer(n)}), ATOM_TYPE_UNDEFINED, Atom::gnd(space.clone())]);
+
+        let (result, short_len) = interpret_max_stack_len(&space, &count(10));
+        assert_eq!(result, vec![expr!("done")]);
+        let (result, long_len) = interpret_max_stack_len(&space, &count(200));
+        assert_eq!(result, vec![expr!("done")]);
+        assert_eq!(short_len, long_len);
+    }
+
     #[test]
     fn interpret_duplicated_types() {
         let space = DynSpace::new(space("
@@ -10,6 +10,7 @@ pub mod interpreter_minimal;
 #[cfg(not(feature = "old_interpreter"))]
 pub use interpreter_minimal as interpreter;
 pub mod types;
+pub mod normal_form;
 pub mod runner;
 
 use crate::*;
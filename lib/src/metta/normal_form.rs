@@ -0,0 +1,547 @@
+//! Evaluation strategy which reduces an atom to its normal form.
+//!
+//! Unlike [interpreter](super::interpreter) which collects results of the
+//! evaluation, this strategy rewrites subexpressions of the atom using
+//! `(= <lhs> <rhs>)` rules from the space and grounded functions until no
+//! rewrite can be applied. An atom which cannot be rewritten further is a
+//! normal form. All rewriting orders are explored, thus when rules are not
+//! confluent all distinct normal forms are returned.
+
+use crate::*;
+use crate::space::Space;
+use crate::metta::*;
+use crate::atom::matcher::atoms_are_equivalent;
+use crate::common::gensym::gensym;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+
+/// Default maximal number of rewrites which are produced by [interpret_normal_form]
+/// before the reduction is stopped.
+pub const DEFAULT_STEP_LIMIT: usize = 10000;
+
+/// Maximal depth of the atoms produced by [interpret_normal_form_with_limit]
+/// and maximal nesting of the arguments reduced by [interpret_explained_with_limit].
+pub const DEFAULT_DEPTH_LIMIT: usize = 256;
+
+/// Result of the reduction of the atom to the normal form.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NormalForm {
+    /// All rewriting orders lead to the same normal form (up to variable renaming).
+    Unique(Atom),
+    /// Different rewriting orders lead to the different normal forms.
+    NotConfluent(Vec<Atom>),
+}
+
+/// Reduces `atom` to its normal form using [DEFAULT_STEP_LIMIT].
+/// See [interpret_normal_form_with_limit].
+pub fn interpret_normal_form<T: Space>(space: T, atom: &Atom) -> Result<NormalForm, String> {
+    interpret_normal_form_with_limit(space, atom, DEFAULT_STEP_LIMIT)
+}
+
+/// Reduces `atom` to its normal form. On each step any subexpression of the
+/// atom can be rewritten: a grounded function call is replaced by its
+/// results, any other atom is replaced by the right hand side of a matching
+/// `(= <lhs> <rhs>)` rule. Grounded function call which returns an error or
+/// grounded function which is not [pure](CustomExecute::is_pure) is not
+/// rewritten. Each distinct atom is rewritten once, so cycles of rules do
+/// not lead to an infinite loop. Returns an error when more than `step_limit`
+/// rewrites are produced, when a rewrite is deeper than [DEFAULT_DEPTH_LIMIT]
+/// or when there are no normal forms.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::space::grounding::GroundingSpace;
+/// use hyperon::metta::normal_form::{interpret_normal_form, NormalForm};
+///
+/// let space = GroundingSpace::from_vec(vec![
+///     expr!("=" ("not" "True") "False"),
+///     expr!("=" ("not" "False") "True"),
+/// ]);
+///
+/// let result = interpret_normal_form(&space, &expr!("not" ("not" "True")));
+///
+/// assert_eq!(result, Ok(NormalForm::Unique(expr!("True"))));
+/// ```
+pub fn interpret_normal_form_with_limit<T: Space>(space: T, atom: &Atom, step_limit: usize) -> Result<NormalForm, String> {
+    let mut queue = VecDeque::from([atom.clone()]);
+    let mut visited = HashSet::from([AtomKey(atom.clone())]);
+    let mut rewrites = HashMap::new();
+    let mut forms: Vec<Atom> = Vec::new();
+    let mut steps = 0;
+
+    while let Some(next) = queue.pop_front() {
+        let rewritten = rewrite_any(&space, &next, &mut rewrites);
+        log::debug!("interpret_normal_form: atom: {}, rewritten: {:?}", next, rewritten);
+        if rewritten.is_empty() {
+            if !forms.iter().any(|form| atoms_are_equivalent(form, &next)) {
+                forms.push(next);
+            }
+        } else {
+            for rewrite in rewritten {
+                if steps == step_limit {
+                    return Err(format!("Step limit {} is exceeded while reducing {}", step_limit, atom));
+                }
+                steps += 1;
+                if atom_depth(&rewrite) > DEFAULT_DEPTH_LIMIT {
+                    return Err(format!("Depth limit {} is exceeded while reducing {}", DEFAULT_DEPTH_LIMIT, atom));
+                }
+                if visited.insert(AtomKey(rewrite.clone())) {
+                    queue.push_back(rewrite);
+                }
+            }
+        }
+    }
+
+    match forms.len() {
+        0 => Err(format!("Atom {} has no normal form", atom)),
+        1 => Ok(NormalForm::Unique(forms.pop().unwrap())),
+        _ => Ok(NormalForm::NotConfluent(forms)),
+    }
+}
+
+/// Wrapper which allows using atoms as keys of [HashSet] and [HashMap].
+/// Grounded atoms are compared using [Atom::eq] only, thus all of them have
+/// the same hash.
+struct AtomKey(Atom);
+
+impl PartialEq for AtomKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for AtomKey {}
+
+impl Hash for AtomKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        fn hash_atom<H: Hasher>(atom: &Atom, state: &mut H) {
+            match atom {
+                Atom::Symbol(sym) => { 0.hash(state); sym.hash(state); },
+                Atom::Variable(var) => { 1.hash(state); var.hash(state); },
+                Atom::Grounded(_) => 2.hash(state),
+                Atom::Expression(expr) => {
+                    3.hash(state);
+                    expr.children().len().hash(state);
+                    expr.children().iter().for_each(|child| hash_atom(child, state));
+                },
+            }
+        }
+        hash_atom(&self.0, state)
+    }
+}
+
+fn atom_depth(atom: &Atom) -> usize {
+    match atom {
+        Atom::Expression(expr) => 1 + expr.children().iter().map(atom_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Returns all atoms which can be obtained by rewriting any single
+/// subexpression of the atom. Rewrites of the subexpressions are cached in
+/// `rewrites` because the same subexpressions are met in many atoms.
+fn rewrite_any<T: Space>(space: &T, atom: &Atom, rewrites: &mut HashMap<AtomKey, Vec<Atom>>) -> Vec<Atom> {
+    let mut result = rewrites.entry(AtomKey(atom.clone()))
+        .or_insert_with(|| rewrite_root(space, atom))
+        .clone();
+    if let Atom::Expression(expr) = atom {
+        for (i, child) in expr.children().iter().enumerate() {
+            for rewritten in rewrite_any(space, child, rewrites) {
+                let mut children = expr.children().clone();
+                children[i] = rewritten;
+                result.push(Atom::expr(children));
+            }
+        }
+    }
+    result
+}
+
+/// Returns all atoms which can be obtained by rewriting the atom itself.
+pub(crate) fn rewrite_root<T: Space>(space: &T, atom: &Atom) -> Vec<Atom> {
+    match atom {
+        Atom::Variable(_) => return vec![],
+        Atom::Expression(expr) => {
+            if let Some((Atom::Grounded(op), args)) = expr.children().split_first() {
+                if let Some(executable) = op.as_grounded().as_execute() {
+                    if !executable.is_pure() {
+                        return vec![];
+                    }
+                    return executable.execute(args).unwrap_or_default();
+                }
+            }
+        },
+        _ => {},
+    }
+    let var_x = gensym("X");
+    let query = Atom::expr([EQUAL_SYMBOL, atom.clone(), Atom::Variable(var_x.clone())]);
+    space.query(&query).into_iter()
+        .filter_map(|bindings| bindings.resolve(&var_x))
+        .collect()
+}
+
+/// Atom obtained by [interpret_explained] and the instances of the
+/// `(= <lhs> <rhs>)` rules which were applied to obtain it in order of
+/// application.
+pub type Explanation = (Atom, Vec<Atom>);
+
+/// Reduces `atom` using [DEFAULT_STEP_LIMIT] and explains each result.
+/// See [interpret_explained_with_limit].
+pub fn interpret_explained<T: Space>(space: T, atom: &Atom) -> Result<Vec<Explanation>, String> {
+    interpret_explained_with_limit(space, atom, DEFAULT_STEP_LIMIT)
+}
+
+/// Reduces `atom` and returns each result together with the list of rules
+/// applied to obtain it. Unlike [interpret_normal_form_with_limit] the
+/// reduction order follows the interpreter: arguments of the expression are
+/// reduced first and then the expression itself is rewritten either by
+/// calling grounded function or by applying matching `(= <lhs> <rhs>)` rules.
+/// Arguments which have `Atom` type or the meta type of the argument
+/// according to the function type of the operation are not reduced, thus
+/// branches of `if` are reduced only when selected. Each matching rule leads
+/// to a separate result. Rules are returned instantiated by the bindings of
+/// the match. Grounded function call which returns an error is not
+/// rewritten. Returns an error when more than `step_limit` rewrites are
+/// performed or when nesting of the reduced arguments exceeds
+/// [DEFAULT_DEPTH_LIMIT].
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::space::grounding::GroundingSpace;
+/// use hyperon::metta::normal_form::interpret_explained;
+///
+/// let space = GroundingSpace::from_vec(vec![
+///     expr!("=" ("frog" x) ("green" x)),
+///     expr!("=" ("green" "Fritz") "True"),
+/// ]);
+///
+/// let result = interpret_explained(&space, &expr!("frog" "Fritz"));
+///
+/// assert_eq!(result, Ok(vec![(expr!("True"), vec![
+///     expr!("=" ("frog" "Fritz") ("green" "Fritz")),
+///     expr!("=" ("green" "Fritz") "True"),
+/// ])]));
+/// ```
+pub fn interpret_explained_with_limit<T: Space>(space: T, atom: &Atom, step_limit: usize) -> Result<Vec<Explanation>, String> {
+    let mut steps = 0;
+    explain(&space, atom, &mut steps, step_limit, 0)
+        .map_err(|limit| match limit {
+            ExplainLimit::Steps => format!("Step limit {} is exceeded while explaining {}", step_limit, atom),
+            ExplainLimit::Depth => format!("Depth limit {} is exceeded while explaining {}", DEFAULT_DEPTH_LIMIT, atom),
+        })
+}
+
+enum ExplainLimit {
+    Steps,
+    Depth,
+}
+
+fn explain<T: Space>(space: &T, atom: &Atom, steps: &mut usize, step_limit: usize, depth: usize) -> Result<Vec<Explanation>, ExplainLimit> {
+    if depth == DEFAULT_DEPTH_LIMIT {
+        return Err(ExplainLimit::Depth);
+    }
+    let mut result = Vec::new();
+    let mut queue = VecDeque::from([(atom.clone(), Vec::new())]);
+    while let Some((atom, rules)) = queue.pop_front() {
+        for (atom, args_rules) in explain_args(space, &atom, steps, step_limit, depth)? {
+            let mut rules = rules.clone();
+            rules.extend(args_rules);
+            match explain_root(space, &atom) {
+                None => result.push((atom, rules)),
+                Some(rewritten) => {
+                    if *steps == step_limit {
+                        return Err(ExplainLimit::Steps);
+                    }
+                    *steps += 1;
+                    for (next, rule) in rewritten {
+                        let mut rules = rules.clone();
+                        rules.extend(rule);
+                        queue.push_back((next, rules));
+                    }
+                },
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Reduces arguments of the expression which are evaluated by the
+/// interpreter before the expression itself.
+fn explain_args<T: Space>(space: &T, atom: &Atom, steps: &mut usize, step_limit: usize, depth: usize) -> Result<Vec<Explanation>, ExplainLimit> {
+    let expr = match atom {
+        Atom::Expression(expr) => expr,
+        _ => return Ok(vec![(atom.clone(), Vec::new())]),
+    };
+    let lazy = lazy_args(space, expr);
+    let mut prefixes: Vec<(Vec<Atom>, Vec<Atom>)> = vec![(Vec::new(), Vec::new())];
+    for (i, child) in expr.children().iter().enumerate() {
+        let explained = if lazy[i] {
+            vec![(child.clone(), Vec::new())]
+        } else {
+            explain(space, child, steps, step_limit, depth + 1)?
+        };
+        prefixes = prefixes.into_iter()
+            .flat_map(|(children, rules)| explained.iter().map(move |(child, child_rules)| {
+                let mut children = children.clone();
+                children.push(child.clone());
+                let mut rules = rules.clone();
+                rules.extend(child_rules.iter().cloned());
+                (children, rules)
+            }))
+            .collect();
+    }
+    Ok(prefixes.into_iter()
+        .map(|(children, rules)| (Atom::expr(children), rules))
+        .collect())
+}
+
+/// Returns which children of the expression are not reduced before the
+/// expression itself. Head and arguments of the expression without function
+/// type are reduced, arguments of the function are not reduced when their
+/// type is `Atom` or the meta type of the argument.
+fn lazy_args<T: Space>(space: &T, expr: &ExpressionAtom) -> Vec<bool> {
+    let children = expr.children();
+    let mut lazy = vec![false; children.len()];
+    let op_type = children.first().and_then(|op| types::get_atom_types(space, op).into_iter()
+        .find(|typ| types::is_func(typ) && atom_as_children(typ).len() == children.len() + 1));
+    if let Some(op_type) = op_type {
+        lazy[0] = true;
+        let arg_types = atom_as_children(&op_type).iter().skip(1);
+        for ((lazy, arg), typ) in lazy.iter_mut().zip(children).skip(1).zip(arg_types) {
+            *lazy = *typ == ATOM_TYPE_ATOM || *typ == types::get_meta_type(arg) || matches!(arg, Atom::Variable(_));
+        }
+    }
+    lazy
+}
+
+fn atom_as_children(atom: &Atom) -> &[Atom] {
+    match atom {
+        Atom::Expression(expr) => expr.children().as_slice(),
+        _ => &[],
+    }
+}
+
+/// Returns atoms which can be obtained by rewriting the atom itself together
+/// with the instances of the applied rules or `None` when atom cannot be
+/// rewritten.
+fn explain_root<T: Space>(space: &T, atom: &Atom) -> Option<Vec<(Atom, Option<Atom>)>> {
+    match atom {
+        Atom::Variable(_) => return None,
+        Atom::Expression(expr) => {
+            if let Some((Atom::Grounded(op), args)) = expr.children().split_first() {
+                if let Some(executable) = op.as_grounded().as_execute() {
+                    return executable.execute(args).ok()
+                        .map(|results| results.into_iter().map(|result| (result, None)).collect());
+                }
+            }
+        },
+        _ => {},
+    }
+    let var_x = gensym("X");
+    let query = Atom::expr([EQUAL_SYMBOL, atom.clone(), Atom::Variable(var_x.clone())]);
+    let rewritten: Vec<(Atom, Option<Atom>)> = space.query(&query).into_iter()
+        .filter_map(|bindings| {
+            let rhs = bindings.resolve(&var_x)?;
+            Some((rhs, Some(matcher::apply_bindings_to_atom_move(query.clone(), &bindings))))
+        })
+        .collect();
+    if rewritten.is_empty() {
+        None
+    } else {
+        Some(rewritten)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::space::grounding::GroundingSpace;
+    use crate::metta::runner::arithmetics::*;
+    use crate::metta::runner::stdlib::EqualOp;
+
+    #[test]
+    fn normal_form_of_arithmetic_expression() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" ("double" x) ({MulOp{}} x {Number::Integer(2)})),
+        ]);
+        let atom = expr!({SumOp{}} {Number::Integer(1)} ("double" ({SubOp{}} {Number::Integer(5)} {Number::Integer(2)})));
+
+        let result = interpret_normal_form(&space, &atom);
+
+        assert_eq!(result, Ok(NormalForm::Unique(expr!({Number::Integer(7)}))));
+    }
+
+    #[test]
+    fn normal_form_not_confluent() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" ("f" x) ("g" x)),
+            expr!("=" ("f" "A") "B"),
+        ]);
+
+        let result = interpret_normal_form(&space, &expr!("f" "A"));
+
+        match result {
+            Ok(NormalForm::NotConfluent(forms)) => assert_eq_no_order!(forms, vec![expr!("g" "A"), expr!("B")]),
+            _ => panic!("Two normal forms are expected, found: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn normal_form_of_normal_atom() {
+        let space = GroundingSpace::new();
+        assert_eq!(interpret_normal_form(&space, &expr!("a" x)), Ok(NormalForm::Unique(expr!("a" x))));
+    }
+
+    #[test]
+    fn normal_form_step_limit() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" ("inc" x) ("inc" ("s" x))),
+        ]);
+
+        let result = interpret_normal_form_with_limit(&space, &expr!("inc" "Z"), 10);
+
+        assert_eq!(result, Err("Step limit 10 is exceeded while reducing (inc Z)".into()));
+    }
+
+    #[test]
+    fn normal_form_of_unbounded_recursion() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" ("if" "True" then else) then),
+            expr!("=" ("if" "False" then else) else),
+            expr!("=" ("fact" n) ("if" ({EqualOp{}} n {Number::Integer(0)}) {Number::Integer(1)}
+                ({MulOp{}} n ("fact" ({SubOp{}} n {Number::Integer(1)}))))),
+        ]);
+
+        let result = interpret_normal_form(&space, &expr!("fact" {Number::Integer(3)}));
+
+        assert_eq!(result, Err("Step limit 10000 is exceeded while reducing (fact 3)".into()));
+    }
+
+    #[test]
+    fn normal_form_depth_limit() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" ("deep" x) ("s" ("deep" x))),
+        ]);
+
+        let result = interpret_normal_form(&space, &expr!("deep" "Z"));
+
+        assert_eq!(result, Err(format!("Depth limit {} is exceeded while reducing (deep Z)", DEFAULT_DEPTH_LIMIT)));
+    }
+
+    #[derive(PartialEq, Clone, Debug)]
+    struct Impure();
+
+    impl Grounded for Impure {
+        fn type_(&self) -> Atom {
+            expr!("->" "Atom")
+        }
+        fn as_execute(&self) -> Option<&dyn CustomExecute> {
+            Some(self)
+        }
+    }
+
+    impl CustomExecute for Impure {
+        fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+            Ok(vec![expr!("executed")])
+        }
+    }
+
+    impl std::fmt::Display for Impure {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "impure")
+        }
+    }
+
+    #[test]
+    fn normal_form_does_not_execute_impure_functions() {
+        let space = GroundingSpace::new();
+
+        let result = interpret_normal_form(&space, &expr!(({Impure()})));
+
+        assert_eq!(result, Ok(NormalForm::Unique(expr!(({Impure()})))));
+    }
+
+    #[test]
+    fn explained_arithmetic_expression() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" ("double" x) ({MulOp{}} x {Number::Integer(2)})),
+        ]);
+        let atom = expr!({SumOp{}} {Number::Integer(1)} ("double" ({SubOp{}} {Number::Integer(5)} {Number::Integer(2)})));
+
+        let result = interpret_explained(&space, &atom);
+
+        assert_eq!(result, Ok(vec![(expr!({Number::Integer(7)}), vec![
+            expr!("=" ("double" {Number::Integer(3)}) ({MulOp{}} {Number::Integer(3)} {Number::Integer(2)})),
+        ])]));
+    }
+
+    #[test]
+    fn explained_nondeterministic_rules() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" ("f" x) ("g" x)),
+            expr!("=" ("f" "A") "B"),
+        ]);
+
+        let result = interpret_explained(&space, &expr!("f" "A")).unwrap();
+
+        assert_eq_no_order!(result, vec![
+            (expr!("g" "A"), vec![expr!("=" ("f" "A") ("g" "A"))]),
+            (expr!("B"), vec![expr!("=" ("f" "A") "B")]),
+        ]);
+    }
+
+    #[test]
+    fn explained_step_limit() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" ("inc" x) ("inc" ("s" x))),
+        ]);
+
+        let result = interpret_explained_with_limit(&space, &expr!("inc" "Z"), 10);
+
+        assert_eq!(result, Err("Step limit 10 is exceeded while explaining (inc Z)".into()));
+    }
+
+    #[test]
+    fn explained_if_based_recursion() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!(":" "if" ("->" "Bool" "Atom" "Atom" t)),
+            expr!("=" ("if" "True" then else) then),
+            expr!("=" ("if" "False" then else) else),
+            expr!("=" ("is-zero" "Z") "True"),
+            expr!("=" ("is-zero" ("S" n)) "False"),
+            expr!("=" ("pred" ("S" n)) n),
+            expr!("=" ("count" n) ("if" ("is-zero" n) "Z" ("S" ("count" ("pred" n))))),
+        ]);
+
+        let result = interpret_explained(&space, &expr!("count" ("S" "Z"))).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0, expr!("S" "Z"));
+        assert_eq!(result[0].1.len(), 7);
+    }
+
+    #[test]
+    fn explained_depth_limit() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" ("deep" x) ("s" ("deep" x))),
+        ]);
+
+        let result = interpret_explained(&space, &expr!("deep" "Z"));
+
+        assert_eq!(result, Err(format!("Depth limit {} is exceeded while explaining (deep Z)", DEFAULT_DEPTH_LIMIT)));
+    }
+
+    #[test]
+    fn normal_form_cycle() {
+        let space = GroundingSpace::from_vec(vec![
+            expr!("=" "a" "b"),
+            expr!("=" "b" "a"),
+        ]);
+
+        assert_eq!(interpret_normal_form(&space, &expr!("a")), Err("Atom a has no normal form".into()));
+    }
+}
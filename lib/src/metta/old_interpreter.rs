@@ -70,6 +70,7 @@ use crate::atom::subexpr::*;
 use crate::atom::matcher::*;
 use crate::space::*;
 use crate::common::collections::ListMap;
+use crate::common::gensym::gensym;
 use crate::metta::*;
 use crate::metta::types::{is_func, get_arg_types, get_type_bindings,
     get_atom_types, match_reducted_types};
@@ -164,6 +165,30 @@ type Results = Vec<InterpretedAtom>;
 type InterpreterError = (Atom, Atom);
 type NoInputPlan<'a> = Box<dyn Plan<'a, (), Results, InterpreterError> + 'a>;
 
+/// Reports violation of the interpreter invariant. When `plan_validation`
+/// feature is enabled the violation is returned as an interpreter error
+/// which contains the offending atom and the description of the invariant.
+/// Otherwise interpreter panics.
+#[cfg(feature = "plan_validation")]
+macro_rules! invariant_violation {
+    ($atom:expr, $($arg:tt)+) => {{
+        let message = format!("Interpreter invariant is violated: {}", format!($($arg)+));
+        StepResult::err(($atom, Atom::sym(message)))
+    }}
+}
+
+#[cfg(not(feature = "plan_validation"))]
+macro_rules! invariant_violation {
+    ($atom:expr, $($arg:tt)+) => {
+        invariant_panic(format!($($arg)+))
+    }
+}
+
+#[cfg(not(feature = "plan_validation"))]
+fn invariant_panic<'a>(message: String) -> StepResult<'a, Results, InterpreterError> {
+    panic!("{}", message)
+}
+
 /// Initialize interpreter and returns the result of the zero step.
 /// It can be error, immediate result or interpretation plan to be executed.
 /// See [crate::metta::interpreter] for algorithm explanation.
@@ -172,16 +197,30 @@ type NoInputPlan<'a> = Box<dyn Plan<'a, (), Results, InterpreterError> + 'a>;
 /// * `space` - atomspace to query for interpretation
 /// * `expr` - atom to interpret
 pub fn interpret_init<'a, T: Space + 'a>(space: T, expr: &Atom) -> InterpreterState<'a, T> {
-    let step_result = interpret_init_internal(space, expr);
+    interpret_init_with_order(space, expr, ResultOrder::default())
+}
+
+/// Initialize interpreter the same way as [interpret_init] does but use
+/// `order` to merge results of the alternative interpretations. Alternatives
+/// are the results of the matched rules and grounded operations which are
+/// interpreted further and the results of the reducted arguments. See
+/// [ResultOrder].
+///
+/// # Arguments
+/// * `space` - atomspace to query for interpretation
+/// * `expr` - atom to interpret
+/// * `order` - order in which results of the alternatives are merged
+pub fn interpret_init_with_order<'a, T: Space + 'a>(space: T, expr: &Atom, order: ResultOrder) -> InterpreterState<'a, T> {
+    let step_result = interpret_init_internal(space, expr, order);
     InterpreterState { step_result: step_result, phantom: <_>::default() }
 }
 
-fn interpret_init_internal<'a, T: Space + 'a>(space: T, expr: &Atom) -> StepResult<'a, Results, InterpreterError> {
+fn interpret_init_internal<'a, T: Space + 'a>(space: T, expr: &Atom, order: ResultOrder) -> StepResult<'a, Results, InterpreterError> {
     let expr = match <&[Atom]>::try_from(expr).ok() {
         Some([op, atom, _typ, _space]) if *op == METTA_SYMBOL => atom,
         _ => expr,
     };
-    let context = InterpreterContextRef::new(space);
+    let context = InterpreterContextRef::with_order(space, order);
     interpret_as_type_plan(context,
         InterpretedAtom(expr.clone(), Bindings::new()),
         ATOM_TYPE_UNDEFINED)
@@ -279,16 +318,17 @@ impl<'a, T: Space + 'a> SpaceRef<'a> for T {}
 struct InterpreterContext<'a, T: SpaceRef<'a>> {
     space: T,
     cache: SpaceObserverRef<InterpreterCache>,
+    order: ResultOrder,
     phantom: PhantomData<&'a T>,
 }
 
 struct InterpreterContextRef<'a, T: SpaceRef<'a>>(Rc<InterpreterContext<'a, T>>);
 
 impl<'a, T: SpaceRef<'a>> InterpreterContextRef<'a, T> {
-    fn new(space: T) -> Self {
+    fn with_order(space: T, order: ResultOrder) -> Self {
         let cache = space.common().register_observer(InterpreterCache::new());
 
-        Self(Rc::new(InterpreterContext{ space, cache, phantom: PhantomData }))
+        Self(Rc::new(InterpreterContext{ space, cache, order, phantom: PhantomData }))
     }
 }
 
@@ -314,6 +354,10 @@ fn is_grounded_op(expr: &ExpressionAtom) -> bool {
     }
 }
 
+fn is_data_constructor_expr<T: Space>(space: &T, expr: &ExpressionAtom) -> bool {
+    expr.children().first().is_some_and(|head| space.is_data_constructor(head))
+}
+
 fn is_variable_op(expr: &ExpressionAtom) -> bool {
     match expr.children().get(0) {
         Some(Atom::Variable(_)) => true,
@@ -325,11 +369,7 @@ fn is_variable_op(expr: &ExpressionAtom) -> bool {
 fn has_grounded_sub_expr(expr: &ExpressionAtom) -> bool {
     return is_grounded_op(expr) ||
         SubexprStream::from_expr(Atom::Expression(expr.clone()), TOP_DOWN_DEPTH_WALK)
-            .any(|sub| if let Atom::Expression(sub) = sub {
-                    is_grounded_op(&sub)
-                } else {
-                    panic!("Expression is expected");
-                });
+            .any(|sub| matches!(sub, Atom::Expression(sub) if is_grounded_op(&sub)));
 }
 
 fn interpret_as_type_plan<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>,
@@ -398,44 +438,37 @@ fn interpret_expression_as_type_plan<'a, T: SpaceRef<'a>>(context: InterpreterCo
         input: InterpretedAtom, typ: Atom) -> OperatorPlan<'a, Vec<Atom>, Results, InterpreterError> {
     let descr = format!("form alternative plans for expression {} using types", input);
     OperatorPlan::new(move |op_types: Vec<Atom>| {
-        make_alternives_plan(input.0.clone(), op_types, move |op_typ| {
+        make_alternives_plan(input.0.clone(), op_types, context.order, move |op_typ| {
             interpret_expression_as_type_op(context.clone(),
                 input.clone(), op_typ, typ.clone())
         })
     }, descr)
 }
 
-fn get_expr(atom: &Atom) -> &ExpressionAtom {
-    match atom {
-        Atom::Expression(expr) => expr,
-        _ => panic!("Atom::Expression is expected, recieved: {}", atom),
-    }
-}
-
-fn get_expr_mut(atom: &mut Atom) -> &mut ExpressionAtom {
+fn get_expr(atom: &Atom) -> Option<&ExpressionAtom> {
     match atom {
-        Atom::Expression(expr) => expr,
-        _ => panic!("Atom::Expression is expected, recieved: {}", atom),
+        Atom::Expression(expr) => Some(expr),
+        _ => None,
     }
 }
 
 fn interpret_expression_as_type_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>,
-        input: InterpretedAtom, op_typ: Atom, ret_typ: Atom) -> NoInputPlan<'a> {
+        mut input: InterpretedAtom, op_typ: Atom, ret_typ: Atom) -> NoInputPlan<'a> {
     log::debug!("interpret_expression_as_type_op: input: {}, operation type: {}, expected return type: {}", input, op_typ, ret_typ);
     if ret_typ == ATOM_TYPE_ATOM || ret_typ == ATOM_TYPE_EXPRESSION {
-        Box::new(StepResult::ret(vec![input]))
-    } else if is_func(&op_typ) {
-        let InterpretedAtom(input_atom, mut input_bindings) = input;
-        let expr = get_expr(&input_atom);
+        return Box::new(StepResult::ret(vec![input]));
+    }
+    let Some(expr) = get_expr(&input.0) else {
+        return Box::new(invariant_violation!(input.0, "Atom::Expression is expected, received: {}", input.0));
+    };
+    if is_func(&op_typ) {
         let (op_arg_types, op_ret_typ) = get_arg_types(&op_typ);
         // TODO: supertypes should be checked as well
-        if !match_reducted_types(op_ret_typ, &ret_typ, &mut input_bindings) {
-            Box::new(StepResult::err((input_atom, BAD_TYPE_SYMBOL)))
+        if !match_reducted_types(op_ret_typ, &ret_typ, &mut input.1) {
+            Box::new(StepResult::err((input.0, BAD_TYPE_SYMBOL)))
         } else if op_arg_types.len() != (expr.children().len() - 1) {
-            Box::new(StepResult::err((input_atom, INCORRECT_NUMBER_OF_ARGUMENTS_SYMBOL)))
+            Box::new(StepResult::err((input.0, INCORRECT_NUMBER_OF_ARGUMENTS_SYMBOL)))
         } else {
-            let input = InterpretedAtom(input_atom, input_bindings);
-            let expr = get_expr(input.atom());
             assert!(!expr.children().is_empty(), "Empty expression is not expected");
             let mut plan: NoInputPlan = Box::new(StepResult::ret(vec![input.clone()]));
             for expr_idx in 0..(expr.children().len()) {
@@ -449,7 +482,7 @@ fn interpret_expression_as_type_op<'a, T: SpaceRef<'a>>(context: InterpreterCont
                 plan = Box::new(SequencePlan::new(
                     plan,
                     OperatorPlan::new(move |results: Results| {
-                        make_alternives_plan(arg.clone(), results, move |result| -> NoInputPlan {
+                        make_alternives_plan(arg.clone(), results, context.order, move |result| -> NoInputPlan {
                             let arg_typ = apply_bindings_to_atom_move(arg_typ.clone(), result.bindings());
                             Box::new(SequencePlan::new(
                                 interpret_as_type_plan(context.clone(),
@@ -463,7 +496,6 @@ fn interpret_expression_as_type_op<'a, T: SpaceRef<'a>>(context: InterpreterCont
             call_alternatives_plan(plan, context, input)
         }
     } else {
-        let expr = get_expr(input.atom());
         let mut plan: NoInputPlan = Box::new(StepResult::ret(vec![input.clone()]));
         for expr_idx in 0..(expr.children().len()) {
             let arg = expr.children()[expr_idx].clone();
@@ -471,7 +503,7 @@ fn interpret_expression_as_type_op<'a, T: SpaceRef<'a>>(context: InterpreterCont
             plan = Box::new(SequencePlan::new(
                 plan,
                 OperatorPlan::new(move |results: Results| {
-                    make_alternives_plan(arg.clone(), results, move |result| -> NoInputPlan {
+                    make_alternives_plan(arg.clone(), results, context.order, move |result| -> NoInputPlan {
                         Box::new(SequencePlan::new(
                             interpret_as_type_plan(context.clone(),
                                 InterpretedAtom(arg.clone(), result.bindings().clone()),
@@ -488,7 +520,7 @@ fn interpret_expression_as_type_op<'a, T: SpaceRef<'a>>(context: InterpreterCont
 fn call_alternatives_plan<'a, T: SpaceRef<'a>>(plan: NoInputPlan<'a>, context: InterpreterContextRef<'a, T>,
     input: InterpretedAtom) -> NoInputPlan<'a> {
     Box::new(SequencePlan::new(plan, OperatorPlan::new(move |results: Results| {
-        make_alternives_plan(input.0, results, move |result| {
+        make_alternives_plan(input.0, results, context.order, move |result| {
             call_plan(context.clone(), result)
         })
     }, "interpret each alternative")))
@@ -500,12 +532,15 @@ fn insert_reducted_arg_plan<'a>(expr: InterpretedAtom, atom_idx: usize) -> Opera
 }
 
 fn insert_reducted_arg_op<'a>(expr: InterpretedAtom, atom_idx: usize, mut arg_variants: Results) -> StepResult<'a, Results, InterpreterError> {
+    let Some(template) = get_expr(expr.atom()) else {
+        return invariant_violation!(expr.0, "Atom::Expression is expected, received: {}", expr.0);
+    };
     let result = arg_variants.drain(0..).map(|arg| {
         let InterpretedAtom(arg, bindings) = arg;
-        let mut expr_with_arg = expr.atom().clone();
-        get_expr_mut(&mut expr_with_arg).children_mut()[atom_idx] = arg;
-        InterpretedAtom(apply_bindings_to_atom_move(expr_with_arg, &bindings), bindings)
-    }).collect();
+        let mut expr_with_arg = template.clone();
+        expr_with_arg.children_mut()[atom_idx] = arg;
+        InterpretedAtom(apply_bindings_to_atom_move(Atom::Expression(expr_with_arg), &bindings), bindings)
+    }).collect::<Results>();
     log::debug!("insert_reducted_arg_op: result: {:?}", result);
     StepResult::ret(result)
 }
@@ -541,7 +576,7 @@ fn call_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input: In
                         StepResult::ret(vec![input])))
             }
         } else {
-            panic!("Only expressions are expected to be called");
+            invariant_violation!(input.0, "Only expressions are expected to be called, received: {}", input.0)
         }
     }
 }
@@ -570,11 +605,13 @@ fn interpret_reducted_plan<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'
             #[cfg(not(feature = "variable_operation"))]
             let result = Box::new(StepResult::ret(vec![input]));
             result
+        } else if is_data_constructor_expr(&context.space, expr) {
+            Box::new(StepResult::ret(vec![input]))
         } else {
             Box::new(match_plan(context, input))
         }
     } else {
-        panic!("Only expression is expected, received: {}", input);
+        Box::new(invariant_violation!(input.0, "Only expression is expected, received: {}", input))
     }
 }
 
@@ -594,7 +631,7 @@ fn execute_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input:
                 match op.as_grounded().as_execute() {
                     None => StepResult::err((input.0, NOT_REDUCIBLE_SYMBOL)),
                     Some(executable) => {
-                        match executable.execute(&args[1..]) {
+                        match executable.execute_bindings(&args[1..], bindings) {
                             Ok(mut vec) => {
                                 let results: Vec<InterpretedAtom> = vec.drain(0..)
                                     .map(|atom| InterpretedAtom(atom, bindings.clone()))
@@ -602,7 +639,7 @@ fn execute_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input:
                                 if results.is_empty() {
                                     StepResult::ret(results)
                                 } else {
-                                    make_alternives_plan(input.0, results, move |result| {
+                                    make_alternives_plan(input.0, results, context.order, move |result| {
                                         interpret_as_type_plan(context.clone(),
                                             result, ATOM_TYPE_UNDEFINED)
                                     })
@@ -615,10 +652,10 @@ fn execute_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input:
                     },
                 }
             } else {
-                panic!("Trying to execute non grounded atom: {}", expr)
+                invariant_violation!(input.0.clone(), "Trying to execute non grounded atom: {}", expr)
             }
         },
-        _ => panic!("Unexpected non expression argument: {}", input),
+        _ => invariant_violation!(input.0, "Unexpected non expression argument: {}", input),
     }
 }
 
@@ -629,7 +666,7 @@ fn match_plan<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input:
 
 fn match_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input: InterpretedAtom) -> StepResult<'a, Results, InterpreterError> {
     log::debug!("match_op: {}", input);
-    let var_x = VariableAtom::new("X").make_unique();
+    let var_x = gensym("X");
     let query = Atom::expr(vec![EQUAL_SYMBOL, input.atom().clone(), Atom::Variable(var_x.clone())]);
     let mut query_bindings = context.space.query(&query);
     let results: Vec<InterpretedAtom> = query_bindings
@@ -649,13 +686,13 @@ fn match_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input: I
         .filter(|(_, bindings)| bindings.is_ok())
         .map(|(result, bindings)| InterpretedAtom(result, bindings.unwrap()))
         .collect();
-    make_alternives_plan(input.0, results, move |result| {
+    make_alternives_plan(input.0, results, context.order, move |result| {
         interpret_as_type_plan(context.clone(), result, ATOM_TYPE_UNDEFINED)
     })
 }
 
 fn make_alternives_plan<'a, T: Debug, F, P>(input: Atom, mut results: Vec<T>,
-    plan: F) -> StepResult<'a, Results, InterpreterError>
+    order: ResultOrder, plan: F) -> StepResult<'a, Results, InterpreterError>
 where
     F: 'a + Fn(T) -> P,
     P: 'a + Plan<'a, (), Results, InterpreterError>
@@ -665,11 +702,12 @@ where
         0 => StepResult::err((input, NO_VALID_ALTERNATIVES)),
         1 => StepResult::execute(plan(results.pop().unwrap())),
         _ => {
-            StepResult::execute(AlternativeInterpretationsPlan::new(
+            StepResult::execute(AlternativeInterpretationsPlan::with_order(
                 input,
                 results.drain(0..)
                     .map(|result| -> NoInputPlan { Box::new(plan(result)) })
-                    .collect()))
+                    .collect(),
+                order))
         },
     }
 }
@@ -679,11 +717,14 @@ use std::collections::VecDeque;
 /// Plan which interprets in parallel alternatives of the expression.
 /// Each successful result is appended to the overall result of the plan.
 /// If no alternatives returned successful result the plan returns error.
+/// Alternatives are executed one after another or step by step depending on
+/// the [ResultOrder].
 pub struct AlternativeInterpretationsPlan<'a, T> {
     atom: Atom,
     plans: VecDeque<Box<dyn Plan<'a, (), Vec<T>, InterpreterError> + 'a>>,
     results: Vec<T>,
     success: bool,
+    order: ResultOrder,
 }
 
 impl<'a, T> AlternativeInterpretationsPlan<'a, T> {
@@ -693,7 +734,13 @@ impl<'a, T> AlternativeInterpretationsPlan<'a, T> {
     /// `atom` - atom to be printed as root of the alternative interpretations
     /// `plan` - altenative plans for the atom
     pub fn new(atom: Atom, plans: Vec<Box<dyn Plan<'a, (), Vec<T>, InterpreterError> + 'a>>) -> Self {
-        Self{ atom, plans: plans.into(), results: Vec::new(), success: false }
+        Self::with_order(atom, plans, ResultOrder::default())
+    }
+
+    /// Create new instance of [AlternativeInterpretationsPlan] which
+    /// merges results of the alternatives in the passed `order`.
+    pub fn with_order(atom: Atom, plans: Vec<Box<dyn Plan<'a, (), Vec<T>, InterpreterError> + 'a>>, order: ResultOrder) -> Self {
+        Self{ atom, plans: plans.into(), results: Vec::new(), success: false, order }
     }
 }
 
@@ -710,7 +757,10 @@ impl<'a, T: Debug> Plan<'a, (), Vec<T>, InterpreterError> for AlternativeInterpr
             let plan = self.plans.pop_front().unwrap();
             match plan.step(()) {
                 StepResult::Execute(next) => {
-                    self.plans.push_front(next);
+                    match self.order {
+                        ResultOrder::Evaluation => self.plans.push_front(next),
+                        ResultOrder::Completion => self.plans.push_back(next),
+                    }
                     StepResult::Execute(self)
                 },
                 StepResult::Return(mut result) => {
@@ -750,6 +800,34 @@ mod tests {
     use crate::common::*;
     use crate::common::test_utils::*;
 
+    #[cfg(feature = "plan_validation")]
+    #[test]
+    fn test_plan_validation_reports_invariant_violation() {
+        let space = GroundingSpace::new();
+        let context = InterpreterContextRef::new(&space);
+
+        let result = execute_op(context.clone(), InterpretedAtom(sym!("A"), Bindings::new()));
+        assert!(matches!(result, StepResult::Error((atom, err)) if atom == sym!("A")
+            && err == sym!("Interpreter invariant is violated: Unexpected non expression argument: A")));
+
+        let result = execute_op(context.clone(), InterpretedAtom(expr!("foo" "A"), Bindings::new()));
+        assert!(matches!(result, StepResult::Error((atom, err)) if atom == expr!("foo" "A")
+            && err == sym!("Interpreter invariant is violated: Trying to execute non grounded atom: (foo A)")));
+
+        let result = insert_reducted_arg_op(InterpretedAtom(sym!("A"), Bindings::new()), 0, vec![]);
+        assert!(matches!(result, StepResult::Error((atom, err)) if atom == sym!("A")
+            && err == sym!("Interpreter invariant is violated: Atom::Expression is expected, received: A")));
+
+        let result = interpret_expression_as_type_op(context.clone(), InterpretedAtom(sym!("A"), Bindings::new()),
+            ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED).step(());
+        assert!(matches!(result, StepResult::Error((atom, err)) if atom == sym!("A")
+            && err == sym!("Interpreter invariant is violated: Atom::Expression is expected, received: A")));
+
+        let result = interpret_reducted_plan(context, InterpretedAtom(sym!("A"), Bindings::new())).step(());
+        assert!(matches!(result, StepResult::Error((atom, err)) if atom == sym!("A")
+            && err == sym!("Interpreter invariant is violated: Only expression is expected, received: A")));
+    }
+
     #[test]
     fn test_match_all() {
         let mut space = GroundingSpace::new();
@@ -821,7 +899,7 @@ mod tests {
     #[test]
     fn test_make_alternatives_plan_no_alternative() {
         let plan = make_alternives_plan(sym!("Test"),
-            vec![], |_res: InterpretedAtom| StepResult::ret(vec![]));
+            vec![], ResultOrder::default(), |_res: InterpretedAtom| StepResult::ret(vec![]));
 
         let result = test_interpret(plan, ());
 
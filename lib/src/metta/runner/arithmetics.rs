@@ -208,6 +208,10 @@ macro_rules! def_binary_number_op {
 
                 Ok(vec![Atom::gnd(res)])
             }
+
+            fn is_pure(&self) -> bool {
+                true
+            }
         }
     }
 }
@@ -251,6 +255,10 @@ macro_rules! def_binary_bool_op {
 
                 Ok(vec![Atom::gnd(Bool(a $op b))])
             }
+
+            fn is_pure(&self) -> bool {
+                true
+            }
         }
     }
 }
@@ -313,6 +321,125 @@ impl CustomExecute for NotOp {
 
         Ok(vec![Atom::gnd(Bool(!a))])
     }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ApproxEqualOp{}
+
+impl Display for ApproxEqualOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "approx-equal")
+    }
+}
+
+impl Grounded for ApproxEqualOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ApproxEqualOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("approx-equal expects three number arguments: two numbers to compare and non-negative tolerance");
+        if args.len() != 3 {
+            return Err(arg_error());
+        }
+        let number = |atom: &Atom| AsPrimitive::from_atom(atom).as_number().map(Into::<f64>::into).ok_or_else(arg_error);
+        let a = number(&args[0])?;
+        let b = number(&args[1])?;
+        let tolerance = number(&args[2])?;
+        if tolerance.is_nan() || tolerance < 0.0 {
+            return Err(arg_error());
+        }
+
+        Ok(vec![Atom::gnd(Bool((a - b).abs() <= tolerance))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Converts number into floating point number: `(to-float 3)` returns `3.0`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ToFloatOp{}
+
+impl Display for ToFloatOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "to-float")
+    }
+}
+
+impl Grounded for ToFloatOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ToFloatOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("to-float expects one number argument");
+        let number = args.first().and_then(|a| AsPrimitive::from_atom(a).as_number()).ok_or_else(arg_error)?;
+
+        Ok(vec![Atom::gnd(Number::Float(number.into()))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Converts number into integer number. Fractional part of the floating
+/// point number is truncated: `(to-int 3.7)` returns `3` and `(to-int -3.7)`
+/// returns `-3`. Floating point number which is not finite or doesn't fit
+/// into the integer number leads to the error.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ToIntOp{}
+
+impl Display for ToIntOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "to-int")
+    }
+}
+
+impl Grounded for ToIntOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ToIntOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("to-int expects one finite number argument in range of integer numbers");
+        let number = args.first().and_then(|a| AsPrimitive::from_atom(a).as_number()).ok_or_else(arg_error)?;
+
+        let n = match number {
+            Number::Integer(n) => n,
+            Number::Float(n) if n.is_finite() && n.trunc() >= i64::MIN as f64 && n.trunc() < i64::MAX as f64 => n.trunc() as i64,
+            Number::Float(_) => return Err(arg_error()),
+        };
+        Ok(vec![Atom::gnd(Number::Integer(n))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Default)]
@@ -484,6 +611,35 @@ mod tests {
         assert_binary_op!(DivOp, Number::Float(430.5), Number::Float(10.25), Number::Float(42.0));
     }
 
+    #[test]
+    fn approx_equal_op() {
+        let approx_equal = |a: Number, b: Number, t: Number| ApproxEqualOp{}.execute(&mut vec![Atom::gnd(a), Atom::gnd(b), Atom::gnd(t)]);
+        assert_eq!(approx_equal(Number::Float(0.1), Number::Float(0.1000001), Number::Float(0.001)), Ok(vec![Atom::gnd(Bool(true))]));
+        assert_eq!(approx_equal(Number::Float(0.1), Number::Float(0.2), Number::Float(0.001)), Ok(vec![Atom::gnd(Bool(false))]));
+        assert_eq!(approx_equal(Number::Integer(42), Number::Float(42.5), Number::Integer(1)), Ok(vec![Atom::gnd(Bool(true))]));
+        assert_eq!(approx_equal(Number::Integer(1), Number::Integer(1), Number::Integer(0)), Ok(vec![Atom::gnd(Bool(true))]));
+        assert!(approx_equal(Number::Integer(1), Number::Integer(1), Number::Integer(-1)).is_err());
+        assert!(ApproxEqualOp{}.execute(&mut vec![Atom::gnd(Number::Integer(1)), Atom::gnd(Number::Integer(1))]).is_err());
+        assert!(ApproxEqualOp{}.execute(&mut vec![Atom::gnd(Number::Integer(1)), Atom::sym("a"), Atom::gnd(Number::Integer(1))]).is_err());
+    }
+
+    #[test]
+    fn to_float_op() {
+        assert_eq!(ToFloatOp{}.execute(&mut vec![Atom::gnd(Number::Integer(3))]), Ok(vec![Atom::gnd(Number::Float(3.0))]));
+        assert_eq!(ToFloatOp{}.execute(&mut vec![Atom::gnd(Number::Float(3.5))]), Ok(vec![Atom::gnd(Number::Float(3.5))]));
+        assert!(ToFloatOp{}.execute(&mut vec![Atom::sym("a")]).is_err());
+    }
+
+    #[test]
+    fn to_int_op() {
+        assert_eq!(ToIntOp{}.execute(&mut vec![Atom::gnd(Number::Float(3.7))]), Ok(vec![Atom::gnd(Number::Integer(3))]));
+        assert_eq!(ToIntOp{}.execute(&mut vec![Atom::gnd(Number::Float(-3.7))]), Ok(vec![Atom::gnd(Number::Integer(-3))]));
+        assert_eq!(ToIntOp{}.execute(&mut vec![Atom::gnd(Number::Integer(42))]), Ok(vec![Atom::gnd(Number::Integer(42))]));
+        assert!(ToIntOp{}.execute(&mut vec![Atom::gnd(Number::Float(f64::NAN))]).is_err());
+        assert!(ToIntOp{}.execute(&mut vec![Atom::gnd(Number::Float(1e20))]).is_err());
+        assert!(ToIntOp{}.execute(&mut vec![Atom::sym("a")]).is_err());
+    }
+
     #[test]
     fn mod_op() {
         assert_binary_op!(ModOp, Number::Integer(85), Number::Integer(43), Number::Integer(42));
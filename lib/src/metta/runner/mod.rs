@@ -87,7 +87,13 @@ pub use environment::{Environment, EnvBuilder};
 
 #[macro_use]
 pub mod stdlib;
-use super::interpreter::{interpret, interpret_init, interpret_step, InterpreterState};
+use super::interpreter::{interpret, interpret_step, InterpreterState};
+#[cfg(not(feature = "old_interpreter"))]
+use super::interpreter::interpret_init;
+#[cfg(feature = "old_interpreter")]
+use super::interpreter::interpret_init_with_order;
+#[cfg(feature = "old_interpreter")]
+use crate::common::plan::ResultOrder;
 
 #[cfg(not(feature = "old_interpreter"))]
 pub mod stdlib_minimal;
@@ -1067,7 +1073,11 @@ impl<'input> RunContext<'_, '_, 'input> {
                                 } else {
                                     wrap_atom_by_metta_interpreter(self.module().space().clone(), atom)
                                 };
-                                self.i_wrapper.interpreter_state = Some(interpret_init(self.module().space().clone(), &atom));
+                                #[cfg(feature = "old_interpreter")]
+                                let state = interpret_init_with_order(self.module().space().clone(), &atom, result_order(self.metta));
+                                #[cfg(not(feature = "old_interpreter"))]
+                                let state = interpret_init(self.module().space().clone(), &atom);
+                                self.i_wrapper.interpreter_state = Some(state);
                             }
                         },
                         MettaRunnerMode::TERMINATE => {
@@ -1092,6 +1102,16 @@ fn is_bare_minimal_interpreter(metta: &Metta) -> bool {
     metta.get_setting_string("interpreter") == Some("bare-minimal".into())
 }
 
+/// Returns the order of the results set by `(pragma! result-order completion)`,
+/// results are returned in evaluation order by default.
+#[cfg(feature = "old_interpreter")]
+fn result_order(metta: &Metta) -> ResultOrder {
+    match metta.get_setting_string("result-order").as_deref() {
+        Some("completion") => ResultOrder::Completion,
+        _ => ResultOrder::Evaluation,
+    }
+}
+
 // *-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*
 // InterpreterWrapper & related objects
 // *-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*-=-*
@@ -1213,6 +1233,27 @@ mod tests {
         assert_eq!(result, Ok(vec![vec![Atom::sym("T")]]));
     }
 
+    #[cfg(feature = "old_interpreter")]
+    #[test]
+    fn metta_result_order() {
+        let program = "
+            (= (f) (g))
+            (= (f) B)
+            (= (g) (h))
+            (= (h) A)
+            !(f)
+        ";
+
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = metta.run(SExprParser::new(program));
+        assert_eq!(result, Ok(vec![vec![expr!("A"), expr!("B")]]));
+
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        metta.set_setting("result-order".into(), sym!("completion"));
+        let result = metta.run(SExprParser::new(program));
+        assert_eq!(result, Ok(vec![vec![expr!("B"), expr!("A")]]));
+    }
+
     #[test]
     fn metta_add_type_check() {
         let program = "
@@ -1,5 +1,6 @@
 use crate::*;
 use crate::space::*;
+use crate::matcher::Bindings;
 use crate::metta::*;
 use crate::metta::text::Tokenizer;
 use crate::metta::text::SExprParser;
@@ -9,6 +10,7 @@ use crate::metta::types::{get_atom_types, get_meta_type};
 #[cfg(feature = "old_interpreter")]
 use crate::metta::interpreter::interpret;
 use crate::common::shared::Shared;
+use crate::common::gensym::{gensym, is_internal_variable};
 use crate::common::CachingMapper;
 use crate::common::multitrie::MultiTrie;
 use crate::space::grounding::atom_to_trie_key;
@@ -20,6 +22,8 @@ use std::rc::Rc;
 use std::cell::RefCell;
 use std::fmt::Display;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
 use regex::Regex;
 
 use super::arithmetics::*;
@@ -341,6 +345,268 @@ impl CustomExecute for BindOp {
     }
 }
 
+/// Parses the string using the tokenizer of the module and returns the atom.
+#[derive(Clone, Debug)]
+pub struct ParseOp {
+    tokenizer: Shared<Tokenizer>,
+}
+
+grounded_op!(ParseOp, "parse");
+
+impl ParseOp {
+    pub fn new(tokenizer: Shared<Tokenizer>) -> Self {
+        Self{ tokenizer }
+    }
+}
+
+impl Grounded for ParseOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ParseOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("parse expects single string as an argument");
+        let text = args.first().and_then(|a| a.as_gnd::<Str>()).ok_or_else(arg_error)?;
+
+        let tokenizer = self.tokenizer.borrow();
+        let mut parser = SExprParser::new(text.as_str());
+        match parser.parse(&tokenizer)? {
+            None => Err("parse expects string which contains an atom".into()),
+            Some(atom) => match parser.parse(&tokenizer)? {
+                None => Ok(vec![atom]),
+                Some(_) => Err("parse expects string which contains single atom".into()),
+            },
+        }
+    }
+}
+
+/// Parses the string using the tokenizer of the module and adds all parsed
+/// atoms into the space. Returns the number of atoms added. When the string
+/// cannot be parsed returns an error and doesn't add any atom.
+#[derive(Clone, Debug)]
+pub struct LoadStringOp {
+    tokenizer: Shared<Tokenizer>,
+}
+
+grounded_op!(LoadStringOp, "load-string");
+
+impl LoadStringOp {
+    pub fn new(tokenizer: Shared<Tokenizer>) -> Self {
+        Self{ tokenizer }
+    }
+}
+
+impl Grounded for LoadStringOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_STRING, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for LoadStringOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("load-string expects two arguments: space and string");
+        let space = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let text = args.get(1).and_then(|a| a.as_gnd::<Str>()).ok_or_else(arg_error)?;
+
+        let tokenizer = self.tokenizer.borrow();
+        let mut parser = SExprParser::new(text.as_str());
+        let mut atoms = Vec::new();
+        while let Some(atom) = parser.parse(&tokenizer)? {
+            atoms.push(atom);
+        }
+        let count = atoms.len();
+        let mut space = space.borrow_mut();
+        for atom in atoms {
+            space.add(atom);
+        }
+        Ok(vec![Atom::gnd(Number::Integer(count as i64))])
+    }
+}
+
+/// Converts the atom into the string using its textual representation.
+#[derive(Clone, Debug)]
+pub struct ReprOp {}
+
+grounded_op!(ReprOp, "repr");
+
+impl Grounded for ReprOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ReprOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("repr expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Str::from_string(atom.to_string()))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Parses the string as a number. Integer text is converted to an integer
+/// number, any other numeric text is converted to a floating point number.
+#[derive(Clone, Debug)]
+pub struct ParseNumberOp {}
+
+grounded_op!(ParseNumberOp, "parse-number");
+
+impl Grounded for ParseNumberOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ParseNumberOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("parse-number expects single string as an argument");
+        let text = args.first().and_then(|a| a.as_gnd::<Str>()).ok_or_else(arg_error)?;
+        let text = text.as_str().trim();
+
+        let number = Number::from_int_str(text)
+            .or_else(|_| Number::from_float_str(text))
+            .ok()
+            .filter(|n| !matches!(n, Number::Float(f) if !f.is_finite()))
+            .ok_or_else(|| ExecError::from(format!("parse-number expects numeric string, found: \"{}\"", text)))?;
+        Ok(vec![Atom::gnd(number)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Converts the number into the string. Integers are formatted without
+/// fractional part, floating point numbers always keep it, thus the result
+/// can be converted back by `parse-number` without changing the number kind.
+#[derive(Clone, Debug)]
+pub struct NumberToStringOp {}
+
+grounded_op!(NumberToStringOp, "number->string");
+
+impl Grounded for NumberToStringOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for NumberToStringOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("number->string expects single number as an argument");
+        let number = args.first().and_then(|a| AsPrimitive::from_atom(a).as_number()).ok_or_else(arg_error)?;
+        let text = match number {
+            Number::Integer(n) => n.to_string(),
+            Number::Float(n) => format!("{:?}", n),
+        };
+        Ok(vec![Atom::gnd(Str::from_string(text))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Splits the string passed as the second argument by the separator passed
+/// as the first argument: `(split "," "a,b,c")` returns `("a" "b" "c")`.
+/// When the string doesn't contain the separator the result contains the
+/// whole string. Empty string produces empty expression. Separator should not
+/// be empty.
+#[derive(Clone, Debug)]
+pub struct SplitOp {}
+
+grounded_op!(SplitOp, "split");
+
+impl Grounded for SplitOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SplitOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("split expects non-empty separator and string as arguments");
+        let separator = args.first().and_then(|a| a.as_gnd::<Str>()).ok_or_else(arg_error)?;
+        let text = args.get(1).and_then(|a| a.as_gnd::<Str>()).ok_or_else(arg_error)?;
+        if separator.as_str().is_empty() {
+            return Err(arg_error());
+        }
+        let parts = match text.as_str() {
+            "" => vec![],
+            text => text.split(separator.as_str())
+                .map(|part| Atom::gnd(Str::from_string(part.to_string())))
+                .collect(),
+        };
+        Ok(vec![Atom::expr(parts)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Joins strings from the expression passed as the second argument using
+/// the separator passed as the first argument: `(join "," ("a" "b" "c"))`
+/// returns `"a,b,c"`. Empty expression produces empty string.
+#[derive(Clone, Debug)]
+pub struct JoinOp {}
+
+grounded_op!(JoinOp, "join");
+
+impl Grounded for JoinOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_EXPRESSION, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for JoinOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("join expects separator and expression of strings as arguments");
+        let separator = args.first().and_then(|a| a.as_gnd::<Str>()).ok_or_else(arg_error)?;
+        let parts = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let parts = parts.children().iter()
+            .map(|part| part.as_gnd::<Str>().map(Str::as_str).ok_or_else(arg_error))
+            .collect::<Result<Vec<&str>, ExecError>>()?;
+        Ok(vec![Atom::gnd(Str::from_string(parts.join(separator.as_str())))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct NewSpaceOp {}
 
@@ -449,22 +715,17 @@ impl CustomExecute for GetAtomsOp {
     }
 }
 
+/// Returns single expression which contains all atoms of the space in order
+/// they are returned by the space iterator. For [GroundingSpace] it is the
+/// order of insertion.
 #[derive(Clone, Debug)]
-pub struct PragmaOp {
-    settings: Shared<HashMap<String, Atom>>,
-}
-
-grounded_op!(PragmaOp, "pragma!");
+pub struct SpaceToExprOp {}
 
-impl PragmaOp {
-    pub fn new(settings: Shared<HashMap<String, Atom>>) -> Self {
-        Self{ settings }
-    }
-}
+grounded_op!(SpaceToExprOp, "space->expr");
 
-impl Grounded for PragmaOp {
+impl Grounded for SpaceToExprOp {
     fn type_(&self) -> Atom {
-        ATOM_TYPE_UNDEFINED
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_EXPRESSION])
     }
 
     fn as_execute(&self) -> Option<&dyn CustomExecute> {
@@ -472,32 +733,54 @@ impl Grounded for PragmaOp {
     }
 }
 
-impl CustomExecute for PragmaOp {
+impl CustomExecute for SpaceToExprOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("pragma! expects key and value as arguments");
-        let key = <&SymbolAtom>::try_from(args.get(0).ok_or_else(arg_error)?).map_err(|_| "pragma! expects symbol atom as a key")?.name();
-        let value = args.get(1).ok_or_else(arg_error)?;
-        self.settings.borrow_mut().insert(key.into(), value.clone());
-        unit_result()
+        let arg_error = || ExecError::from("space->expr expects one argument: space");
+        let space = args.first().ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("space->expr expects a space as its argument")?;
+        space.borrow().as_space().atom_iter()
+            .map(|iter| vec![Atom::expr(iter.cloned().map(make_variables_unique).collect::<Vec<Atom>>())])
+            .ok_or(ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))
     }
 }
 
+/// Creates new space and adds children of the expression into it.
 #[derive(Clone, Debug)]
-pub struct GetTypeOp {
-    space: DynSpace,
-}
+pub struct ExprToSpaceOp {}
 
-grounded_op!(GetTypeOp, "get-type");
+grounded_op!(ExprToSpaceOp, "expr->space");
 
-impl GetTypeOp {
-    pub fn new(space: DynSpace) -> Self {
-        Self{ space }
+impl Grounded for ExprToSpaceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, rust_type_atom::<DynSpace>()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
     }
 }
 
-impl Grounded for GetTypeOp {
+impl CustomExecute for ExprToSpaceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("expr->space expects single expression atom as an argument");
+        let expr = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?)?;
+        let mut space = GroundingSpace::new();
+        expr.children().iter().for_each(|atom| space.add(atom.clone()));
+        Ok(vec![Atom::gnd(DynSpace::new(space))])
+    }
+}
+
+/// Returns atoms of the first space which are not present in the second
+/// space. Spaces are not modified.
+#[derive(Clone, Debug)]
+pub struct SubtractSpaceOp {}
+
+grounded_op!(SubtractSpaceOp, "subtract");
+
+impl Grounded for SubtractSpaceOp {
     fn type_(&self) -> Atom {
-        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(),
+            rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM])
     }
 
     fn as_execute(&self) -> Option<&dyn CustomExecute> {
@@ -505,23 +788,38 @@ impl Grounded for GetTypeOp {
     }
 }
 
-impl CustomExecute for GetTypeOp {
+impl CustomExecute for SubtractSpaceOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("get-type expects single atom as an argument");
-        let atom = args.get(0).ok_or_else(arg_error)?;
-
-        Ok(get_atom_types(self.space.borrow().as_space(), atom))
+        let arg_error = || ExecError::from("subtract expects two arguments: space and space");
+        let space_error = || ExecError::from("subtract expects spaces as its arguments");
+        let left = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(space_error)?;
+        let right = Atom::as_gnd::<DynSpace>(args.get(1).ok_or_else(arg_error)?).ok_or_else(space_error)?;
+        let iter_error = || ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string());
+
+        let right = right.borrow();
+        let right: Vec<&Atom> = right.as_space().atom_iter().ok_or_else(iter_error)?.collect();
+        let left = left.borrow();
+        let result = left.as_space().atom_iter().ok_or_else(iter_error)?
+            .filter(|atom| !right.contains(atom))
+            .cloned()
+            .map(make_variables_unique)
+            .collect();
+        Ok(result)
     }
 }
 
+/// Returns atoms of the first space which are present in the second space
+/// as well. Atoms are compared by equality, rules `(= <lhs> <rhs>)` are
+/// compared as any other atom. Spaces are not modified.
 #[derive(Clone, Debug)]
-pub struct GetTypeSpaceOp {}
+pub struct IntersectSpaceOp {}
 
-grounded_op!(GetTypeSpaceOp, "get-type-space");
+grounded_op!(IntersectSpaceOp, "intersect");
 
-impl Grounded for GetTypeSpaceOp {
+impl Grounded for IntersectSpaceOp {
     fn type_(&self) -> Atom {
-        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(),
+            rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM])
     }
 
     fn as_execute(&self) -> Option<&dyn CustomExecute> {
@@ -529,26 +827,41 @@ impl Grounded for GetTypeSpaceOp {
     }
 }
 
-impl CustomExecute for GetTypeSpaceOp {
+impl CustomExecute for IntersectSpaceOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("get-type-space expects two arguments: space and atom");
-        let space = args.get(0).ok_or_else(arg_error)?;
-        let space = Atom::as_gnd::<DynSpace>(space).ok_or("get-type-space expects a space as the first argument")?;
-        let atom = args.get(1).ok_or_else(arg_error)?;
-        log::debug!("GetTypeSpaceOp::execute: space: {}, atom: {}", space, atom);
-
-        Ok(get_atom_types(space, atom))
+        let arg_error = || ExecError::from("intersect expects two arguments: space and space");
+        let space_error = || ExecError::from("intersect expects spaces as its arguments");
+        let left = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(space_error)?;
+        let right = Atom::as_gnd::<DynSpace>(args.get(1).ok_or_else(arg_error)?).ok_or_else(space_error)?;
+        let iter_error = || ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string());
+
+        let right = right.borrow();
+        let right: Vec<&Atom> = right.as_space().atom_iter().ok_or_else(iter_error)?.collect();
+        let left = left.borrow();
+        let result = left.as_space().atom_iter().ok_or_else(iter_error)?
+            .filter(|atom| right.contains(atom))
+            .cloned()
+            .map(make_variables_unique)
+            .collect();
+        Ok(result)
     }
 }
 
+/// Creates new space which contains atoms of both spaces passed without
+/// duplicates: `(merge-dedup &a &b)`. Atoms are compared by equality, each
+/// atom is added once even when it is duplicated inside one of the spaces.
+/// Atoms of the first space are added first, then atoms of the second space
+/// which are not added yet. As [CloneSpaceOp] the new space is independent
+/// from the original ones.
 #[derive(Clone, Debug)]
-pub struct GetMetaTypeOp { }
+pub struct MergeDedupOp {}
 
-grounded_op!(GetMetaTypeOp, "get-metatype");
+grounded_op!(MergeDedupOp, "merge-dedup");
 
-impl Grounded for GetMetaTypeOp {
+impl Grounded for MergeDedupOp {
     fn type_(&self) -> Atom {
-        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(),
+            rust_type_atom::<DynSpace>(), rust_type_atom::<DynSpace>()])
     }
 
     fn as_execute(&self) -> Option<&dyn CustomExecute> {
@@ -556,24 +869,42 @@ impl Grounded for GetMetaTypeOp {
     }
 }
 
-impl CustomExecute for GetMetaTypeOp {
+impl CustomExecute for MergeDedupOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("get-metatype expects single atom as an argument");
-        let atom = args.get(0).ok_or_else(arg_error)?;
-
-        Ok(vec![get_meta_type(&atom)])
+        let arg_error = || ExecError::from("merge-dedup expects two arguments: space and space");
+        let space_error = || ExecError::from("merge-dedup expects spaces as its arguments");
+        let left = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(space_error)?;
+        let right = Atom::as_gnd::<DynSpace>(args.get(1).ok_or_else(arg_error)?).ok_or_else(space_error)?;
+        let iter_error = || ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string());
+
+        let left = left.borrow();
+        let right = right.borrow();
+        let mut merged: Vec<&Atom> = Vec::new();
+        for atom in left.as_space().atom_iter().ok_or_else(iter_error)?
+            .chain(right.as_space().atom_iter().ok_or_else(iter_error)?) {
+            if !merged.contains(&atom) {
+                merged.push(atom);
+            }
+        }
+        let mut space = GroundingSpace::new();
+        merged.into_iter().for_each(|atom| space.add(atom.clone()));
+        Ok(vec![Atom::gnd(DynSpace::new(space))])
     }
 }
 
-
+/// Creates new space which contains the same atoms as the space passed.
+/// The new space is independent: atoms added to or removed from either
+/// space are not visible in the other one, and observers of the original
+/// space are not copied. Grounded atoms (including nested spaces) are
+/// shared between the spaces as they are copied by reference.
 #[derive(Clone, Debug)]
-pub struct PrintlnOp {}
+pub struct CloneSpaceOp {}
 
-grounded_op!(PrintlnOp, "println!");
+grounded_op!(CloneSpaceOp, "clone-space");
 
-impl Grounded for PrintlnOp {
+impl Grounded for CloneSpaceOp {
     fn type_(&self) -> Atom {
-        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, UNIT_TYPE()])
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), rust_type_atom::<DynSpace>()])
     }
 
     fn as_execute(&self) -> Option<&dyn CustomExecute> {
@@ -581,25 +912,31 @@ impl Grounded for PrintlnOp {
     }
 }
 
-impl CustomExecute for PrintlnOp {
+impl CustomExecute for CloneSpaceOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("println! expects single atom as an argument");
-        let atom = args.get(0).ok_or_else(arg_error)?;
-        println!("{}", atom_to_string(atom));
-        unit_result()
+        let arg_error = || ExecError::from("clone-space expects single space as an argument");
+        let space = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let space = space.borrow();
+        let atoms = space.as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?;
+        let mut clone = GroundingSpace::new();
+        atoms.for_each(|atom| clone.add(atom.clone()));
+        Ok(vec![Atom::gnd(DynSpace::new(clone))])
     }
 }
 
+/// Creates new space which contains only atoms of the space passed which
+/// match the pattern: `(filter-space &kb (likes $x $y))`. Bindings of the
+/// matches are not used, each atom is added at most once. As [CloneSpaceOp]
+/// the new space is independent from the original one.
 #[derive(Clone, Debug)]
-pub struct FormatArgsOp {}
-
-grounded_op!(FormatArgsOp, "format-args");
+pub struct FilterSpaceOp {}
 
-use dyn_fmt::AsStrFormatExt;
+grounded_op!(FilterSpaceOp, "filter-space");
 
-impl Grounded for FormatArgsOp {
+impl Grounded for FilterSpaceOp {
     fn type_(&self) -> Atom {
-        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM])
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, rust_type_atom::<DynSpace>()])
     }
 
     fn as_execute(&self) -> Option<&dyn CustomExecute> {
@@ -607,30 +944,360 @@ impl Grounded for FormatArgsOp {
     }
 }
 
-impl CustomExecute for FormatArgsOp {
+impl CustomExecute for FilterSpaceOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("format-args expects format string as a first argument and expression as a second argument");
-        let format = atom_to_string(args.get(0).ok_or_else(arg_error)?);
-        let args = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?)?;
-        let args: Vec<String> = args.children().iter()
-            .map(|atom| atom_to_string(atom))
-            .collect();
-        let res = format.format(args.as_slice());
-        Ok(vec![Atom::gnd(Str::from_string(res))])
+        let arg_error = || ExecError::from("filter-space expects space and pattern as arguments");
+        let space = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let pattern = args.get(1).ok_or_else(arg_error)?;
+        let space = space.borrow();
+        let atoms = space.as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?;
+        let mut filtered = GroundingSpace::new();
+        atoms.filter(|atom| matcher::match_atoms(pattern, atom).next().is_some())
+            .for_each(|atom| filtered.add(atom.clone()));
+        Ok(vec![Atom::gnd(DynSpace::new(filtered))])
     }
 }
 
-fn atom_to_string(atom: &Atom) -> String {
-    match atom {
-        Atom::Grounded(gnd) if gnd.type_() == ATOM_TYPE_STRING => {
-            let mut s = gnd.to_string();
-            s.remove(0);
-            s.pop();
-            s
-        },
-        _ => atom.to_string(),
+/// Creates new space where each occurrence of the symbol passed as the
+/// second argument is replaced by the symbol passed as the third argument:
+/// `(rename-symbol &kb parent father)`. Symbols are replaced at any depth
+/// both in heads and arguments of the expressions. Original space is not
+/// changed, as [CloneSpaceOp] the new space is independent from it.
+#[derive(Clone, Debug)]
+pub struct RenameSymbolOp {}
+
+grounded_op!(RenameSymbolOp, "rename-symbol");
+
+impl Grounded for RenameSymbolOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_SYMBOL, ATOM_TYPE_SYMBOL, rust_type_atom::<DynSpace>()])
     }
-}
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RenameSymbolOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("rename-symbol expects space, old symbol and new symbol as arguments");
+        let space = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let old = match args.get(1) {
+            Some(old @ Atom::Symbol(_)) => old,
+            _ => return Err(arg_error()),
+        };
+        let new = match args.get(2) {
+            Some(new @ Atom::Symbol(_)) => new,
+            _ => return Err(arg_error()),
+        };
+        let space = space.borrow();
+        let atoms = space.as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?;
+        let mut renamed = GroundingSpace::new();
+        atoms.for_each(|atom| renamed.add(substitute(old, new, atom.clone())));
+        Ok(vec![Atom::gnd(DynSpace::new(renamed))])
+    }
+}
+
+/// Returns summary of the space content as an expression
+/// `((atoms <count>) (rules <count>) (heads <count>))`, where `atoms` is a
+/// total number of atoms in the space, `rules` is a number of `(= <lhs> <rhs>)`
+/// atoms and `heads` is a number of distinct operators defined by the rules,
+/// i.e. distinct heads of the rules' `<lhs>` expressions.
+#[derive(Clone, Debug)]
+pub struct SpaceStatsOp {}
+
+grounded_op!(SpaceStatsOp, "space-stats");
+
+impl Grounded for SpaceStatsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SpaceStatsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("space-stats expects single space as an argument");
+        let space = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let space = space.borrow();
+        let atoms = space.as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?;
+
+        let mut atom_count = 0;
+        let mut rule_count = 0;
+        let mut heads: Vec<&Atom> = Vec::new();
+        for atom in atoms {
+            atom_count += 1;
+            if let Atom::Expression(expr) = atom {
+                if let [op, lhs, _rhs] = expr.children().as_slice() {
+                    if *op == EQUAL_SYMBOL {
+                        rule_count += 1;
+                        if let Some(head) = <&[Atom]>::try_from(lhs).ok().and_then(<[Atom]>::first) {
+                            if !heads.contains(&head) {
+                                heads.push(head);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let stat = |name: &str, count: usize| Atom::expr([Atom::sym(name), Atom::gnd(Number::Integer(count as i64))]);
+        Ok(vec![Atom::expr([stat("atoms", atom_count), stat("rules", rule_count), stat("heads", heads.len())])])
+    }
+}
+
+/// Checks the space for contradictory rules and returns an expression which
+/// lists them: `(check-consistent &kb)`. Two rules `(= <lhs1> True)` and
+/// `(= <lhs2> False)` contradict each other when `<lhs1>` and `<lhs2>` can
+/// be unified, i.e. there is a call for which both rules are applicable.
+/// Variables of different rules are considered as different variables even
+/// when they have the same names. Each contradiction is returned as a pair of
+/// the rules, thus an empty expression is returned for a consistent space.
+/// Rules with equal left hand sides and other different right hand sides are
+/// not reported because they are a valid way to define nondeterministic
+/// functions.
+#[derive(Clone, Debug)]
+pub struct CheckConsistentOp {}
+
+grounded_op!(CheckConsistentOp, "check-consistent");
+
+impl Grounded for CheckConsistentOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for CheckConsistentOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("check-consistent expects single space as an argument");
+        let space = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let space = space.borrow();
+        let atoms = space.as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?;
+
+        let mut truths: Vec<(Atom, &Atom)> = Vec::new();
+        let mut falsities: Vec<(&Atom, &Atom)> = Vec::new();
+        for atom in atoms {
+            if let Atom::Expression(expr) = atom {
+                if let [head, lhs, rhs] = expr.children().as_slice() {
+                    if *head == EQUAL_SYMBOL {
+                        match Atom::as_gnd::<Bool>(rhs) {
+                            Some(Bool(true)) => truths.push((make_variables_unique(lhs.clone()), atom)),
+                            Some(Bool(false)) => falsities.push((lhs, atom)),
+                            None => {},
+                        }
+                    }
+                }
+            }
+        }
+        let conflicts = truths.iter()
+            .flat_map(|(lhs, rule)| falsities.iter()
+                .filter(|(other, _)| matcher::match_atoms(lhs, other).next().is_some())
+                .map(|(_, other_rule)| Atom::expr([(*rule).clone(), (*other_rule).clone()])))
+            .collect::<Vec<_>>();
+        Ok(vec![Atom::expr(conflicts)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PragmaOp {
+    settings: Shared<HashMap<String, Atom>>,
+}
+
+grounded_op!(PragmaOp, "pragma!");
+
+impl PragmaOp {
+    pub fn new(settings: Shared<HashMap<String, Atom>>) -> Self {
+        Self{ settings }
+    }
+}
+
+impl Grounded for PragmaOp {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_UNDEFINED
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for PragmaOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("pragma! expects key and value as arguments");
+        let key = <&SymbolAtom>::try_from(args.get(0).ok_or_else(arg_error)?).map_err(|_| "pragma! expects symbol atom as a key")?.name();
+        let value = args.get(1).ok_or_else(arg_error)?;
+        self.settings.borrow_mut().insert(key.into(), value.clone());
+        unit_result()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GetTypeOp {
+    space: DynSpace,
+}
+
+grounded_op!(GetTypeOp, "get-type");
+
+impl GetTypeOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for GetTypeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GetTypeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("get-type expects single atom as an argument");
+        let atom = args.get(0).ok_or_else(arg_error)?;
+
+        Ok(get_atom_types(self.space.borrow().as_space(), atom))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GetTypeSpaceOp {}
+
+grounded_op!(GetTypeSpaceOp, "get-type-space");
+
+impl Grounded for GetTypeSpaceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GetTypeSpaceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("get-type-space expects two arguments: space and atom");
+        let space = args.get(0).ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("get-type-space expects a space as the first argument")?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        log::debug!("GetTypeSpaceOp::execute: space: {}, atom: {}", space, atom);
+
+        Ok(get_atom_types(space, atom))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GetMetaTypeOp { }
+
+grounded_op!(GetMetaTypeOp, "get-metatype");
+
+impl Grounded for GetMetaTypeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GetMetaTypeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("get-metatype expects single atom as an argument");
+        let atom = args.get(0).ok_or_else(arg_error)?;
+
+        Ok(vec![get_meta_type(&atom)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+
+#[derive(Clone, Debug)]
+pub struct PrintlnOp {}
+
+grounded_op!(PrintlnOp, "println!");
+
+impl Grounded for PrintlnOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, UNIT_TYPE()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for PrintlnOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("println! expects single atom as an argument");
+        let atom = args.get(0).ok_or_else(arg_error)?;
+        println!("{}", atom_to_string(atom));
+        unit_result()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FormatArgsOp {}
+
+grounded_op!(FormatArgsOp, "format-args");
+
+use dyn_fmt::AsStrFormatExt;
+
+impl Grounded for FormatArgsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for FormatArgsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("format-args expects format string as a first argument and expression as a second argument");
+        let format = atom_to_string(args.get(0).ok_or_else(arg_error)?);
+        let args = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?)?;
+        let args: Vec<String> = args.children().iter()
+            .map(|atom| atom_to_string(atom))
+            .collect();
+        let res = format.format(args.as_slice());
+        Ok(vec![Atom::gnd(Str::from_string(res))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+fn atom_to_string(atom: &Atom) -> String {
+    match atom {
+        Atom::Grounded(gnd) if gnd.type_() == ATOM_TYPE_STRING => {
+            let mut s = gnd.to_string();
+            s.remove(0);
+            s.pop();
+            s
+        },
+        _ => atom.to_string(),
+    }
+}
 
 
 /// Implement trace! built-in.
@@ -908,23 +1575,233 @@ impl CustomExecute for MatchOp {
     }
 }
 
-/// The op atoms that depend on the pkg_mgmt feature
-#[cfg(feature = "pkg_mgmt")]
-pub(crate) mod pkg_mgmt_ops {
-    use super::*;
+/// Performs single reduction step of the atom using `(= <lhs> <rhs>)`
+/// rules from the space. Returns the `<rhs>` of the first found rule which
+/// `<lhs>` matches the atom with bindings applied. Returns the atom
+/// unchanged when no rule matches it. Result is wrapped into `quote` to
+/// prevent its further evaluation: `(rewrite &self (add (S Z) Z))` returns
+/// `(quote (S (add Z Z)))`.
+#[derive(Clone, Debug)]
+pub struct RewriteOp {}
 
-    /// Provides a way to access [Metta::load_module_at_path] from within MeTTa code
-    #[derive(Clone, Debug)]
-    pub struct RegisterModuleOp {
-        metta: Metta
-    }
+grounded_op!(RewriteOp, "rewrite");
 
-    grounded_op!(RegisterModuleOp, "register-module!");
+impl Grounded for RewriteOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
 
-    impl RegisterModuleOp {
-        pub fn new(metta: Metta) -> Self {
-            Self{ metta }
-        }
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RewriteOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("rewrite expects two arguments: space and atom");
+        let space = args.first().ok_or_else(arg_error)?;
+        let target = args.get(1).ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("rewrite expects a space as the first argument")?;
+
+        let var_x = gensym("X");
+        let query = Atom::expr([EQUAL_SYMBOL, target.clone(), Atom::Variable(var_x.clone())]);
+        let rewritten = space.borrow().query(&query).into_iter()
+            .find_map(|bindings| bindings.resolve(&var_x));
+        log::debug!("RewriteOp::execute: target: {}, rewritten: {:?}", target, rewritten);
+        Ok(vec![Atom::expr([Atom::sym("quote"), rewritten.unwrap_or_else(|| target.clone())])])
+    }
+}
+
+/// Performs single reduction step of the atom using the first rule in the
+/// order of the space atoms iterator: `(rewrite-first &space (f a))`. Unlike
+/// [RewriteOp] which takes the first result of the space query the rules are
+/// checked in the order they are traversed, thus the result is deterministic
+/// when several rules are applicable. For [GroundingSpace] it is the order
+/// of adding to the space only while no atoms are removed: an atom added
+/// after a removal takes the slot of the removed one. Returns the `<rhs>` of
+/// the rule with bindings applied or the atom unchanged when no rule matches
+/// it. Result is wrapped into `quote` to prevent its further evaluation.
+#[derive(Clone, Debug)]
+pub struct RewriteFirstOp {}
+
+grounded_op!(RewriteFirstOp, "rewrite-first");
+
+impl Grounded for RewriteFirstOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RewriteFirstOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("rewrite-first expects two arguments: space and atom");
+        let space = args.first().ok_or_else(arg_error)?;
+        let target = args.get(1).ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("rewrite-first expects a space as the first argument")?;
+        let space = space.borrow();
+        let mut atoms = space.as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?;
+
+        let rewritten = atoms.find_map(|atom| {
+            match <[Atom; 3]>::try_from(make_variables_unique(atom.clone())) {
+                Ok([head, lhs, rhs]) if head == EQUAL_SYMBOL => matcher::match_atoms(&lhs, target).next()
+                    .map(|bindings| matcher::apply_bindings_to_atom_move(rhs, &bindings)),
+                _ => None,
+            }
+        });
+        log::debug!("RewriteFirstOp::execute: target: {}, rewritten: {:?}", target, rewritten);
+        Ok(vec![Atom::expr([Atom::sym("quote"), rewritten.unwrap_or_else(|| target.clone())])])
+    }
+}
+
+/// Returns all `(= (<head> ...) <rhs>)` rules of the space which left hand
+/// side is an expression with the given head: `(get-definitions &self f)`.
+/// Heads are compared using atom equality, thus `(= f <rhs>)` and rules
+/// with variable head like `(= ($op a) <rhs>)` are not returned. Each rule
+/// is returned as a separate result in the order of adding to the space.
+/// Rules are quoted, otherwise the interpreter would evaluate their left
+/// hand sides: `(quote (= (f $x) (g $x)))`.
+#[derive(Clone, Debug)]
+pub struct GetDefinitionsOp {}
+
+grounded_op!(GetDefinitionsOp, "get-definitions");
+
+impl Grounded for GetDefinitionsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GetDefinitionsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("get-definitions expects two arguments: space and head of the function");
+        let space = args.first().ok_or_else(arg_error)?;
+        let head = args.get(1).ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("get-definitions expects a space as the first argument")?;
+        let space = space.borrow();
+        let atoms = space.as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?;
+
+        let is_definition = |atom: &Atom| match <&[Atom]>::try_from(atom) {
+            Ok([eq, Atom::Expression(lhs), _]) => *eq == EQUAL_SYMBOL && lhs.children().first() == Some(head),
+            _ => false,
+        };
+        Ok(atoms.filter(|atom| is_definition(atom))
+            .map(|rule| Atom::expr([Atom::sym("quote"), rule.clone()]))
+            .collect())
+    }
+}
+
+/// Queries space using the pattern and returns each found bindings set as
+/// an expression of `(<name> <value>)` pairs: `(query-dict &space (rel $a $b))`
+/// returns `((a <value-of-a>) (b <value-of-b>))` for each match. Pairs are
+/// ordered by the first occurrence of the variable in the pattern. Wildcard
+/// variable `$_` is not included. Unbound variable is returned as a value
+/// of its pair.
+#[derive(Clone, Debug)]
+pub struct QueryDictOp {}
+
+grounded_op!(QueryDictOp, "query-dict");
+
+impl Grounded for QueryDictOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for QueryDictOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("query-dict expects two arguments: space and pattern");
+        let space = args.first().ok_or_else(arg_error)?;
+        let pattern = args.get(1).ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("query-dict expects a space as the first argument")?;
+
+        let mut vars: Vec<&VariableAtom> = Vec::new();
+        for var in pattern.iter().filter_type::<&VariableAtom>() {
+            if var.name() != "_" && !vars.contains(&var) {
+                vars.push(var);
+            }
+        }
+        let result = space.borrow().query(pattern).into_iter()
+            .map(|bindings| {
+                let pairs = vars.iter()
+                    .map(|var| {
+                        let value = bindings.resolve(var).unwrap_or_else(|| Atom::Variable((*var).clone()));
+                        // unique variables created by interpreter have #<id> suffix
+                        let name = var.name();
+                        let name = name.split('#').next().unwrap_or(&name);
+                        Atom::expr([Atom::sym(name), value])
+                    })
+                    .collect::<Vec<_>>();
+                Atom::expr(pairs)
+            })
+            .collect();
+        log::debug!("QueryDictOp::execute: pattern: {}, result: {:?}", pattern, result);
+        Ok(result)
+    }
+}
+
+/// Returns `True` when the pattern matches at least one atom of the space
+/// and `False` otherwise. Together with `not` it implements negation as
+/// failure: `(not (provable &space fact))` returns `True` when `fact` cannot
+/// be matched. It relies on the closed-world assumption: everything which
+/// is not present in the space is considered false. Only atoms of the space
+/// are matched, `(= <lhs> <rhs>)` rules are not evaluated.
+#[derive(Clone, Debug)]
+pub struct ProvableOp {}
+
+grounded_op!(ProvableOp, "provable");
+
+impl Grounded for ProvableOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ProvableOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("provable expects two arguments: space and pattern");
+        let space = args.first().ok_or_else(arg_error)?;
+        let pattern = args.get(1).ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("provable expects a space as the first argument")?;
+        let provable = !space.borrow().query(pattern).is_empty();
+        Ok(vec![Atom::gnd(Bool(provable))])
+    }
+}
+
+/// The op atoms that depend on the pkg_mgmt feature
+#[cfg(feature = "pkg_mgmt")]
+pub(crate) mod pkg_mgmt_ops {
+    use super::*;
+
+    /// Provides a way to access [Metta::load_module_at_path] from within MeTTa code
+    #[derive(Clone, Debug)]
+    pub struct RegisterModuleOp {
+        metta: Metta
+    }
+
+    grounded_op!(RegisterModuleOp, "register-module!");
+
+    impl RegisterModuleOp {
+        pub fn new(metta: Metta) -> Self {
+            Self{ metta }
+        }
     }
 
     impl Grounded for RegisterModuleOp {
@@ -1187,6 +2064,46 @@ impl CustomExecute for IndexAtomOp {
     }
 }
 
+/// Returns a copy of the expression where the child with the given index is
+/// replaced by the value: `(update-at 1 x (a b c))` returns `(a x c)`.
+/// Index starts from 0 as in `index-atom`. Returns an error when the index
+/// is out of bounds.
+#[derive(Clone, Debug)]
+pub struct UpdateAtOp {}
+
+grounded_op!(UpdateAtOp, "update-at");
+
+impl Grounded for UpdateAtOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for UpdateAtOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("update-at expects three arguments: index, value and expression");
+        let index = match AsPrimitive::from_atom(args.first().ok_or_else(arg_error)?).as_number() {
+            Some(Number::Integer(index)) => index,
+            _ => return Err(arg_error()),
+        };
+        let value = args.get(1).ok_or_else(arg_error)?;
+        let mut children = TryInto::<&ExpressionAtom>::try_into(args.get(2).ok_or_else(arg_error)?)
+            .map_err(|_| arg_error())?.children().clone();
+        let child = usize::try_from(index).ok().and_then(|index| children.get_mut(index))
+            .ok_or_else(|| ExecError::from("Index is out of bounds"))?;
+        *child = value.clone();
+        Ok(vec![Atom::expr(children)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SubtractionAtomOp {}
 
@@ -1253,6 +2170,908 @@ impl CustomExecute for SubtractionAtomOp {
     }
 }
 
+/// Reverses the order of the children of an expression. The expression is
+/// treated as a plain tuple, so the head is moved together with the rest of
+/// the children: `(reverse (a b c))` returns `(c b a)`.
+#[derive(Clone, Debug)]
+pub struct ReverseAtomOp {}
+
+grounded_op!(ReverseAtomOp, "reverse");
+
+impl Grounded for ReverseAtomOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ReverseAtomOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("reverse expects single expression atom as an argument");
+        let expr = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?)?;
+
+        let mut atoms = expr.children().clone();
+        atoms.reverse();
+        Ok(vec![Atom::expr(atoms)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Pairs children of two expressions positionally. The result is truncated
+/// to the length of the shorter expression: `(zip (a b c) (1 2))` returns
+/// `((a 1) (b 2))`.
+#[derive(Clone, Debug)]
+pub struct ZipAtomOp {}
+
+grounded_op!(ZipAtomOp, "zip");
+
+impl Grounded for ZipAtomOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ZipAtomOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("zip expects two expression atoms as arguments");
+        let left = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let right = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+
+        let pairs = left.children().iter().zip(right.children().iter())
+            .map(|(l, r)| Atom::expr([l.clone(), r.clone()]))
+            .collect::<Vec<Atom>>();
+        Ok(vec![Atom::expr(pairs)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Checks whether the atom is one of the children of the expression:
+/// `(contains (a b c) b)` returns `True`. Only direct children are checked,
+/// atoms are compared using atom equality. Use [ContainsDeepOp] to search
+/// nested expressions.
+#[derive(Clone, Debug)]
+pub struct ContainsOp {}
+
+grounded_op!(ContainsOp, "contains");
+
+impl Grounded for ContainsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ContainsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("contains expects two arguments: expression and atom");
+        let expr = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(expr.children().contains(atom)))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Checks whether the atom is a child of the expression or a child of any
+/// of its nested expressions: `(contains-deep (a (b (c))) c)` returns `True`.
+/// The expression itself is not compared with the atom.
+#[derive(Clone, Debug)]
+pub struct ContainsDeepOp {}
+
+grounded_op!(ContainsDeepOp, "contains-deep");
+
+impl Grounded for ContainsDeepOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+fn contains_deep(expr: &ExpressionAtom, atom: &Atom) -> bool {
+    expr.children().iter().any(|child| child == atom || match child {
+        Atom::Expression(child) => contains_deep(child, atom),
+        _ => false,
+    })
+}
+
+impl CustomExecute for ContainsDeepOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("contains-deep expects two arguments: expression and atom");
+        let expr = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(contains_deep(expr, atom)))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Looks up the value by the key in the expression of `(key value)` pairs:
+/// `(assoc b ((a 1) (b 2)))` returns `2`. Keys are compared using atom
+/// equality, the first matching pair is used. Returns no results when key
+/// is absent and error when one of the children is not a pair.
+#[derive(Clone, Debug)]
+pub struct AssocAtomOp {}
+
+grounded_op!(AssocAtomOp, "assoc");
+
+impl Grounded for AssocAtomOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssocAtomOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("assoc expects two arguments: key atom and expression of (key value) pairs");
+        let key = args.first().ok_or_else(arg_error)?;
+        let pairs = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+
+        let mut value = None;
+        for pair in pairs.children() {
+            match pair {
+                Atom::Expression(pair) if pair.children().len() == 2 => {
+                    if value.is_none() && pair.children()[0] == *key {
+                        value = Some(pair.children()[1].clone());
+                    }
+                },
+                _ => return Err(ExecError::from(format!("assoc expects (key value) pair, found: {}", pair))),
+            }
+        }
+        Ok(value.into_iter().collect())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns new expression of `(key value)` pairs where value of the pair
+/// with the given key is replaced: `(assoc-set b 3 ((a 1) (b 2)))` returns
+/// `((a 1) (b 3))`. When key is absent the new pair is appended to the end.
+/// Keys are compared using atom equality. Only the first matching pair is
+/// replaced which is consistent with `assoc` returning the value of the first
+/// matching pair; the rest of duplicates are kept as is. Returns error when
+/// one of the children is not a pair.
+#[derive(Clone, Debug)]
+pub struct AssocSetAtomOp {}
+
+grounded_op!(AssocSetAtomOp, "assoc-set");
+
+impl Grounded for AssocSetAtomOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssocSetAtomOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("assoc-set expects three arguments: key atom, value atom and expression of (key value) pairs");
+        let key = args.first().ok_or_else(arg_error)?;
+        let value = args.get(1).ok_or_else(arg_error)?;
+        let pairs = TryInto::<&ExpressionAtom>::try_into(args.get(2).ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+
+        let mut is_set = false;
+        let mut result = Vec::with_capacity(pairs.children().len() + 1);
+        for pair in pairs.children() {
+            match pair {
+                Atom::Expression(pair_expr) if pair_expr.children().len() == 2 => {
+                    if !is_set && pair_expr.children()[0] == *key {
+                        result.push(Atom::expr([key.clone(), value.clone()]));
+                        is_set = true;
+                    } else {
+                        result.push(pair.clone());
+                    }
+                },
+                _ => return Err(ExecError::from(format!("assoc-set expects (key value) pair, found: {}", pair))),
+            }
+        }
+        if !is_set {
+            result.push(Atom::expr([key.clone(), value.clone()]));
+        }
+        Ok(vec![Atom::expr(result)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Constructs an expression from its arguments: `(make-expr point (+ 0 1) 2)`
+/// returns `(quote (point 1 2))`. Arguments are evaluated before the
+/// expression is constructed. The result is wrapped into `quote` to prevent
+/// further evaluation, thus the head is not called even when it has a
+/// reduction rule.
+#[derive(Clone, Debug)]
+pub struct MakeExprOp {}
+
+grounded_op!(MakeExprOp, "make-expr");
+
+impl Grounded for MakeExprOp {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_UNDEFINED
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MakeExprOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        Ok(vec![Atom::expr([Atom::sym("quote"), Atom::expr(args)])])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns number of arguments of the expression, i.e. number of children
+/// after the head: `(arity (f a b))` returns `2`. Argument is not evaluated.
+/// Empty expression has zero arity.
+#[derive(Clone, Debug)]
+pub struct ArityOp {}
+
+grounded_op!(ArityOp, "arity");
+
+impl Grounded for ArityOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ArityOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("arity expects expression as an argument");
+        let expr = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        Ok(vec![Atom::gnd(Number::Integer(expr.arity() as i64))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Compares two expressions as multisets of their children: `(set-equal (a b c)
+/// (c a b))` returns `True`. The head is not distinguished from the other
+/// children, thus `(set-equal (f a g) (g f a))` returns `True` as well while
+/// `(set-equal (f a b) (g a b))` returns `False`. Each child should be
+/// present in both expressions the same number of times.
+#[derive(Clone, Debug)]
+pub struct SetEqualOp {}
+
+grounded_op!(SetEqualOp, "set-equal");
+
+impl Grounded for SetEqualOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SetEqualOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-equal expects two expressions as arguments");
+        let a = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let b = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+
+        let mut rest: Vec<&Atom> = b.children().iter().collect();
+        let equal = a.children().len() == rest.len()
+            && a.children().iter().all(|child| {
+                match rest.iter().position(|other| *other == child) {
+                    Some(i) => { rest.swap_remove(i); true },
+                    None => false,
+                }
+            });
+        Ok(vec![Atom::gnd(Bool(equal))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Replaces each occurrence of the first argument by the second one inside
+/// the third argument: `(substitute a b (f a (g a)))` returns `(f b (g b))`.
+/// Subterms are compared using `Atom::eq`, thus a variable is replaced only
+/// when exactly the same variable is found, it is not matched with other atoms.
+/// Replaced subterm is not traversed further.
+#[derive(Clone, Debug)]
+pub struct SubstituteOp {}
+
+grounded_op!(SubstituteOp, "substitute");
+
+impl Grounded for SubstituteOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SubstituteOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("substitute expects three arguments: atom to replace, replacement and term");
+        let old = args.first().ok_or_else(arg_error)?;
+        let new = args.get(1).ok_or_else(arg_error)?;
+        let term = args.get(2).ok_or_else(arg_error)?;
+        Ok(vec![substitute(old, new, term.clone())])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+fn substitute(old: &Atom, new: &Atom, term: Atom) -> Atom {
+    if term == *old {
+        new.clone()
+    } else {
+        match term {
+            Atom::Expression(expr) => Atom::expr(expr.into_children().into_iter()
+                .map(|child| substitute(old, new, child)).collect::<Vec<Atom>>()),
+            _ => term,
+        }
+    }
+}
+
+/// Replaces each occurrence of the variable passed as the first argument
+/// inside the template passed as the third argument by the value passed as
+/// the second argument: `(replace-all $x a (f $x (g $x)))` returns
+/// `(f a (g a))`. Unlike [SubstituteOp] the replacement is performed by
+/// applying bindings of the variable to the template. Template which doesn't
+/// contain the variable is returned unchanged. Value which contains the
+/// variable itself leads to the error. Arguments are not evaluated.
+#[derive(Clone, Debug)]
+pub struct ReplaceAllOp {}
+
+grounded_op!(ReplaceAllOp, "replace-all");
+
+impl Grounded for ReplaceAllOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_VARIABLE, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ReplaceAllOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("replace-all expects three arguments: variable, value and template");
+        let var = TryInto::<&VariableAtom>::try_into(args.first().ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let value = args.get(1).ok_or_else(arg_error)?;
+        let template = args.get(2).ok_or_else(arg_error)?;
+        let loop_error = || ExecError::from("replace-all cannot replace variable by value which contains this variable");
+        if value.iter().filter_type::<&VariableAtom>().any(|v| v == var) {
+            return Err(loop_error());
+        }
+        let bindings = Bindings::new().add_var_binding_v2(var, value).map_err(|_| loop_error())?;
+        Ok(vec![matcher::apply_bindings_to_atom_move(template.clone(), &bindings)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns hash of the atom as a number: `(atom-hash (f 1 2))`. Equal atoms
+/// have equal hashes. Variables are hashed by the order of their first
+/// occurrence, thus alpha equivalent atoms like `(f $x $y $x)` and
+/// `(f $a $b $a)` have equal hashes as well. Grounded atoms are hashed using
+/// their text representation. Argument is not evaluated.
+#[derive(Clone, Debug)]
+pub struct AtomHashOp {}
+
+grounded_op!(AtomHashOp, "atom-hash");
+
+impl Grounded for AtomHashOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AtomHashOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("atom-hash expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+        let mut hasher = DefaultHasher::new();
+        hash_atom(atom, &mut Vec::new(), &mut hasher);
+        Ok(vec![Atom::gnd(Number::Integer(hasher.finish() as i64))])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+fn hash_atom<'a>(atom: &'a Atom, vars: &mut Vec<&'a VariableAtom>, hasher: &mut DefaultHasher) {
+    match atom {
+        Atom::Symbol(sym) => {
+            0u8.hash(hasher);
+            sym.name().hash(hasher);
+        },
+        Atom::Variable(var) => {
+            1u8.hash(hasher);
+            let index = match vars.iter().position(|v| *v == var) {
+                Some(index) => index,
+                None => {
+                    vars.push(var);
+                    vars.len() - 1
+                },
+            };
+            index.hash(hasher);
+        },
+        Atom::Grounded(gnd) => {
+            2u8.hash(hasher);
+            gnd.to_string().hash(hasher);
+        },
+        Atom::Expression(expr) => {
+            3u8.hash(hasher);
+            expr.children().len().hash(hasher);
+            for child in expr.children() {
+                hash_atom(child, vars, hasher);
+            }
+        },
+    }
+}
+
+/// Operation created by `partial`. Keeps an operation and its leading
+/// arguments. When it is called the call expression of the operation with
+/// the leading arguments followed by the actual arguments is returned and
+/// evaluated further, thus the arity and types of the arguments are checked
+/// when the resulting call is evaluated.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PartialAtom {
+    op: Atom,
+    args: Vec<Atom>,
+}
+
+impl Display for PartialAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(partial {}", self.op)?;
+        self.args.iter().try_for_each(|arg| write!(f, " {}", arg))?;
+        write!(f, ")")
+    }
+}
+
+impl Grounded for PartialAtom {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_UNDEFINED
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for PartialAtom {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let call = std::iter::once(self.op.clone())
+            .chain(self.args.iter().cloned())
+            .chain(args.iter().cloned())
+            .collect::<Vec<Atom>>();
+        Ok(vec![Atom::expr(call)])
+    }
+}
+
+/// Captures an operation and its leading arguments into a new operation:
+/// `((partial + 10) 5)` returns `15`.
+#[derive(Clone, Debug)]
+pub struct PartialOp {}
+
+grounded_op!(PartialOp, "partial");
+
+impl Grounded for PartialOp {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_UNDEFINED
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for PartialOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("partial expects operation and leading arguments");
+        let (op, args) = args.split_first().ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(PartialAtom{ op: op.clone(), args: args.to_vec() })])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Operation created by `compose`. Keeps two operations `f` and `g`. When it
+/// is called the expression `(f (g args...))` is returned and evaluated
+/// further, thus all actual arguments are passed to `g`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ComposedAtom {
+    f: Atom,
+    g: Atom,
+}
+
+impl Display for ComposedAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(compose {} {})", self.f, self.g)
+    }
+}
+
+impl Grounded for ComposedAtom {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_UNDEFINED
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ComposedAtom {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let inner = std::iter::once(self.g.clone())
+            .chain(args.iter().cloned())
+            .collect::<Vec<Atom>>();
+        Ok(vec![Atom::expr([self.f.clone(), Atom::expr(inner)])])
+    }
+}
+
+/// Composes two operations into a new operation which applies the second
+/// operation and then the first one to its result: `((compose f g) x)`
+/// returns `(f (g x))`.
+#[derive(Clone, Debug)]
+pub struct ComposeOp {}
+
+grounded_op!(ComposeOp, "compose");
+
+impl Grounded for ComposeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ComposeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("compose expects two operations as arguments");
+        let f = args.first().ok_or_else(arg_error)?;
+        let g = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(ComposedAtom{ f: f.clone(), g: g.clone() })])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns the head of the expression without evaluating it:
+/// `(get-head (f a b))` returns `f`. Returns an error when the argument is
+/// not an expression or an empty expression.
+#[derive(Clone, Debug)]
+pub struct GetHeadOp {}
+
+grounded_op!(GetHeadOp, "get-head");
+
+impl Grounded for GetHeadOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GetHeadOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("get-head expects non-empty expression as an argument");
+        match args.first() {
+            Some(Atom::Expression(expr)) => {
+                let head = expr.children().first().ok_or_else(arg_error)?;
+                Ok(vec![head.clone()])
+            },
+            _ => Err(arg_error()),
+        }
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns the children of the expression except the head without
+/// evaluating it: `(get-children (f a b))` returns `(a b)`. Children of an
+/// empty expression is an empty expression. Returns an error when the
+/// argument is not an expression.
+#[derive(Clone, Debug)]
+pub struct GetChildrenOp {}
+
+grounded_op!(GetChildrenOp, "get-children");
+
+impl Grounded for GetChildrenOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GetChildrenOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("get-children expects expression as an argument");
+        match args.first() {
+            Some(Atom::Expression(expr)) => {
+                let children = expr.children().iter().skip(1).cloned().collect::<Vec<_>>();
+                Ok(vec![Atom::expr(children)])
+            },
+            _ => Err(arg_error()),
+        }
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns a copy of the argument which doesn't share storage with it:
+/// `(deep-copy (f 1 "a"))` returns `(quote (f 1 "a"))`. Atoms do not share
+/// structure, thus the copy is made by `Atom::clone` which allocates new
+/// storage for expressions and symbols and clones grounded values. Grounded
+/// values which keep a reference to a shared object by design, like spaces
+/// and states, still refer to the same object after copying. Argument is not
+/// evaluated and the copy is wrapped into `quote` to prevent its further
+/// evaluation.
+#[derive(Clone, Debug)]
+pub struct DeepCopyOp {}
+
+grounded_op!(DeepCopyOp, "deep-copy");
+
+impl Grounded for DeepCopyOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for DeepCopyOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("deep-copy expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+        Ok(vec![Atom::expr([Atom::sym("quote"), atom.clone()])])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns an expression of distinct variables of the argument in the order
+/// of their first occurrence: `(get-variables (f $x (g $y $x)))` returns
+/// `($x $y)`. Argument is not evaluated. Returns an empty expression when
+/// atom contains no variables.
+#[derive(Clone, Debug)]
+pub struct GetVariablesOp {}
+
+grounded_op!(GetVariablesOp, "get-variables");
+
+impl Grounded for GetVariablesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GetVariablesOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("get-variables expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        let mut vars: Vec<Atom> = Vec::new();
+        for var in atom.iter().filter_type::<&VariableAtom>() {
+            let var = Atom::Variable(var.clone());
+            if !vars.contains(&var) {
+                vars.push(var);
+            }
+        }
+        Ok(vec![Atom::expr(vars)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns sliding windows of the given size over the children of the
+/// expression: `(windows 2 (a b c d))` returns `((a b) (b c) (c d))`. When
+/// size is greater than the number of children the result is an empty
+/// expression. Size should be a positive integer number.
+#[derive(Clone, Debug)]
+pub struct WindowsOp {}
+
+grounded_op!(WindowsOp, "windows");
+
+impl Grounded for WindowsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for WindowsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("windows expects positive integer size and expression as arguments");
+        let size = match AsPrimitive::from_atom(args.first().ok_or_else(arg_error)?).as_number() {
+            Some(Number::Integer(size)) if size > 0 => size as usize,
+            _ => return Err(arg_error()),
+        };
+        let expr = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let windows = expr.children().windows(size)
+            .map(Atom::expr)
+            .collect::<Vec<Atom>>();
+        Ok(vec![Atom::expr(windows)])
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns integer numbers from the first argument (inclusive) to the second
+/// argument (exclusive) as separate results: `(range 1 4)` returns `1`, `2`
+/// and `3`. Optional third argument is a non-zero step, negative step makes
+/// range descending: `(range 3 0 -1)` returns `3`, `2` and `1`. When the
+/// second bound cannot be reached from the first one moving by the step
+/// the range is empty and there are no results.
+#[derive(Clone, Debug)]
+pub struct RangeOp {}
+
+grounded_op!(RangeOp, "range");
+
+impl Grounded for RangeOp {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_UNDEFINED
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RangeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("range expects two or three integer numbers: start, end and optional non-zero step");
+        let int_arg = |atom: &Atom| match AsPrimitive::from_atom(atom).as_number() {
+            Some(Number::Integer(n)) => Ok(n),
+            _ => Err(arg_error()),
+        };
+        let (start, end, step) = match args {
+            [start, end] => (int_arg(start)?, int_arg(end)?, 1),
+            [start, end, step] => (int_arg(start)?, int_arg(end)?, int_arg(step)?),
+            _ => return Err(arg_error()),
+        };
+        if step == 0 {
+            return Err(arg_error());
+        }
+
+        let mut result = Vec::new();
+        let mut next = start;
+        while (step > 0 && next < end) || (step < 0 && next > end) {
+            result.push(Atom::gnd(Number::Integer(next)));
+            next = match next.checked_add(step) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        Ok(result)
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+}
+
+/// Returns bindings of the variables at the moment of the call as an
+/// expression of `($var value)` pairs: `(let $x 5 (current-bindings))`
+/// returns `(($x 5))`. Variable is returned as a symbol with the variable's
+/// name, otherwise interpreter would replace it by its value. Pairs are
+/// sorted by the variable name. Variables created by the interpreter itself
+/// are not included.
+#[derive(Clone, Debug)]
+pub struct CurrentBindingsOp {}
+
+grounded_op!(CurrentBindingsOp, "current-bindings");
+
+impl Grounded for CurrentBindingsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for CurrentBindingsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        self.execute_bindings(args, &Bindings::new())
+    }
+
+    fn execute_bindings(&self, args: &[Atom], bindings: &Bindings) -> Result<Vec<Atom>, ExecError> {
+        if !args.is_empty() {
+            return Err(ExecError::from("current-bindings expects no arguments"));
+        }
+        let mut pairs: Vec<(&VariableAtom, Atom)> = bindings.iter()
+            .filter(|(var, _)| !is_internal_variable(var))
+            .collect();
+        pairs.sort_by_key(|(var, _)| var.name());
+        let pairs = pairs.into_iter()
+            .map(|(var, value)| Atom::expr([Atom::sym(var.to_string()), value]))
+            .collect::<Vec<_>>();
+        Ok(vec![Atom::expr(pairs)])
+    }
+}
+
 /// The internal `non_minimal_only_stdlib` module contains code that is never used by the minimal stdlib
 #[cfg(feature = "old_interpreter")]
 mod non_minimal_only_stdlib {
@@ -1748,8 +3567,32 @@ mod non_minimal_only_stdlib {
 
         let match_op = Atom::gnd(MatchOp{});
         tref.register_token(regex(r"match"), move |_| { match_op.clone() });
-        let bind_op = Atom::gnd(BindOp::new(tokenizer));
+        let rewrite_op = Atom::gnd(RewriteOp{});
+        tref.register_token(regex(r"rewrite"), move |_| { rewrite_op.clone() });
+        let rewrite_first_op = Atom::gnd(RewriteFirstOp{});
+        tref.register_token(regex(r"rewrite-first"), move |_| { rewrite_first_op.clone() });
+        let get_definitions_op = Atom::gnd(GetDefinitionsOp{});
+        tref.register_token(regex(r"get-definitions"), move |_| { get_definitions_op.clone() });
+        let query_dict_op = Atom::gnd(QueryDictOp{});
+        tref.register_token(regex(r"query-dict"), move |_| { query_dict_op.clone() });
+        let provable_op = Atom::gnd(ProvableOp{});
+        tref.register_token(regex(r"provable"), move |_| { provable_op.clone() });
+        let bind_op = Atom::gnd(BindOp::new(tokenizer.clone()));
         tref.register_token(regex(r"bind!"), move |_| { bind_op.clone() });
+        let parse_op = Atom::gnd(ParseOp::new(tokenizer.clone()));
+        tref.register_token(regex(r"parse"), move |_| { parse_op.clone() });
+        let load_string_op = Atom::gnd(LoadStringOp::new(tokenizer));
+        tref.register_token(regex(r"load-string"), move |_| { load_string_op.clone() });
+        let repr_op = Atom::gnd(ReprOp{});
+        tref.register_token(regex(r"repr"), move |_| { repr_op.clone() });
+        let parse_number_op = Atom::gnd(ParseNumberOp{});
+        tref.register_token(regex(r"parse-number"), move |_| { parse_number_op.clone() });
+        let number_to_string_op = Atom::gnd(NumberToStringOp{});
+        tref.register_token(regex(r"number->string"), move |_| { number_to_string_op.clone() });
+        let split_op = Atom::gnd(SplitOp{});
+        tref.register_token(regex(r"split"), move |_| { split_op.clone() });
+        let join_op = Atom::gnd(JoinOp{});
+        tref.register_token(regex(r"join"), move |_| { join_op.clone() });
         let new_space_op = Atom::gnd(NewSpaceOp{});
         tref.register_token(regex(r"new-space"), move |_| { new_space_op.clone() });
         let add_atom_op = Atom::gnd(AddAtomOp{});
@@ -1758,6 +3601,26 @@ mod non_minimal_only_stdlib {
         tref.register_token(regex(r"remove-atom"), move |_| { remove_atom_op.clone() });
         let get_atoms_op = Atom::gnd(GetAtomsOp{});
         tref.register_token(regex(r"get-atoms"), move |_| { get_atoms_op.clone() });
+        let subtract_space_op = Atom::gnd(SubtractSpaceOp{});
+        tref.register_token(regex(r"subtract"), move |_| { subtract_space_op.clone() });
+        let intersect_space_op = Atom::gnd(IntersectSpaceOp{});
+        tref.register_token(regex(r"intersect"), move |_| { intersect_space_op.clone() });
+        let merge_dedup_op = Atom::gnd(MergeDedupOp{});
+        tref.register_token(regex(r"merge-dedup"), move |_| { merge_dedup_op.clone() });
+        let clone_space_op = Atom::gnd(CloneSpaceOp{});
+        tref.register_token(regex(r"clone-space"), move |_| { clone_space_op.clone() });
+        let filter_space_op = Atom::gnd(FilterSpaceOp{});
+        tref.register_token(regex(r"filter-space"), move |_| { filter_space_op.clone() });
+        let rename_symbol_op = Atom::gnd(RenameSymbolOp{});
+        tref.register_token(regex(r"rename-symbol"), move |_| { rename_symbol_op.clone() });
+        let space_stats_op = Atom::gnd(SpaceStatsOp{});
+        tref.register_token(regex(r"space-stats"), move |_| { space_stats_op.clone() });
+        let check_consistent_op = Atom::gnd(CheckConsistentOp{});
+        tref.register_token(regex(r"check-consistent"), move |_| { check_consistent_op.clone() });
+        let space_to_expr_op = Atom::gnd(SpaceToExprOp{});
+        tref.register_token(regex(r"space->expr"), move |_| { space_to_expr_op.clone() });
+        let expr_to_space_op = Atom::gnd(ExprToSpaceOp{});
+        tref.register_token(regex(r"expr->space"), move |_| { expr_to_space_op.clone() });
         let car_atom_op = Atom::gnd(CarAtomOp{});
         tref.register_token(regex(r"car-atom"), move |_| { car_atom_op.clone() });
         let cdr_atom_op = Atom::gnd(CdrAtomOp{});
@@ -1766,6 +3629,8 @@ mod non_minimal_only_stdlib {
         tref.register_token(regex(r"cons-atom"), move |_| { cons_atom_op.clone() });
         let index_atom_op = Atom::gnd(IndexAtomOp{});
         tref.register_token(regex(r"index-atom"), move |_| { index_atom_op.clone() });
+        let update_at_op = Atom::gnd(UpdateAtOp{});
+        tref.register_token(regex(r"update-at"), move |_| { update_at_op.clone() });
         let println_op = Atom::gnd(PrintlnOp{});
         tref.register_token(regex(r"println!"), move |_| { println_op.clone() });
         let format_args_op = Atom::gnd(FormatArgsOp{});
@@ -1800,6 +3665,48 @@ mod non_minimal_only_stdlib {
         tref.register_token(regex(r"intersection-atom"), move |_| { intersection_op.clone() });
         let union_op = Atom::gnd(UnionAtomOp{});
         tref.register_token(regex(r"union-atom"), move |_| { union_op.clone() });
+        let reverse_op = Atom::gnd(ReverseAtomOp{});
+        tref.register_token(regex(r"reverse"), move |_| { reverse_op.clone() });
+        let zip_op = Atom::gnd(ZipAtomOp{});
+        tref.register_token(regex(r"zip"), move |_| { zip_op.clone() });
+        let contains_op = Atom::gnd(ContainsOp{});
+        tref.register_token(regex(r"contains"), move |_| { contains_op.clone() });
+        let contains_deep_op = Atom::gnd(ContainsDeepOp{});
+        tref.register_token(regex(r"contains-deep"), move |_| { contains_deep_op.clone() });
+        let assoc_op = Atom::gnd(AssocAtomOp{});
+        tref.register_token(regex(r"assoc"), move |_| { assoc_op.clone() });
+        let assoc_set_op = Atom::gnd(AssocSetAtomOp{});
+        tref.register_token(regex(r"assoc-set"), move |_| { assoc_set_op.clone() });
+        let make_expr_op = Atom::gnd(MakeExprOp{});
+        tref.register_token(regex(r"make-expr"), move |_| { make_expr_op.clone() });
+        let arity_op = Atom::gnd(ArityOp{});
+        tref.register_token(regex(r"arity"), move |_| { arity_op.clone() });
+        let set_equal_op = Atom::gnd(SetEqualOp{});
+        tref.register_token(regex(r"set-equal"), move |_| { set_equal_op.clone() });
+        let substitute_op = Atom::gnd(SubstituteOp{});
+        tref.register_token(regex(r"substitute"), move |_| { substitute_op.clone() });
+        let replace_all_op = Atom::gnd(ReplaceAllOp{});
+        tref.register_token(regex(r"replace-all"), move |_| { replace_all_op.clone() });
+        let atom_hash_op = Atom::gnd(AtomHashOp{});
+        tref.register_token(regex(r"atom-hash"), move |_| { atom_hash_op.clone() });
+        let partial_op = Atom::gnd(PartialOp{});
+        tref.register_token(regex(r"partial"), move |_| { partial_op.clone() });
+        let compose_op = Atom::gnd(ComposeOp{});
+        tref.register_token(regex(r"compose"), move |_| { compose_op.clone() });
+        let get_head_op = Atom::gnd(GetHeadOp{});
+        tref.register_token(regex(r"get-head"), move |_| { get_head_op.clone() });
+        let get_children_op = Atom::gnd(GetChildrenOp{});
+        tref.register_token(regex(r"get-children"), move |_| { get_children_op.clone() });
+        let deep_copy_op = Atom::gnd(DeepCopyOp{});
+        tref.register_token(regex(r"deep-copy"), move |_| { deep_copy_op.clone() });
+        let get_variables_op = Atom::gnd(GetVariablesOp{});
+        tref.register_token(regex(r"get-variables"), move |_| { get_variables_op.clone() });
+        let windows_op = Atom::gnd(WindowsOp{});
+        tref.register_token(regex(r"windows"), move |_| { windows_op.clone() });
+        let range_op = Atom::gnd(RangeOp{});
+        tref.register_token(regex(r"range"), move |_| { range_op.clone() });
+        let current_bindings_op = Atom::gnd(CurrentBindingsOp{});
+        tref.register_token(regex(r"current-bindings"), move |_| { current_bindings_op.clone() });
 
         #[cfg(feature = "pkg_mgmt")]
         pkg_mgmt_ops::register_pkg_mgmt_tokens(tref, metta);
@@ -1879,6 +3786,12 @@ mod non_minimal_only_stdlib {
         tref.register_token(regex(r">="), move |_| { ge_op.clone() });
         let eq_op = Atom::gnd(EqualOp{});
         tref.register_token(regex(r"=="), move |_| { eq_op.clone() });
+        let approx_equal_op = Atom::gnd(ApproxEqualOp{});
+        tref.register_token(regex(r"approx-equal"), move |_| { approx_equal_op.clone() });
+        let to_float_op = Atom::gnd(ToFloatOp{});
+        tref.register_token(regex(r"to-float"), move |_| { to_float_op.clone() });
+        let to_int_op = Atom::gnd(ToIntOp{});
+        tref.register_token(regex(r"to-int"), move |_| { to_int_op.clone() });
         let and_op = Atom::gnd(AndOp{});
         tref.register_token(regex(r"and"), move |_| { and_op.clone() });
         let or_op = Atom::gnd(OrOp{});
@@ -4,12 +4,19 @@ use crate::metta::*;
 use crate::metta::text::Tokenizer;
 use crate::metta::runner::Metta;
 use crate::metta::types::get_atom_types;
+use crate::metta::normal_form::NormalForm;
+use crate::metta::interpreter_minimal::error_message;
 use crate::common::assert::vec_eq_no_order;
 use crate::common::shared::Shared;
 use crate::metta::runner::stdlib;
 use crate::metta::runner::stdlib::regex;
 
 use std::convert::TryInto;
+use std::rc::Rc;
+use std::cell::RefCell;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
 
 use super::arithmetics::*;
 use super::string::*;
@@ -144,9 +151,57 @@ fn interpret_no_error(space: DynSpace, expr: &Atom) -> Result<Vec<Atom>, String>
 }
 
 fn interpret(space: DynSpace, expr: &Atom) -> Result<Vec<Atom>, String> {
-    let expr = Atom::expr([METTA_SYMBOL, expr.clone(), ATOM_TYPE_UNDEFINED, Atom::gnd(space.clone())]);
-    let result = crate::metta::interpreter::interpret(space, &expr);
-    result
+    let metta = Atom::expr([METTA_SYMBOL, expr.clone(), ATOM_TYPE_UNDEFINED, Atom::gnd(space.clone())]);
+    let mut state = crate::metta::interpreter::interpret_init(space, &metta);
+    while state.has_next() {
+        spend_step().map_err(|step_limit| format!("Step limit {} is exceeded while interpreting {}", step_limit, expr))?;
+        state = crate::metta::interpreter::interpret_step(state);
+    }
+    state.into_result()
+}
+
+/// Number of steps performed by the interpretation and optional limit of
+/// the steps.
+struct StepBudget {
+    steps: usize,
+    limit: Option<usize>,
+}
+
+thread_local! {
+    /// Budgets of the unfinished interpretations started by [interpret_with_budget].
+    /// Each step of [interpret] is charged to all of them, thus steps of the
+    /// nested interpretations started by grounded operations (like
+    /// `collapse` or `case`) are counted and limited as well.
+    static STEP_BUDGETS: RefCell<Vec<StepBudget>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Charges a step to all active budgets. Returns the limit of the budget
+/// which is exhausted if any.
+fn spend_step() -> Result<(), usize> {
+    STEP_BUDGETS.with(|budgets| {
+        let mut budgets = budgets.borrow_mut();
+        if let Some(limit) = budgets.iter().find_map(|budget| budget.limit.filter(|limit| budget.steps >= *limit)) {
+            return Err(limit);
+        }
+        budgets.iter_mut().for_each(|budget| budget.steps += 1);
+        Ok(())
+    })
+}
+
+fn interpret_with_budget(space: DynSpace, expr: &Atom, limit: Option<usize>) -> Result<(Vec<Atom>, usize), String> {
+    STEP_BUDGETS.with(|budgets| budgets.borrow_mut().push(StepBudget{ steps: 0, limit }));
+    let result = interpret(space, expr);
+    let steps = STEP_BUDGETS.with(|budgets| budgets.borrow_mut().pop())
+        .map_or(0, |budget| budget.steps);
+    result.map(|results| (results, steps))
+}
+
+fn interpret_with_limit(space: DynSpace, expr: &Atom, step_limit: usize) -> Result<Vec<Atom>, String> {
+    interpret_with_budget(space, expr, Some(step_limit)).map(|(results, _steps)| results)
+}
+
+fn interpret_counting_steps(space: DynSpace, expr: &Atom) -> Result<(Vec<Atom>, usize), String> {
+    interpret_with_budget(space, expr, None)
 }
 
 fn assert_results_equal(actual: &Vec<Atom>, expected: &Vec<Atom>, atom: &Atom) -> Result<Vec<Atom>, ExecError> {
@@ -158,6 +213,27 @@ fn assert_results_equal(actual: &Vec<Atom>, expected: &Vec<Atom>, atom: &Atom) -
     }
 }
 
+/// Wrapper which compares atoms using alpha equivalence
+#[derive(Debug)]
+struct AlphaEq<'a>(&'a Atom);
+
+impl PartialEq for AlphaEq<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        crate::matcher::atoms_are_equivalent(self.0, other.0)
+    }
+}
+
+fn assert_results_alpha_equal(actual: &[Atom], expected: &[Atom], atom: &Atom) -> Result<Vec<Atom>, ExecError> {
+    log::debug!("assert_results_alpha_equal: actual: {:?}, expected: {:?}, actual atom: {:?}", actual, expected, atom);
+    let report = format!("\nExpected: {:?}\nGot: {:?}", expected, actual);
+    let actual: Vec<AlphaEq> = actual.iter().map(AlphaEq).collect();
+    let expected: Vec<AlphaEq> = expected.iter().map(AlphaEq).collect();
+    match vec_eq_no_order(actual.iter(), expected.iter()) {
+        Ok(()) => unit_result(),
+        Err(diff) => Err(ExecError::Runtime(format!("{}\n{}", report, diff)))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AssertEqualOp {
     space: DynSpace,
@@ -195,6 +271,131 @@ impl CustomExecute for AssertEqualOp {
     }
 }
 
+/// Opposite to [AssertEqualOp]: evaluates both arguments and returns an
+/// error when sets of results are equal.
+#[derive(Clone, Debug)]
+pub struct AssertNotEqualOp {
+    space: DynSpace,
+}
+
+grounded_op!(AssertNotEqualOp, "assert-not-equal");
+
+impl AssertNotEqualOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for AssertNotEqualOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertNotEqualOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertNotEqualOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-not-equal expects two atoms as arguments: actual and unexpected");
+        let actual_atom = args.first().ok_or_else(arg_error)?;
+        let unexpected_atom = args.get(1).ok_or_else(arg_error)?;
+
+        let actual = interpret_no_error(self.space.clone(), actual_atom)?;
+        let unexpected = interpret_no_error(self.space.clone(), unexpected_atom)?;
+
+        match vec_eq_no_order(actual.iter(), unexpected.iter()) {
+            Ok(()) => Err(ExecError::Runtime(format!("\nExpected not equal results\nBoth got: {:?}", actual))),
+            Err(_) => unit_result(),
+        }
+    }
+}
+
+/// The same as [AssertEqualOp] but compares results using alpha equivalence,
+/// i.e. up to the variable renaming.
+#[derive(Clone, Debug)]
+pub struct AssertAlphaEqualOp {
+    space: DynSpace,
+}
+
+grounded_op!(AssertAlphaEqualOp, "assert-alpha-equal");
+
+impl AssertAlphaEqualOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for AssertAlphaEqualOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertAlphaEqualOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertAlphaEqualOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-alpha-equal expects two atoms as arguments: actual and expected");
+        let actual_atom = args.first().ok_or_else(arg_error)?;
+        let expected_atom = args.get(1).ok_or_else(arg_error)?;
+
+        let actual = interpret_no_error(self.space.clone(), actual_atom)?;
+        let expected = interpret_no_error(self.space.clone(), expected_atom)?;
+
+        assert_results_alpha_equal(&actual, &expected, actual_atom)
+    }
+}
+
+/// Fully interprets the first argument and checks it has a single result
+/// which is equal to the second argument up to the variable renaming. The
+/// second argument is not evaluated.
+#[derive(Clone, Debug)]
+pub struct AssertReducesToOp {
+    space: DynSpace,
+}
+
+grounded_op!(AssertReducesToOp, "assert-reduces-to");
+
+impl AssertReducesToOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for AssertReducesToOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertReducesToOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertReducesToOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-reduces-to expects two atoms as arguments: input and expected result");
+        let input = args.first().ok_or_else(arg_error)?;
+        let expected = args.get(1).ok_or_else(arg_error)?;
+
+        let actual = interpret_no_error(self.space.clone(), input)?;
+        match actual.as_slice() {
+            [result] if crate::matcher::atoms_are_equivalent(result, expected) => unit_result(),
+            _ => {
+                let actual = actual.iter().map(|atom| atom.to_string()).collect::<Vec<String>>().join(", ");
+                Err(ExecError::Runtime(format!("\nInput: {}\nExpected: {}\nGot: [{}]", input, expected, actual)))
+            },
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AssertEqualToResultOp {
     space: DynSpace,
@@ -233,22 +434,68 @@ impl CustomExecute for AssertEqualToResultOp {
     }
 }
 
+/// Checks that two spaces contain the same atoms. Atoms are compared as a
+/// multiset: the order is ignored but the number of copies of each atom
+/// should be the same. Error lists all atoms which are present in one space
+/// only.
 #[derive(Clone, Debug)]
-pub struct SuperposeOp {
-    space: DynSpace,
+pub struct AssertSpaceEqualOp {}
+
+grounded_op!(AssertSpaceEqualOp, "assert-space-equal");
+
+impl Grounded for AssertSpaceEqualOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(),
+            rust_type_atom::<DynSpace>(), UNIT_TYPE()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
 }
 
-grounded_op!(SuperposeOp, "superpose");
+impl CustomExecute for AssertSpaceEqualOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertSpaceEqualOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-space-equal expects two spaces as arguments: actual and expected");
+        let actual = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let expected = Atom::as_gnd::<DynSpace>(args.get(1).ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let iter_error = || ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string());
+
+        let actual = actual.borrow();
+        let mut excessive: Vec<&Atom> = actual.as_space().atom_iter().ok_or_else(iter_error)?.collect();
+        let expected = expected.borrow();
+        let mut missed = Vec::new();
+        for atom in expected.as_space().atom_iter().ok_or_else(iter_error)? {
+            match excessive.iter().position(|other| *other == atom) {
+                Some(i) => { excessive.remove(i); },
+                None => missed.push(atom),
+            }
+        }
 
-impl SuperposeOp {
-    fn new(space: DynSpace) -> Self {
-        Self{ space }
+        if missed.is_empty() && excessive.is_empty() {
+            unit_result()
+        } else {
+            let to_string = |atoms: Vec<&Atom>| atoms.iter()
+                .map(|atom| atom.to_string()).collect::<Vec<_>>().join(" ");
+            Err(ExecError::Runtime(format!("\nSpaces are not equal\nMissed atoms: ({})\nExcessive atoms: ({})",
+                to_string(missed), to_string(excessive))))
+        }
     }
 }
 
-impl Grounded for SuperposeOp {
+/// Checks that each child of the first expression is present among children
+/// of the second expression: `(assert-subset (a b) (collapse (f)))`.
+/// Arguments are evaluated before the check. Error lists all children of the
+/// first expression which are missed in the second one.
+#[derive(Clone, Debug)]
+pub struct AssertSubsetOp {}
+
+grounded_op!(AssertSubsetOp, "assert-subset");
+
+impl Grounded for AssertSubsetOp {
     fn type_(&self) -> Atom {
-        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_UNDEFINED])
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED, UNIT_TYPE()])
     }
 
     fn as_execute(&self) -> Option<&dyn CustomExecute> {
@@ -256,41 +503,209 @@ impl Grounded for SuperposeOp {
     }
 }
 
-impl CustomExecute for SuperposeOp {
+impl CustomExecute for AssertSubsetOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("superpose expects single expression as an argument");
-        let atom = args.get(0).ok_or_else(arg_error)?;
-        let expr  = TryInto::<&ExpressionAtom>::try_into(atom).map_err(|_| arg_error())?;
+        log::debug!("AssertSubsetOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-subset expects two expressions as arguments: expected and actual");
+        let expected = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let actual = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+
+        let missed: Vec<String> = expected.children().iter()
+            .filter(|atom| !actual.children().contains(atom))
+            .map(|atom| atom.to_string())
+            .collect();
+        if missed.is_empty() {
+            unit_result()
+        } else {
+            Err(ExecError::Runtime(format!("\nExpected atoms are not found\nMissed atoms: ({})", missed.join(" "))))
+        }
+    }
+}
 
-        if expr.children().is_empty() {
-            Ok(vec![EMPTY_SYMBOL])
+/// Checks that the grounded operation passed as an argument is declared
+/// pure, i.e. its [CustomExecute::is_pure] returns `true`: `(assert-pure +)`.
+/// Fails with an error when the argument is not a grounded operation or the
+/// operation is not declared pure.
+#[derive(Clone, Debug)]
+pub struct AssertPureOp {}
+
+grounded_op!(AssertPureOp, "assert-pure");
+
+impl Grounded for AssertPureOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, UNIT_TYPE()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertPureOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertPureOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-pure expects grounded operation as an argument");
+        let op = match args.first() {
+            Some(Atom::Grounded(op)) => op,
+            _ => return Err(arg_error()),
+        };
+        let executable = op.as_grounded().as_execute().ok_or_else(arg_error)?;
+        if executable.is_pure() {
+            unit_result()
         } else {
-            let mut superposed = Vec::new();
-            for atom in expr.children() {
-                match interpret_no_error(self.space.clone(), atom) {
-                    Ok(results) => { superposed.extend(results); },
-                    Err(message) => { return Err(format!("Error: {}", message).into()) },
-                }
+            Err(ExecError::Runtime(format!("Operation {} is not declared pure", op)))
+        }
+    }
+}
+
+/// Checks that definitions in the space do not depend on themselves:
+/// `(assert-acyclic &kb)`. Rule `(= (f ...) <rhs>)` or `(= f <rhs>)` makes
+/// `f` dependent on each symbol of `<rhs>` which is a head of another rule.
+/// Fails with an error listing the first found cycle of dependencies, e.g.
+/// `even -> odd -> even`. The check is conservative: recursive definitions
+/// which always terminate are reported as well.
+#[derive(Clone, Debug)]
+pub struct AssertAcyclicOp {}
+
+grounded_op!(AssertAcyclicOp, "assert-acyclic");
+
+impl Grounded for AssertAcyclicOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), UNIT_TYPE()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+fn find_dependency_cycle<'a>(graph: &[(&'a Atom, Vec<&'a Atom>)]) -> Option<Vec<&'a Atom>> {
+    fn visit<'a>(node: &'a Atom, graph: &[(&'a Atom, Vec<&'a Atom>)],
+        path: &mut Vec<&'a Atom>, visited: &mut Vec<&'a Atom>) -> Option<Vec<&'a Atom>> {
+        if let Some(start) = path.iter().position(|prev| *prev == node) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(node);
+            return Some(cycle);
+        }
+        if visited.contains(&node) {
+            return None;
+        }
+        path.push(node);
+        let deps = graph.iter().find(|(head, _)| *head == node).map(|(_, deps)| deps.as_slice());
+        for dep in deps.unwrap_or_default() {
+            if let Some(cycle) = visit(dep, graph, path, visited) {
+                return Some(cycle);
             }
-            Ok(superposed)
+        }
+        path.pop();
+        visited.push(node);
+        None
+    }
+
+    let mut visited = Vec::new();
+    graph.iter().find_map(|(head, _)| visit(head, graph, &mut Vec::new(), &mut visited))
+}
+
+impl CustomExecute for AssertAcyclicOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertAcyclicOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-acyclic expects single space as an argument");
+        let space = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let space = space.borrow();
+        let atoms = space.as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?;
+
+        let mut graph: Vec<(&Atom, Vec<&Atom>)> = Vec::new();
+        for atom in atoms {
+            let (lhs, rhs) = match <&[Atom]>::try_from(atom) {
+                Ok([eq, lhs, rhs]) if *eq == EQUAL_SYMBOL => (lhs, rhs),
+                _ => continue,
+            };
+            let head = match lhs {
+                Atom::Symbol(_) => lhs,
+                Atom::Expression(expr) => match expr.children().first() {
+                    Some(head @ Atom::Symbol(_)) => head,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let deps = rhs.iter().filter(|atom| matches!(atom, Atom::Symbol(_)));
+            match graph.iter_mut().find(|(prev, _)| *prev == head) {
+                Some((_, prev_deps)) => prev_deps.extend(deps),
+                None => graph.push((head, deps.collect())),
+            }
+        }
+
+        match find_dependency_cycle(&graph) {
+            None => unit_result(),
+            Some(cycle) => {
+                let cycle = cycle.iter().map(|head| head.to_string()).collect::<Vec<_>>();
+                Err(ExecError::Runtime(format!("Cyclic definitions: {}", cycle.join(" -> "))))
+            },
         }
     }
 }
 
+/// Queries the space and checks that at least one of the results binds the
+/// variable to the expected value: `(assert-binding &kb (rel $x foo) $x bar)`.
+/// Values are compared using atom equality. Fails with an error listing the
+/// values found when none of them is equal to the expected one, or when the
+/// variable is not bound by any result.
 #[derive(Clone, Debug)]
-pub struct CollapseOp {
+pub struct AssertBindingOp {}
+
+grounded_op!(AssertBindingOp, "assert-binding");
+
+impl Grounded for AssertBindingOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_VARIABLE, ATOM_TYPE_ATOM, UNIT_TYPE()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertBindingOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertBindingOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-binding expects space, query, variable and expected value as arguments");
+        let space = Atom::as_gnd::<DynSpace>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let query = args.get(1).ok_or_else(arg_error)?;
+        let var = TryInto::<&VariableAtom>::try_into(args.get(2).ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let expected = args.get(3).ok_or_else(arg_error)?;
+
+        let values: Vec<Atom> = space.borrow().query(query).into_iter()
+            .filter_map(|bindings| bindings.resolve(var))
+            .filter(|value| !matches!(value, Atom::Variable(_)))
+            .collect();
+        if values.contains(expected) {
+            unit_result()
+        } else if values.is_empty() {
+            Err(ExecError::Runtime(format!("Variable {} is not bound by query {}", var, query)))
+        } else {
+            Err(ExecError::Runtime(format!("\nExpected binding: {} = {}\nGot: {:?}", var, expected, values)))
+        }
+    }
+}
+
+/// Succeeds returning no results when interpretation of the argument fails.
+/// Interpretation is considered failed when interpreter returns an error or
+/// at least one of the results is an `Error` expression.
+#[derive(Clone, Debug)]
+pub struct ExpectErrorOp {
     space: DynSpace,
 }
 
-grounded_op!(CollapseOp, "collapse");
+grounded_op!(ExpectErrorOp, "expect-error");
 
-impl CollapseOp {
+impl ExpectErrorOp {
     pub fn new(space: DynSpace) -> Self {
         Self{ space }
     }
 }
 
-impl Grounded for CollapseOp {
+impl Grounded for ExpectErrorOp {
     fn type_(&self) -> Atom {
         Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
     }
@@ -300,33 +715,87 @@ impl Grounded for CollapseOp {
     }
 }
 
-impl CustomExecute for CollapseOp {
+impl CustomExecute for ExpectErrorOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("collapse expects single executable atom as an argument");
-        let atom = args.get(0).ok_or_else(arg_error)?;
+        log::debug!("ExpectErrorOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("expect-error expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        match interpret(self.space.clone(), atom) {
+            Err(_) => Ok(vec![]),
+            Ok(results) if results.iter().any(atom_is_error) => Ok(vec![]),
+            Ok(results) => Err(ExecError::Runtime(format!("\nExpected error\nGot: {:?}", results))),
+        }
+    }
+}
 
-        // TODO: Calling interpreter inside the operation is not too good
-        // Could it be done via returning atom for the further interpretation?
-        let result = interpret_no_error(self.space.clone(), atom)?;
+/// Same as [ExpectErrorOp] but additionally checks that the message of the
+/// error contains the string passed as the second argument:
+/// `(assert-throws (+ 1 "a") "Number")`. When interpretation returns `Error`
+/// expressions it is enough for one of them to have a matching message.
+#[derive(Clone, Debug)]
+pub struct AssertThrowsOp {
+    space: DynSpace,
+}
 
-        Ok(vec![Atom::expr(result)])
+grounded_op!(AssertThrowsOp, "assert-throws");
+
+impl AssertThrowsOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for AssertThrowsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_STRING, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertThrowsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertThrowsOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-throws expects atom and string as arguments");
+        let atom = args.first().ok_or_else(arg_error)?;
+        let pattern = Atom::as_gnd::<Str>(args.get(1).ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let pattern = pattern.as_str();
+
+        let messages = match interpret(self.space.clone(), atom) {
+            Err(message) => vec![message],
+            Ok(results) if results.iter().any(atom_is_error) => results.iter()
+                .filter(|result| atom_is_error(result))
+                .map(error_message)
+                .collect(),
+            Ok(results) => return Err(ExecError::Runtime(format!("\nExpected error containing {:?}\nGot: {:?}", pattern, results))),
+        };
+        if messages.iter().any(|message| message.contains(pattern)) {
+            Ok(vec![])
+        } else {
+            Err(ExecError::Runtime(format!("\nExpected error containing {:?}\nGot errors: {:?}", pattern, messages)))
+        }
     }
 }
 
+/// Evaluates the argument and returns its result when there is exactly one
+/// result. Otherwise fails with an error reporting the number of results.
 #[derive(Clone, Debug)]
-pub struct CaptureOp {
+pub struct AssertDeterministicOp {
     space: DynSpace,
 }
 
-grounded_op!(CaptureOp, "capture");
+grounded_op!(AssertDeterministicOp, "assert-deterministic");
 
-impl CaptureOp {
+impl AssertDeterministicOp {
     pub fn new(space: DynSpace) -> Self {
         Self{ space }
     }
 }
 
-impl Grounded for CaptureOp {
+impl Grounded for AssertDeterministicOp {
     fn type_(&self) -> Atom {
         Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
     }
@@ -336,30 +805,40 @@ impl Grounded for CaptureOp {
     }
 }
 
-impl CustomExecute for CaptureOp {
+impl CustomExecute for AssertDeterministicOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("capture expects one argument");
-        let atom = args.get(0).ok_or_else(arg_error)?;
-        interpret(self.space.clone(), &atom).map_err(|e| ExecError::from(e))
+        log::debug!("AssertDeterministicOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-deterministic expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        let results = interpret(self.space.clone(), atom)?;
+        match results.len() {
+            1 => Ok(results),
+            n => Err(ExecError::Runtime(format!("\nExpected single result\nGot {} results: {:?}", n, results))),
+        }
     }
 }
 
+/// Evaluates the argument and then evaluates each of its results again.
+/// Succeeds when the second evaluation returns the result unchanged, i.e.
+/// each result is a normal form: `(assert-idempotent (plus (S Z) Z))`.
+/// Otherwise fails with an error reporting the result and its evaluation.
 #[derive(Clone, Debug)]
-pub struct CaseOp {
+pub struct AssertIdempotentOp {
     space: DynSpace,
 }
 
-grounded_op!(CaseOp, "case");
+grounded_op!(AssertIdempotentOp, "assert-idempotent");
 
-impl CaseOp {
+impl AssertIdempotentOp {
     pub fn new(space: DynSpace) -> Self {
         Self{ space }
     }
 }
 
-impl Grounded for CaseOp {
+impl Grounded for AssertIdempotentOp {
     fn type_(&self) -> Atom {
-        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM])
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, UNIT_TYPE()])
     }
 
     fn as_execute(&self) -> Option<&dyn CustomExecute> {
@@ -367,241 +846,2253 @@ impl Grounded for CaseOp {
     }
 }
 
-impl CustomExecute for CaseOp {
+impl CustomExecute for AssertIdempotentOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
-        let arg_error = || ExecError::from("case expects two arguments: atom and expression of cases");
-        let cases = args.get(1).ok_or_else(arg_error)?;
-        let atom = args.get(0).ok_or_else(arg_error)?;
-        log::debug!("CaseOp::execute: atom: {}, cases: {:?}", atom, cases);
+        log::debug!("AssertIdempotentOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-idempotent expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        for result in interpret(self.space.clone(), atom)? {
+            let second = interpret(self.space.clone(), &result)?;
+            if second != [result.clone()] {
+                return Err(ExecError::Runtime(format!("\nExpected result to be unchanged: {}\nGot: {:?}", result, second)));
+            }
+        }
+        unit_result()
+    }
+}
 
-        let switch = |interpreted: Atom| -> Atom {
-            Atom::expr([sym!("switch"), interpreted, cases.clone()])
-        };
+/// Reduces the argument to the normal form exploring all rewriting orders
+/// and checks that all of them lead to the same normal form (up to variable
+/// renaming): `(assert-confluent (plus (S Z) (S Z)))`. Otherwise fails with
+/// an error listing the distinct normal forms. Reduction is performed by
+/// [interpret_normal_form](crate::metta::normal_form::interpret_normal_form).
+#[derive(Clone, Debug)]
+pub struct AssertConfluentOp {
+    space: DynSpace,
+}
 
-        // Interpreting argument inside CaseOp is required because otherwise `Empty` result
-        // calculated inside interpreter cuts the whole branch of the interpretation. Also we
-        // cannot use `unify` in a unit test because after interpreting `(chain... (chain
-        // (metta (unify ...) Atom <space>)) ...)` `chain` executes `unify` and also gets
-        // `Empty` even if we have `Atom` as a resulting type. It can be solved by different ways.
-        // One way is to invent new type `EmptyType` (type of the `Empty` atom) and use this type
-        // in a function to allow `Empty` atoms as an input. `EmptyType` type should not be
-        // casted to the `%Undefined%` thus one cannot pass `Empty` to the function which accepts
-        // `%Undefined%`. Another way is to introduce "call" level. Thus if function called
-        // returned the result to the `chain` it should stop reducing it and insert it into the
-        // last argument.
-        let results = interpret(self.space.clone(), atom);
-        log::debug!("CaseOp::execute: atom results: {:?}", results);
-        let results = match results {
-            Ok(results) if results.is_empty() =>
-                vec![switch(EMPTY_SYMBOL)],
-            Ok(results) =>
-                results.into_iter().map(|atom| switch(atom)).collect(),
-            Err(err) => vec![Atom::expr([ERROR_SYMBOL, atom.clone(), Atom::sym(err)])],
-        };
-        Ok(results)
+grounded_op!(AssertConfluentOp, "assert-confluent");
+
+impl AssertConfluentOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
     }
 }
 
-//TODO: The additional arguments are a temporary hack on account of the way the operation atoms store references
-// to the runner & module state.  https://github.com/trueagi-io/hyperon-experimental/issues/410
-pub fn register_common_tokens(tref: &mut Tokenizer, _tokenizer: Shared<Tokenizer>, space: &DynSpace, metta: &Metta) {
+impl Grounded for AssertConfluentOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, UNIT_TYPE()])
+    }
 
-    let get_type_op = Atom::gnd(GetTypeOp::new(space.clone()));
-    tref.register_token(regex(r"get-type"), move |_| { get_type_op.clone() });
-    let get_type_space_op = Atom::gnd(stdlib::GetTypeSpaceOp{});
-    tref.register_token(regex(r"get-type-space"), move |_| { get_type_space_op.clone() });
-    let get_meta_type_op = Atom::gnd(stdlib::GetMetaTypeOp{});
-    tref.register_token(regex(r"get-metatype"), move |_| { get_meta_type_op.clone() });
-    let is_equivalent = Atom::gnd(IfEqualOp{});
-    tref.register_token(regex(r"if-equal"), move |_| { is_equivalent.clone() });
-    let new_space_op = Atom::gnd(stdlib::NewSpaceOp{});
-    tref.register_token(regex(r"new-space"), move |_| { new_space_op.clone() });
-    let add_atom_op = Atom::gnd(stdlib::AddAtomOp{});
-    tref.register_token(regex(r"add-atom"), move |_| { add_atom_op.clone() });
-    let remove_atom_op = Atom::gnd(stdlib::RemoveAtomOp{});
-    tref.register_token(regex(r"remove-atom"), move |_| { remove_atom_op.clone() });
-    let get_atoms_op = Atom::gnd(stdlib::GetAtomsOp{});
-    tref.register_token(regex(r"get-atoms"), move |_| { get_atoms_op.clone() });
-    let new_state_op = Atom::gnd(stdlib::NewStateOp{});
-    tref.register_token(regex(r"new-state"), move |_| { new_state_op.clone() });
-    let change_state_op = Atom::gnd(stdlib::ChangeStateOp{});
-    tref.register_token(regex(r"change-state!"), move |_| { change_state_op.clone() });
-    let get_state_op = Atom::gnd(stdlib::GetStateOp{});
-    tref.register_token(regex(r"get-state"), move |_| { get_state_op.clone() });
-    let nop_op = Atom::gnd(stdlib::NopOp{});
-    tref.register_token(regex(r"nop"), move |_| { nop_op.clone() });
-    let match_op = Atom::gnd(stdlib::MatchOp{});
-    tref.register_token(regex(r"match"), move |_| { match_op.clone() });
-    let index_atom_op = Atom::gnd(stdlib::IndexAtomOp{});
-    tref.register_token(regex(r"index-atom"), move |_| { index_atom_op.clone() });
-    let mod_space_op = Atom::gnd(stdlib::ModSpaceOp::new(metta.clone()));
-    tref.register_token(regex(r"mod-space!"), move |_| { mod_space_op.clone() });
-    let print_mods_op = Atom::gnd(stdlib::PrintModsOp::new(metta.clone()));
-    tref.register_token(regex(r"print-mods!"), move |_| { print_mods_op.clone() });
-    let unique_op = Atom::gnd(stdlib::UniqueAtomOp{});
-    tref.register_token(regex(r"unique-atom"), move |_| { unique_op.clone() });
-    let subtraction_op = Atom::gnd(stdlib::SubtractionAtomOp{});
-    tref.register_token(regex(r"subtraction-atom"), move |_| { subtraction_op.clone() });
-    let intersection_op = Atom::gnd(stdlib::IntersectionAtomOp{});
-    tref.register_token(regex(r"intersection-atom"), move |_| { intersection_op.clone() });
-    let union_op = Atom::gnd(stdlib::UnionAtomOp{});
-    tref.register_token(regex(r"union-atom"), move |_| { union_op.clone() });
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
 
-    #[cfg(feature = "pkg_mgmt")]
-    stdlib::pkg_mgmt_ops::register_pkg_mgmt_tokens(tref, metta);
+impl CustomExecute for AssertConfluentOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertConfluentOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-confluent expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        match crate::metta::normal_form::interpret_normal_form(&self.space, atom)? {
+            NormalForm::Unique(_) => unit_result(),
+            NormalForm::NotConfluent(forms) =>
+                Err(ExecError::Runtime(format!("\nExpected unique normal form\nGot: {:?}", forms))),
+        }
+    }
 }
 
-//TODO: The additional arguments are a temporary hack on account of the way the operation atoms store references
-// to the runner & module state.  https://github.com/trueagi-io/hyperon-experimental/issues/410
-pub fn register_runner_tokens(tref: &mut Tokenizer, tokenizer: Shared<Tokenizer>, space: &DynSpace, metta: &Metta) {
+/// Evaluates the first argument performing at most the number of
+/// interpreter steps passed as the second argument. Steps of the nested
+/// evaluations started by grounded operations (like `collapse`) are counted
+/// as well. Returns results of the evaluation when it is finished within the
+/// limit, otherwise fails with an error: `(assert-terminates (loop) 1000)`.
+#[derive(Clone, Debug)]
+pub struct AssertTerminatesOp {
+    space: DynSpace,
+}
 
-    let assert_equal_op = Atom::gnd(AssertEqualOp::new(space.clone()));
-    tref.register_token(regex(r"assertEqual"), move |_| { assert_equal_op.clone() });
-    let assert_equal_to_result_op = Atom::gnd(AssertEqualToResultOp::new(space.clone()));
-    tref.register_token(regex(r"assertEqualToResult"), move |_| { assert_equal_to_result_op.clone() });
-    let superpose_op = Atom::gnd(SuperposeOp::new(space.clone()));
-    tref.register_token(regex(r"superpose"), move |_| { superpose_op.clone() });
-    let collapse_op = Atom::gnd(CollapseOp::new(space.clone()));
-    tref.register_token(regex(r"collapse"), move |_| { collapse_op.clone() });
-    let case_op = Atom::gnd(CaseOp::new(space.clone()));
-    tref.register_token(regex(r"case"), move |_| { case_op.clone() });
-    let capture_op = Atom::gnd(CaptureOp::new(space.clone()));
-    tref.register_token(regex(r"capture"), move |_| { capture_op.clone() });
-    let pragma_op = Atom::gnd(stdlib::PragmaOp::new(metta.settings().clone()));
-    tref.register_token(regex(r"pragma!"), move |_| { pragma_op.clone() });
-    let import_op = Atom::gnd(stdlib::ImportOp::new(metta.clone()));
-    tref.register_token(regex(r"import!"), move |_| { import_op.clone() });
-    let include_op = Atom::gnd(stdlib::IncludeOp::new(metta.clone()));
-    tref.register_token(regex(r"include"), move |_| { include_op.clone() });
-    let bind_op = Atom::gnd(stdlib::BindOp::new(tokenizer.clone()));
-    tref.register_token(regex(r"bind!"), move |_| { bind_op.clone() });
-    let trace_op = Atom::gnd(stdlib::TraceOp{});
-    tref.register_token(regex(r"trace!"), move |_| { trace_op.clone() });
-    let println_op = Atom::gnd(stdlib::PrintlnOp{});
-    tref.register_token(regex(r"println!"), move |_| { println_op.clone() });
-    let format_args_op = Atom::gnd(stdlib::FormatArgsOp{});
-    tref.register_token(regex(r"format-args"), move |_| { format_args_op.clone() });
-    let print_alternatives_op = Atom::gnd(PrintAlternativesOp{});
-    tref.register_token(regex(r"print-alternatives!"), move |_| { print_alternatives_op.clone() });
-    let sealed_op = Atom::gnd(stdlib::SealedOp{});
-    tref.register_token(regex(r"sealed"), move |_| { sealed_op.clone() });
-    // &self should be updated
-    // TODO: adding &self might be done not by stdlib, but by MeTTa itself.
-    // TODO: adding &self introduces self referencing and thus prevents space
-    // from being freed. There are two options to eliminate this. (1) use weak
-    // pointer and somehow use the same type to represent weak and strong
-    // pointers to the atomspace. (2) resolve &self in GroundingSpace::query
-    // method without adding it into container.
-    let self_atom = Atom::gnd(space.clone());
-    tref.register_token(regex(r"&self"), move |_| { self_atom.clone() });
+grounded_op!(AssertTerminatesOp, "assert-terminates");
+
+impl AssertTerminatesOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
 }
 
-pub fn register_rust_stdlib_tokens(target: &mut Tokenizer) {
-    let mut rust_tokens = Tokenizer::new();
-    let tref = &mut rust_tokens;
+impl Grounded for AssertTerminatesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM])
+    }
 
-    tref.register_fallible_token(regex(r"[\-\+]?\d+"),
-        |token| { Ok(Atom::gnd(Number::from_int_str(token)?)) });
-    tref.register_fallible_token(regex(r"[\-\+]?\d+\.\d+"),
-        |token| { Ok(Atom::gnd(Number::from_float_str(token)?)) });
-    tref.register_fallible_token(regex(r"[\-\+]?\d+(\.\d+)?[eE][\-\+]?\d+"),
-        |token| { Ok(Atom::gnd(Number::from_float_str(token)?)) });
-    tref.register_token(regex(r"True|False"),
-        |token| { Atom::gnd(Bool::from_str(token)) });
-    tref.register_token(regex(r#"(?s)^".*"$"#),
-        |token| { let mut s = String::from(token); s.remove(0); s.pop(); Atom::gnd(Str::from_string(s)) });
-    let sum_op = Atom::gnd(SumOp{});
-    tref.register_token(regex(r"\+"), move |_| { sum_op.clone() });
-    let sub_op = Atom::gnd(SubOp{});
-    tref.register_token(regex(r"\-"), move |_| { sub_op.clone() });
-    let mul_op = Atom::gnd(MulOp{});
-    tref.register_token(regex(r"\*"), move |_| { mul_op.clone() });
-    let div_op = Atom::gnd(DivOp{});
-    tref.register_token(regex(r"/"), move |_| { div_op.clone() });
-    let mod_op = Atom::gnd(ModOp{});
-    tref.register_token(regex(r"%"), move |_| { mod_op.clone() });
-    let lt_op = Atom::gnd(LessOp{});
-    tref.register_token(regex(r"<"), move |_| { lt_op.clone() });
-    let gt_op = Atom::gnd(GreaterOp{});
-    tref.register_token(regex(r">"), move |_| { gt_op.clone() });
-    let le_op = Atom::gnd(LessEqOp{});
-    tref.register_token(regex(r"<="), move |_| { le_op.clone() });
-    let ge_op = Atom::gnd(GreaterEqOp{});
-    tref.register_token(regex(r">="), move |_| { ge_op.clone() });
-    let eq_op = Atom::gnd(stdlib::EqualOp{});
-    tref.register_token(regex(r"=="), move |_| { eq_op.clone() });
-    let and_op = Atom::gnd(AndOp{});
-    tref.register_token(regex(r"and"), move |_| { and_op.clone() });
-    let or_op = Atom::gnd(OrOp{});
-    tref.register_token(regex(r"or"), move |_| { or_op.clone() });
-    let not_op = Atom::gnd(NotOp{});
-    tref.register_token(regex(r"not"), move |_| { not_op.clone() });
-    // NOTE: xor and flip are absent in Python intentionally for conversion testing
-    let xor_op = Atom::gnd(XorOp{});
-    tref.register_token(regex(r"xor"), move |_| { xor_op.clone() });
-    let flip_op = Atom::gnd(FlipOp{});
-    tref.register_token(regex(r"flip"), move |_| { flip_op.clone() });
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
 
-    target.move_front(&mut rust_tokens);
+impl CustomExecute for AssertTerminatesOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertTerminatesOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-terminates expects atom and non-negative integer step limit as arguments");
+        let atom = args.first().ok_or_else(arg_error)?;
+        let step_limit = match AsPrimitive::from_atom(args.get(1).ok_or_else(arg_error)?).as_number() {
+            Some(Number::Integer(n)) if n >= 0 => n as usize,
+            _ => return Err(arg_error()),
+        };
+
+        Ok(interpret_with_limit(self.space.clone(), atom, step_limit)?)
+    }
 }
 
-pub static METTA_CODE: &'static str = include_str!("stdlib_minimal.metta");
+/// Clock used by [BenchOp] to measure elapsed time. Returns time passed
+/// since some fixed moment, only differences between calls are used.
+pub type BenchClock = fn() -> std::time::Duration;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::metta::text::SExprParser;
-    use crate::metta::runner::EnvBuilder;
-    use crate::metta::runner::string::Str;
-    use crate::matcher::atoms_are_equivalent;
-    use crate::common::Operation;
-    use crate::common::test_utils::metta_space;
+fn system_clock() -> std::time::Duration {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
 
-    use std::convert::TryFrom;
-    use std::fmt::Display;
-    use regex::Regex;
+/// Evaluates the argument and returns the evaluation statistics instead of
+/// the results: `(bench (fact 5))` returns
+/// `((time <ms>) (steps <n>) (results <k>))` where `<ms>` is a wall time in
+/// milliseconds, `<n>` is a number of interpreter steps including steps of
+/// the nested evaluations and `<k>` is a number of results. Time is measured
+/// by the [BenchClock] passed to [BenchOp::with_clock], system clock is used
+/// by default. The operation is not pure as its result depends on the clock.
+#[derive(Clone, Debug)]
+pub struct BenchOp {
+    space: DynSpace,
+    clock: BenchClock,
+}
 
-    fn run_program(program: &str) -> Result<Vec<Vec<Atom>>, String> {
-        let metta = Metta::new(Some(EnvBuilder::test_env()));
-        metta.run(SExprParser::new(program))
+grounded_op!(BenchOp, "bench");
+
+impl BenchOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self::with_clock(space, system_clock)
     }
 
-    #[test]
-    fn get_type_op() {
-        let space = DynSpace::new(metta_space("
-            (: B Type)
-            (: C Type)
-            (: A B)
-            (: A C)
-        "));
+    pub fn with_clock(space: DynSpace, clock: BenchClock) -> Self {
+        Self{ space, clock }
+    }
+}
 
-        let get_type_op = GetTypeOp::new(space.clone());
-        assert_eq_no_order!(get_type_op.execute(&mut vec![sym!("A"), expr!({space.clone()})]).unwrap(),
-            vec![sym!("B"), sym!("C")]);
+impl Grounded for BenchOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
     }
 
-    #[test]
-    fn get_type_op_non_valid_atom() {
-        let space = DynSpace::new(metta_space("
-            (: f (-> Number String))
-            (: 42 Number)
-            (: \"test\" String)
-        "));
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
 
-        let get_type_op = GetTypeOp::new(space.clone());
-        assert_eq_no_order!(get_type_op.execute(&mut vec![expr!("f" "42"), expr!({space.clone()})]).unwrap(),
-            vec![sym!("String")]);
-        assert_eq_no_order!(get_type_op.execute(&mut vec![expr!("f" "\"test\""), expr!({space.clone()})]).unwrap(),
-            vec![EMPTY_SYMBOL]);
+impl CustomExecute for BenchOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("BenchOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("bench expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        let start = (self.clock)();
+        let (results, steps) = interpret_counting_steps(self.space.clone(), atom)?;
+        let time = (self.clock)().saturating_sub(start);
+
+        let stat = |name: &str, value: usize| Atom::expr([Atom::sym(name),
+            Atom::gnd(Number::Integer(value as i64))]);
+        Ok(vec![Atom::expr([
+            stat("time", time.as_millis() as usize),
+            stat("steps", steps),
+            stat("results", results.len()),
+        ])])
     }
+}
 
+/// Evaluates the second argument and checks that at least one of the
+/// results matches the pattern passed as the first argument:
+/// `(assert-matches (S $n) (plus (S Z) Z))`. Error lists all results when
+/// none of them matches.
+#[derive(Clone, Debug)]
+pub struct AssertMatchesOp {
+    space: DynSpace,
+}
 
-    #[test]
-    fn metta_car_atom() {
-        let result = run_program("!(eval (car-atom (A $b)))");
-        assert_eq!(result, Ok(vec![vec![expr!("A")]]));
-        let result = run_program("!(eval (car-atom ($a B)))");
-        assert_eq!(result, Ok(vec![vec![expr!(a)]]));
+grounded_op!(AssertMatchesOp, "assert-matches");
+
+impl AssertMatchesOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for AssertMatchesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, UNIT_TYPE()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertMatchesOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertMatchesOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-matches expects two atoms as arguments: pattern and expression");
+        let pattern = args.first().ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+
+        let results = interpret(self.space.clone(), atom)?;
+        if results.iter().any(|result| crate::matcher::match_atoms(pattern, result).next().is_some()) {
+            unit_result()
+        } else {
+            Err(ExecError::Runtime(format!("\nExpected result matching: {}\nGot: {:?}", pattern, results)))
+        }
+    }
+}
+
+/// Evaluates the second argument and checks that the predicate passed as
+/// the first argument returns `True` for each result:
+/// `(assert-all is-positive (superpose (1 2 3)))`. Fails with an error which
+/// contains the first result for which the predicate doesn't hold. Succeeds
+/// when evaluation returns no results.
+#[derive(Clone, Debug)]
+pub struct AssertAllOp {
+    space: DynSpace,
+}
+
+grounded_op!(AssertAllOp, "assert-all");
+
+impl AssertAllOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for AssertAllOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, UNIT_TYPE()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertAllOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertAllOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-all expects predicate and atom as arguments");
+        let pred = args.first().ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+
+        let true_atom = Atom::gnd(Bool(true));
+        for result in interpret(self.space.clone(), atom)? {
+            let check = Atom::expr([pred.clone(), result.clone()]);
+            if !interpret(self.space.clone(), &check)?.contains(&true_atom) {
+                return Err(ExecError::Runtime(format!("Predicate {} does not hold for result: {}", pred, result)));
+            }
+        }
+        unit_result()
+    }
+}
+
+/// Evaluates the second argument and checks that the number of its results
+/// is equal to the number passed as the first argument:
+/// `(assert-count 3 (color))`. Error reports the actual number of results.
+#[derive(Clone, Debug)]
+pub struct AssertCountOp {
+    space: DynSpace,
+}
+
+grounded_op!(AssertCountOp, "assert-count");
+
+impl AssertCountOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for AssertCountOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM, UNIT_TYPE()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertCountOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertCountOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-count expects non-negative integer number and atom as arguments");
+        let expected = match AsPrimitive::from_atom(args.first().ok_or_else(arg_error)?).as_number() {
+            Some(Number::Integer(n)) if n >= 0 => n as usize,
+            _ => return Err(arg_error()),
+        };
+        let atom = args.get(1).ok_or_else(arg_error)?;
+
+        let results = interpret(self.space.clone(), atom)?;
+        if results.len() == expected {
+            unit_result()
+        } else {
+            Err(ExecError::Runtime(format!("\nExpected number of results: {}\nGot: {} {:?}", expected, results.len(), results)))
+        }
+    }
+}
+
+/// Applies the step operation passed as the first argument to the evolving
+/// state starting from the initial state passed as the second argument at
+/// most the number of times passed as the fourth argument. Succeeds as soon
+/// as the goal predicate passed as the third argument returns `True` for the
+/// state, otherwise fails with an error:
+/// `(assert-eventually inc 0 is-ten 100)`. The goal is checked for the
+/// initial state as well. When the step returns more than one result the
+/// first one is used as the next state.
+#[derive(Clone, Debug)]
+pub struct AssertEventuallyOp {
+    space: DynSpace,
+}
+
+grounded_op!(AssertEventuallyOp, "assert-eventually");
+
+impl AssertEventuallyOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for AssertEventuallyOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_NUMBER, UNIT_TYPE()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AssertEventuallyOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("AssertEventuallyOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("assert-eventually expects step, initial state, goal and non-negative integer number of steps as arguments");
+        let step = args.first().ok_or_else(arg_error)?;
+        let init = args.get(1).ok_or_else(arg_error)?;
+        let goal = args.get(2).ok_or_else(arg_error)?;
+        let max = match AsPrimitive::from_atom(args.get(3).ok_or_else(arg_error)?).as_number() {
+            Some(Number::Integer(n)) if n >= 0 => n as usize,
+            _ => return Err(arg_error()),
+        };
+
+        let next_state = |atom: &Atom| -> Result<Atom, ExecError> {
+            let result = interpret(self.space.clone(), atom)?.into_iter().next()
+                .ok_or_else(|| ExecError::Runtime(format!("No results returned by {}", atom)))?;
+            if atom_is_error(&result) {
+                Err(ExecError::Runtime(error_message(&result)))
+            } else {
+                Ok(result)
+            }
+        };
+        let mut state = next_state(init)?;
+        for i in 0..=max {
+            let reached = interpret(self.space.clone(), &Atom::expr([goal.clone(), state.clone()]))?
+                .iter().any(|result| *result == Atom::gnd(Bool(true)));
+            if reached {
+                return unit_result();
+            }
+            if i < max {
+                state = next_state(&Atom::expr([step.clone(), state]))?;
+            }
+        }
+        Err(ExecError::Runtime(format!("Goal {} is not reached within {} steps, last state: {}", goal, max, state)))
+    }
+}
+
+/// Evaluates the first argument and returns its results when evaluation
+/// succeeds. When evaluation fails or returns at least one Error atom the
+/// second argument is returned instead. The second argument is evaluated
+/// only in the latter case.
+#[derive(Clone, Debug)]
+pub struct OnErrorOp {
+    space: DynSpace,
+}
+
+grounded_op!(OnErrorOp, "on-error");
+
+impl OnErrorOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for OnErrorOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for OnErrorOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("on-error expects two arguments: atom to evaluate and fallback atom");
+        let atom = args.first().ok_or_else(arg_error)?;
+        let fallback = args.get(1).ok_or_else(arg_error)?;
+
+        match interpret(self.space.clone(), atom) {
+            Ok(results) if !results.iter().any(atom_is_error) => Ok(results),
+            _ => Ok(vec![fallback.clone()]),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SuperposeOp {
+    space: DynSpace,
+}
+
+grounded_op!(SuperposeOp, "superpose");
+
+impl SuperposeOp {
+    fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for SuperposeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SuperposeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("superpose expects single expression as an argument");
+        let atom = args.get(0).ok_or_else(arg_error)?;
+        let expr  = TryInto::<&ExpressionAtom>::try_into(atom).map_err(|_| arg_error())?;
+
+        if expr.children().is_empty() {
+            Ok(vec![EMPTY_SYMBOL])
+        } else {
+            let mut superposed = Vec::new();
+            for atom in expr.children() {
+                match interpret_no_error(self.space.clone(), atom) {
+                    Ok(results) => { superposed.extend(results); },
+                    Err(message) => { return Err(format!("Error: {}", message).into()) },
+                }
+            }
+            Ok(superposed)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CollapseOp {
+    space: DynSpace,
+}
+
+grounded_op!(CollapseOp, "collapse");
+
+impl CollapseOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for CollapseOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for CollapseOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("collapse expects single executable atom as an argument");
+        let atom = args.get(0).ok_or_else(arg_error)?;
+
+        // TODO: Calling interpreter inside the operation is not too good
+        // Could it be done via returning atom for the further interpretation?
+        let result = interpret_no_error(self.space.clone(), atom)?;
+
+        Ok(vec![Atom::expr(result)])
+    }
+}
+
+/// Interprets the argument and returns its distinct results. Results are
+/// compared using alpha equivalence and returned in order of their first
+/// occurrence.
+#[derive(Clone, Debug)]
+pub struct UniqueOp {
+    space: DynSpace,
+}
+
+grounded_op!(UniqueOp, "unique");
+
+impl UniqueOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for UniqueOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for UniqueOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("unique expects single executable atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        let results = interpret_no_error(self.space.clone(), atom)?;
+        let mut unique: Vec<Atom> = Vec::with_capacity(results.len());
+        for result in results {
+            if !unique.iter().any(|u| crate::matcher::atoms_are_equivalent(u, &result)) {
+                unique.push(result);
+            }
+        }
+        Ok(unique)
+    }
+}
+
+/// Interprets the second argument and returns no more than the number of
+/// results passed as the first argument. When there are less results than
+/// required all of them are returned.
+#[derive(Clone, Debug)]
+pub struct LimitOp {
+    space: DynSpace,
+}
+
+grounded_op!(LimitOp, "limit");
+
+impl LimitOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for LimitOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for LimitOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("limit expects two arguments: non-negative integer number and executable atom");
+        let count = match AsPrimitive::from_atom(args.first().ok_or_else(arg_error)?).as_number() {
+            Some(Number::Integer(count)) if count >= 0 => count as usize,
+            _ => return Err(arg_error()),
+        };
+        let atom = args.get(1).ok_or_else(arg_error)?;
+
+        let mut results = interpret_no_error(self.space.clone(), atom)?;
+        results.truncate(count);
+        Ok(results)
+    }
+}
+
+/// Folds children of the expression from right to left. Operation is applied
+/// to the child and accumulated value: `(foldr op seed (a b))` evaluates
+/// `(op a (op b seed))`. Returns seed when expression is empty.
+#[derive(Clone, Debug)]
+pub struct FoldrOp {
+    space: DynSpace,
+}
+
+grounded_op!(FoldrOp, "foldr");
+
+impl FoldrOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for FoldrOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for FoldrOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("foldr expects three arguments: operation, seed and expression");
+        let op = args.first().ok_or_else(arg_error)?;
+        let seed = args.get(1).ok_or_else(arg_error)?;
+        let expr = TryInto::<&ExpressionAtom>::try_into(args.get(2).ok_or_else(arg_error)?)
+            .map_err(|_| arg_error())?;
+
+        let mut folded = vec![seed.clone()];
+        for child in expr.children().iter().rev() {
+            let mut next = Vec::new();
+            for acc in folded {
+                let application = Atom::expr([op.clone(), child.clone(), acc]);
+                next.extend(interpret_no_error(self.space.clone(), &application)?);
+            }
+            folded = next;
+        }
+        Ok(folded)
+    }
+}
+
+/// Applies operation to the corresponding children of two expressions and
+/// collects results into a new expression: `(zip-with + (1 2 3) (10 20 30))`
+/// returns `(11 22 33)`. Result is truncated to the length of the shorter
+/// expression. When application returns several results each combination of
+/// them is returned as a separate expression, when it returns no results no
+/// expressions are returned.
+#[derive(Clone, Debug)]
+pub struct ZipWithOp {
+    space: DynSpace,
+}
+
+grounded_op!(ZipWithOp, "zip-with");
+
+impl ZipWithOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for ZipWithOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ZipWithOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("zip-with expects three arguments: operation and two expressions");
+        let op = args.first().ok_or_else(arg_error)?;
+        let left = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?)
+            .map_err(|_| arg_error())?;
+        let right = TryInto::<&ExpressionAtom>::try_into(args.get(2).ok_or_else(arg_error)?)
+            .map_err(|_| arg_error())?;
+
+        let mut zipped: Vec<Vec<Atom>> = vec![vec![]];
+        for (l, r) in left.children().iter().zip(right.children().iter()) {
+            let application = Atom::expr([op.clone(), l.clone(), r.clone()]);
+            let results = interpret_no_error(self.space.clone(), &application)?;
+            zipped = zipped.into_iter()
+                .flat_map(|prefix| results.iter().map(move |result| {
+                    let mut next = prefix.clone();
+                    next.push(result.clone());
+                    next
+                }))
+                .collect();
+        }
+        Ok(zipped.into_iter().map(Atom::expr).collect())
+    }
+}
+
+/// Destructures the value using the pattern and evaluates the body for each
+/// successful unification. Value is evaluated first, then the arguments of
+/// each result (i.e. children of the expression without the head) are
+/// unified with the pattern: `(bind ($x $y) (pair 1 2) (+ $x $y))` returns
+/// `3`. When value cannot be unified with the pattern no results are returned.
+#[derive(Clone, Debug)]
+pub struct DestructuringBindOp {
+    space: DynSpace,
+}
+
+grounded_op!(DestructuringBindOp, "bind");
+
+impl DestructuringBindOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for DestructuringBindOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for DestructuringBindOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("bind expects three arguments: pattern, value and body");
+        let pattern = args.first().ok_or_else(arg_error)?;
+        let value = args.get(1).ok_or_else(arg_error)?;
+        let body = args.get(2).ok_or_else(arg_error)?;
+
+        let mut results = Vec::new();
+        for value in interpret_no_error(self.space.clone(), value)? {
+            let value_args = match <&[Atom]>::try_from(&value) {
+                Ok([_head, value_args @ ..]) => Atom::expr(value_args),
+                _ => continue,
+            };
+            for bindings in crate::atom::matcher::match_atoms(pattern, &value_args) {
+                let body = crate::atom::matcher::apply_bindings_to_atom_move(body.clone(), &bindings);
+                results.extend(interpret_no_error(self.space.clone(), &body)?);
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// Evaluates the second argument, logs each result prefixed by the label
+/// passed as the first argument and returns results unchanged. Messages are
+/// logged using `log::info!` unless the output is passed explicitly using
+/// [TraceValueOp::with_output].
+#[derive(Clone, Debug)]
+pub struct TraceValueOp {
+    space: DynSpace,
+    output: Option<Shared<Vec<String>>>,
+}
+
+grounded_op!(TraceValueOp, "trace");
+
+impl TraceValueOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space, output: None }
+    }
+
+    pub fn with_output(space: DynSpace, output: Shared<Vec<String>>) -> Self {
+        Self{ space, output: Some(output) }
+    }
+}
+
+impl Grounded for TraceValueOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for TraceValueOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("trace expects two arguments: label and atom");
+        let label = atom_to_string(args.first().ok_or_else(arg_error)?);
+        let atom = args.get(1).ok_or_else(arg_error)?;
+
+        let results = interpret_no_error(self.space.clone(), atom)?;
+        for result in &results {
+            let message = format!("{}: {}", label, result);
+            match &self.output {
+                Some(output) => output.borrow_mut().push(message),
+                None => log::info!("{}", message),
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CaptureOp {
+    space: DynSpace,
+}
+
+grounded_op!(CaptureOp, "capture");
+
+impl CaptureOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for CaptureOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for CaptureOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("capture expects one argument");
+        let atom = args.get(0).ok_or_else(arg_error)?;
+        interpret(self.space.clone(), &atom).map_err(|e| ExecError::from(e))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CaseOp {
+    space: DynSpace,
+}
+
+grounded_op!(CaseOp, "case");
+
+impl CaseOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for CaseOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for CaseOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("case expects two arguments: atom and expression of cases");
+        let cases = args.get(1).ok_or_else(arg_error)?;
+        let atom = args.get(0).ok_or_else(arg_error)?;
+        log::debug!("CaseOp::execute: atom: {}, cases: {:?}", atom, cases);
+
+        let switch = |interpreted: Atom| -> Atom {
+            Atom::expr([sym!("switch"), interpreted, cases.clone()])
+        };
+
+        // Interpreting argument inside CaseOp is required because otherwise `Empty` result
+        // calculated inside interpreter cuts the whole branch of the interpretation. Also we
+        // cannot use `unify` in a unit test because after interpreting `(chain... (chain
+        // (metta (unify ...) Atom <space>)) ...)` `chain` executes `unify` and also gets
+        // `Empty` even if we have `Atom` as a resulting type. It can be solved by different ways.
+        // One way is to invent new type `EmptyType` (type of the `Empty` atom) and use this type
+        // in a function to allow `Empty` atoms as an input. `EmptyType` type should not be
+        // casted to the `%Undefined%` thus one cannot pass `Empty` to the function which accepts
+        // `%Undefined%`. Another way is to introduce "call" level. Thus if function called
+        // returned the result to the `chain` it should stop reducing it and insert it into the
+        // last argument.
+        let results = interpret(self.space.clone(), atom);
+        log::debug!("CaseOp::execute: atom results: {:?}", results);
+        let results = match results {
+            Ok(results) if results.is_empty() =>
+                vec![switch(EMPTY_SYMBOL)],
+            Ok(results) =>
+                results.into_iter().map(|atom| switch(atom)).collect(),
+            Err(err) => vec![Atom::expr([ERROR_SYMBOL, atom.clone(), Atom::sym(err)])],
+        };
+        Ok(results)
+    }
+}
+
+/// Evaluates the second argument and applies the predicate passed as the
+/// first argument to each result. Only results for which the predicate
+/// returns `True` are returned: `(collect-if is-even (range 1 6))` returns
+/// `2` and `4`. `Error` results of the evaluation are returned as is.
+#[derive(Clone, Debug)]
+pub struct CollectIfOp {
+    space: DynSpace,
+}
+
+grounded_op!(CollectIfOp, "collect-if");
+
+impl CollectIfOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for CollectIfOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for CollectIfOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("collect-if expects predicate and atom as arguments");
+        let pred = args.first().ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+
+        let true_atom = Atom::gnd(Bool(true));
+        let mut collected = Vec::new();
+        for result in interpret(self.space.clone(), atom)? {
+            if atom_is_error(&result) {
+                collected.push(result);
+                continue;
+            }
+            let check = Atom::expr([pred.clone(), result.clone()]);
+            if interpret(self.space.clone(), &check)?.contains(&true_atom) {
+                collected.push(result);
+            }
+        }
+        Ok(collected)
+    }
+}
+
+/// Evaluates the second argument and returns its results in order until the
+/// predicate passed as the first argument returns something other than
+/// `True` for the first time: `(take-while is-small (range 1 10))`. All
+/// results are returned when predicate holds for each of them, and no
+/// results are returned when predicate does not hold for the first one.
+#[derive(Clone, Debug)]
+pub struct TakeWhileOp {
+    space: DynSpace,
+}
+
+grounded_op!(TakeWhileOp, "take-while");
+
+impl TakeWhileOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for TakeWhileOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for TakeWhileOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("take-while expects predicate and atom as arguments");
+        let pred = args.first().ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        let mut results = interpret(self.space.clone(), atom)?;
+        let prefix = prefix_len_while(&self.space, pred, &results)?;
+        results.truncate(prefix);
+        Ok(results)
+    }
+}
+
+/// Evaluates the second argument and skips its leading results for which
+/// the predicate passed as the first argument returns `True`. The rest of
+/// the results is returned: `(drop-while is-small (range 1 10))`. No results
+/// are returned when predicate holds for each of them, and all results are
+/// returned when predicate does not hold for the first one.
+#[derive(Clone, Debug)]
+pub struct DropWhileOp {
+    space: DynSpace,
+}
+
+grounded_op!(DropWhileOp, "drop-while");
+
+impl DropWhileOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for DropWhileOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for DropWhileOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("drop-while expects predicate and atom as arguments");
+        let pred = args.first().ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        let mut results = interpret(self.space.clone(), atom)?;
+        let prefix = prefix_len_while(&self.space, pred, &results)?;
+        Ok(results.split_off(prefix))
+    }
+}
+
+fn prefix_len_while(space: &DynSpace, pred: &Atom, results: &[Atom]) -> Result<usize, ExecError> {
+    let true_atom = Atom::gnd(Bool(true));
+    for (i, result) in results.iter().enumerate() {
+        let check = Atom::expr([pred.clone(), result.clone()]);
+        if !interpret(space.clone(), &check)?.contains(&true_atom) {
+            return Ok(i);
+        }
+    }
+    Ok(results.len())
+}
+
+/// Evaluates the second argument and returns the result with the maximal
+/// value of the key function passed as the first argument:
+/// `(max-by cost (superpose (a b c)))` returns the atom with the maximal
+/// `(cost x)`. Key function should return a number. When several results
+/// have the same maximal key the first one is returned. When there are no
+/// results the operation returns no results as well.
+#[derive(Clone, Debug)]
+pub struct MaxByOp {
+    space: DynSpace,
+}
+
+grounded_op!(MaxByOp, "max-by");
+
+impl MaxByOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for MaxByOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MaxByOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        extremum_by(&self.space, args, "max-by", |key, best| key > best)
+    }
+}
+
+/// Same as [MaxByOp] but returns the result with the minimal value of the key
+/// function: `(min-by cost (superpose (a b c)))`.
+#[derive(Clone, Debug)]
+pub struct MinByOp {
+    space: DynSpace,
+}
+
+grounded_op!(MinByOp, "min-by");
+
+impl MinByOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for MinByOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MinByOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        extremum_by(&self.space, args, "min-by", |key, best| key < best)
+    }
+}
+
+/// Evaluates the second argument and groups the results by the value of the
+/// key function passed as the first argument. Each group is returned as a
+/// separate result `(<key> (<member> ...))`: `(group-by parity (superpose (1
+/// 2 3)))` returns `(odd (1 3))` and `(even (2))`. Keys are compared using
+/// `Atom::eq`, groups are returned in the order the keys are first seen.
+#[derive(Clone, Debug)]
+pub struct GroupByOp {
+    space: DynSpace,
+}
+
+grounded_op!(GroupByOp, "group-by");
+
+impl GroupByOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for GroupByOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GroupByOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("group-by expects key function and atom as arguments");
+        let key = args.first().ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+
+        let mut groups: Vec<(Atom, Vec<Atom>)> = Vec::new();
+        for result in interpret(self.space.clone(), atom)? {
+            let call = Atom::expr([key.clone(), result.clone()]);
+            let value = interpret(self.space.clone(), &call)?.into_iter().next()
+                .ok_or_else(|| ExecError::from(format!("group-by expects key function returning value, {} has no results", call)))?;
+            match groups.iter_mut().find(|(group, _)| *group == value) {
+                Some((_, members)) => members.push(result),
+                None => groups.push((value, vec![result])),
+            }
+        }
+        Ok(groups.into_iter()
+            .map(|(value, members)| Atom::expr([value, Atom::expr(members)]))
+            .collect())
+    }
+}
+
+/// Evaluates the argument and returns the number of distinct results:
+/// `(count-distinct (superpose (a b a)))` returns `2`. Results which are
+/// equal up to variable renaming are counted once, thus `(f $x)` and `(f $y)`
+/// are not distinct.
+#[derive(Clone, Debug)]
+pub struct CountDistinctOp {
+    space: DynSpace,
+}
+
+grounded_op!(CountDistinctOp, "count-distinct");
+
+impl CountDistinctOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for CountDistinctOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for CountDistinctOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("count-distinct expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        let mut distinct: Vec<Atom> = Vec::new();
+        for result in interpret(self.space.clone(), atom)? {
+            if !distinct.iter().any(|seen| crate::matcher::atoms_are_equivalent(seen, &result)) {
+                distinct.push(result);
+            }
+        }
+        Ok(vec![Atom::gnd(Number::Integer(distinct.len() as i64))])
+    }
+}
+
+/// Evaluates both arguments and returns their results interleaved:
+/// `(interleave (superpose (a b c)) (superpose (1 2)))` returns `a`, `1`,
+/// `b`, `2`, `c`. When one of the arguments has more results than the other
+/// the rest of its results is appended to the end.
+#[derive(Clone, Debug)]
+pub struct InterleaveOp {
+    space: DynSpace,
+}
+
+grounded_op!(InterleaveOp, "interleave");
+
+impl InterleaveOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for InterleaveOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for InterleaveOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("interleave expects two atoms as arguments");
+        let left = args.first().ok_or_else(arg_error)?;
+        let right = args.get(1).ok_or_else(arg_error)?;
+
+        let mut left = interpret(self.space.clone(), left)?.into_iter();
+        let mut right = interpret(self.space.clone(), right)?.into_iter();
+        let mut results = Vec::new();
+        loop {
+            match (left.next(), right.next()) {
+                (None, None) => break,
+                (l, r) => results.extend(l.into_iter().chain(r)),
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// Applies the operation passed as the first argument to each atom of the
+/// space passed as the second argument and returns a new space which
+/// contains results of the applications: `(map-space swap &kb)`. All results
+/// of the application are added into the new space, atom which has no
+/// results is dropped. When an application returns an `Error` expression
+/// the operation fails with this error and no space is returned.
+#[derive(Clone, Debug)]
+pub struct MapSpaceOp {
+    space: DynSpace,
+}
+
+grounded_op!(MapSpaceOp, "map-space");
+
+impl MapSpaceOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for MapSpaceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, rust_type_atom::<DynSpace>(), rust_type_atom::<DynSpace>()])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MapSpaceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-space expects operation and space as arguments");
+        let op = args.first().ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(args.get(1).ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let atoms: Vec<Atom> = space.borrow().as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?
+            .cloned().collect();
+
+        let mut mapped = GroundingSpace::new();
+        for atom in atoms {
+            let call = Atom::expr([op.clone(), atom]);
+            for result in interpret(self.space.clone(), &call)? {
+                if atom_is_error(&result) {
+                    return Ok(vec![result]);
+                }
+                mapped.add(result);
+            }
+        }
+        Ok(vec![Atom::gnd(DynSpace::new(mapped))])
+    }
+}
+
+/// Applies the predicate passed as the first argument to each atom of the
+/// space passed as the second argument and returns a pair of new spaces:
+/// the first one contains atoms for which the predicate returns `True`, the
+/// second one contains the rest of the atoms: `(partition-space is-fact &kb)`.
+/// Atom for which the predicate returns anything else including no results
+/// is put into the second space. When an application returns an `Error`
+/// expression the operation fails with this error and no spaces are returned.
+#[derive(Clone, Debug)]
+pub struct PartitionSpaceOp {
+    space: DynSpace,
+}
+
+grounded_op!(PartitionSpaceOp, "partition-space");
+
+impl PartitionSpaceOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for PartitionSpaceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, rust_type_atom::<DynSpace>(), ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for PartitionSpaceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("partition-space expects predicate and space as arguments");
+        let pred = args.first().ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(args.get(1).ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        let atoms: Vec<Atom> = space.borrow().as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?
+            .cloned().collect();
+
+        let true_atom = Atom::gnd(Bool(true));
+        let mut accepted = GroundingSpace::new();
+        let mut rejected = GroundingSpace::new();
+        for atom in atoms {
+            let check = Atom::expr([pred.clone(), atom.clone()]);
+            let results = interpret(self.space.clone(), &check)?;
+            if let Some(error) = results.iter().find(|result| atom_is_error(result)) {
+                return Ok(vec![error.clone()]);
+            }
+            if results.contains(&true_atom) {
+                accepted.add(atom);
+            } else {
+                rejected.add(atom);
+            }
+        }
+        Ok(vec![Atom::expr([Atom::gnd(DynSpace::new(accepted)), Atom::gnd(DynSpace::new(rejected))])])
+    }
+}
+
+/// Performs a single reduction step of the argument: either calls pure
+/// grounded operation or applies one of the matching `(= <lhs> <rhs>)`
+/// rules. Each rewrite is returned as a separate result. Argument which
+/// cannot be reduced is returned unchanged. Results are wrapped into `quote` to
+/// prevent further evaluation: `(reduce-once (plus (S Z) Z))` returns
+/// `(quote (S (plus Z Z)))`. Only the atom itself is reduced, its
+/// subexpressions are not.
+#[derive(Clone, Debug)]
+pub struct ReduceOnceOp {
+    space: DynSpace,
+}
+
+grounded_op!(ReduceOnceOp, "reduce-once");
+
+impl ReduceOnceOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for ReduceOnceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ReduceOnceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("reduce-once expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        let mut results = crate::metta::normal_form::rewrite_root(&self.space, atom);
+        if results.is_empty() {
+            results.push(atom.clone());
+        }
+        Ok(results.into_iter()
+            .map(|result| Atom::expr([Atom::sym("quote"), result]))
+            .collect())
+    }
+}
+
+/// Reduces the argument and returns each result together with the rules
+/// applied to obtain it as `(quote (<result> (<rule> ...)))`. Rules are
+/// listed in order of application and instantiated by the bindings of the
+/// match: `(trace-reductions (frog Fritz))` returns
+/// `(quote (True ((= (frog Fritz) (green Fritz)) (= (green Fritz) True))))`.
+/// Reduction is performed by [interpret_explained](crate::metta::normal_form::interpret_explained)
+/// which reduces children before the expression itself, thus results can
+/// differ from the results of the interpreter for the functions which don't
+/// evaluate their arguments.
+#[derive(Clone, Debug)]
+pub struct TraceReductionsOp {
+    space: DynSpace,
+}
+
+grounded_op!(TraceReductionsOp, "trace-reductions");
+
+impl TraceReductionsOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for TraceReductionsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for TraceReductionsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("trace-reductions expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        let explained = crate::metta::normal_form::interpret_explained(&self.space, atom)?;
+        Ok(explained.into_iter()
+            .map(|(result, rules)| Atom::expr([Atom::sym("quote"), Atom::expr([result, Atom::expr(rules)])]))
+            .collect())
+    }
+}
+
+/// Evaluates the second argument in a temporary space which contains atoms
+/// of the current space and children of the first argument:
+/// `(with-space ((= (f $x) $x)) (f 1))` returns `1`. Atoms added do not
+/// shadow rules of the current space: both local and existing rules are
+/// applied. The temporary space is discarded after evaluation, thus local
+/// atoms are not visible afterwards. Operations which keep a reference to
+/// the space, like `match &self` or `collapse`, still work with the current
+/// space.
+#[derive(Clone, Debug)]
+pub struct WithSpaceOp {
+    space: DynSpace,
+}
+
+grounded_op!(WithSpaceOp, "with-space");
+
+impl WithSpaceOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for WithSpaceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for WithSpaceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("with-space expects expression of atoms and body as arguments");
+        let atoms = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let body = args.get(1).ok_or_else(arg_error)?;
+
+        let mut local = GroundingSpace::new();
+        self.space.borrow().as_space().atom_iter()
+            .ok_or_else(|| ExecError::Runtime("Unsupported Operation. Can't traverse atoms in this space".to_string()))?
+            .for_each(|atom| local.add(atom.clone()));
+        atoms.children().iter().for_each(|atom| local.add(atom.clone()));
+        Ok(interpret(DynSpace::new(local), body)?)
+    }
+}
+
+/// Evaluates the second argument using only the rules passed as the first
+/// argument instead of the rules of the current space:
+/// `(apply-rules ((= (f $x) (g $x))) (f a))` returns `(quote (g a))`. When no
+/// listed rule applies the argument is returned unchanged. Results are
+/// wrapped into `quote` to prevent their further evaluation using the rules
+/// of the current space.
+#[derive(Clone, Debug)]
+pub struct ApplyRulesOp {}
+
+grounded_op!(ApplyRulesOp, "apply-rules");
+
+impl Grounded for ApplyRulesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ApplyRulesOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("apply-rules expects expression of rules and atom as arguments");
+        let rules = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+
+        let mut space = GroundingSpace::new();
+        rules.children().iter().for_each(|rule| space.add(rule.clone()));
+        Ok(interpret(DynSpace::new(space), atom)?.into_iter()
+            .map(|result| Atom::expr([Atom::sym("quote"), result]))
+            .collect())
+    }
+}
+
+/// Evaluates the second argument and returns the number of randomly chosen
+/// distinct results passed as the first argument: `(sample 2 (get-atoms
+/// &self))`. When there are less distinct results than requested all of
+/// them are returned. Use [SampleSeededOp] to get reproducible results.
+#[derive(Clone, Debug)]
+pub struct SampleOp {
+    space: DynSpace,
+}
+
+grounded_op!(SampleOp, "sample");
+
+impl SampleOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for SampleOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SampleOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("sample expects non-negative integer number and atom as arguments");
+        let count = args.first().and_then(non_negative_int).ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        sample(&self.space, atom, count, &mut StdRng::from_entropy())
+    }
+}
+
+/// Same as [SampleOp] but uses random number generator initialized by the
+/// seed passed as the first argument, thus the same seed gives the same
+/// sample: `(sample-seeded 42 2 (get-atoms &self))`.
+#[derive(Clone, Debug)]
+pub struct SampleSeededOp {
+    space: DynSpace,
+}
+
+grounded_op!(SampleSeededOp, "sample-seeded");
+
+impl SampleSeededOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for SampleSeededOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SampleSeededOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("sample-seeded expects non-negative integer seed, non-negative integer number and atom as arguments");
+        let seed = args.first().and_then(non_negative_int).ok_or_else(arg_error)?;
+        let count = args.get(1).and_then(non_negative_int).ok_or_else(arg_error)?;
+        let atom = args.get(2).ok_or_else(arg_error)?;
+        sample(&self.space, atom, count, &mut StdRng::seed_from_u64(seed as u64))
+    }
+}
+
+fn non_negative_int(atom: &Atom) -> Option<usize> {
+    match AsPrimitive::from_atom(atom).as_number() {
+        Some(Number::Integer(n)) if n >= 0 => Some(n as usize),
+        _ => None,
+    }
+}
+
+fn sample(space: &DynSpace, atom: &Atom, count: usize, rng: &mut StdRng) -> Result<Vec<Atom>, ExecError> {
+    let mut distinct: Vec<Atom> = Vec::new();
+    for result in interpret(space.clone(), atom)? {
+        if !distinct.contains(&result) {
+            distinct.push(result);
+        }
+    }
+    Ok(distinct.into_iter().choose_multiple(rng, count))
+}
+
+fn extremum_by(space: &DynSpace, args: &[Atom], name: &str, is_better: fn(f64, f64) -> bool) -> Result<Vec<Atom>, ExecError> {
+    let arg_error = || ExecError::from(format!("{} expects key function and atom as arguments", name));
+    let key = args.first().ok_or_else(arg_error)?;
+    let atom = args.get(1).ok_or_else(arg_error)?;
+
+    let mut best: Option<(f64, Atom)> = None;
+    for result in interpret(space.clone(), atom)? {
+        let call = Atom::expr([key.clone(), result.clone()]);
+        let value = interpret(space.clone(), &call)?.into_iter()
+            .find_map(|value| Atom::as_gnd::<Number>(&value).cloned())
+            .ok_or_else(|| ExecError::from(format!("{} expects key function returning number, {} is not a number", name, call)))?;
+        let value: f64 = value.into();
+        match best {
+            Some((best_value, _)) if !is_better(value, best_value) => {},
+            _ => best = Some((value, result)),
+        }
+    }
+    Ok(best.into_iter().map(|(_, result)| result).collect())
+}
+
+//TODO: The additional arguments are a temporary hack on account of the way the operation atoms store references
+// to the runner & module state.  https://github.com/trueagi-io/hyperon-experimental/issues/410
+pub fn register_common_tokens(tref: &mut Tokenizer, _tokenizer: Shared<Tokenizer>, space: &DynSpace, metta: &Metta) {
+
+    let get_type_op = Atom::gnd(GetTypeOp::new(space.clone()));
+    tref.register_token(regex(r"get-type"), move |_| { get_type_op.clone() });
+    let get_type_space_op = Atom::gnd(stdlib::GetTypeSpaceOp{});
+    tref.register_token(regex(r"get-type-space"), move |_| { get_type_space_op.clone() });
+    let get_meta_type_op = Atom::gnd(stdlib::GetMetaTypeOp{});
+    tref.register_token(regex(r"get-metatype"), move |_| { get_meta_type_op.clone() });
+    let is_equivalent = Atom::gnd(IfEqualOp{});
+    tref.register_token(regex(r"if-equal"), move |_| { is_equivalent.clone() });
+    let new_space_op = Atom::gnd(stdlib::NewSpaceOp{});
+    tref.register_token(regex(r"new-space"), move |_| { new_space_op.clone() });
+    let add_atom_op = Atom::gnd(stdlib::AddAtomOp{});
+    tref.register_token(regex(r"add-atom"), move |_| { add_atom_op.clone() });
+    let remove_atom_op = Atom::gnd(stdlib::RemoveAtomOp{});
+    tref.register_token(regex(r"remove-atom"), move |_| { remove_atom_op.clone() });
+    let get_atoms_op = Atom::gnd(stdlib::GetAtomsOp{});
+    tref.register_token(regex(r"get-atoms"), move |_| { get_atoms_op.clone() });
+    let subtract_space_op = Atom::gnd(stdlib::SubtractSpaceOp{});
+    tref.register_token(regex(r"subtract"), move |_| { subtract_space_op.clone() });
+    let intersect_space_op = Atom::gnd(stdlib::IntersectSpaceOp{});
+    tref.register_token(regex(r"intersect"), move |_| { intersect_space_op.clone() });
+    let merge_dedup_op = Atom::gnd(stdlib::MergeDedupOp{});
+    tref.register_token(regex(r"merge-dedup"), move |_| { merge_dedup_op.clone() });
+    let clone_space_op = Atom::gnd(stdlib::CloneSpaceOp{});
+    tref.register_token(regex(r"clone-space"), move |_| { clone_space_op.clone() });
+    let filter_space_op = Atom::gnd(stdlib::FilterSpaceOp{});
+    tref.register_token(regex(r"filter-space"), move |_| { filter_space_op.clone() });
+    let rename_symbol_op = Atom::gnd(stdlib::RenameSymbolOp{});
+    tref.register_token(regex(r"rename-symbol"), move |_| { rename_symbol_op.clone() });
+    let assert_space_equal_op = Atom::gnd(AssertSpaceEqualOp{});
+    tref.register_token(regex(r"assert-space-equal"), move |_| { assert_space_equal_op.clone() });
+    let assert_pure_op = Atom::gnd(AssertPureOp{});
+    tref.register_token(regex(r"assert-pure"), move |_| { assert_pure_op.clone() });
+    let space_stats_op = Atom::gnd(stdlib::SpaceStatsOp{});
+    tref.register_token(regex(r"space-stats"), move |_| { space_stats_op.clone() });
+    let check_consistent_op = Atom::gnd(stdlib::CheckConsistentOp{});
+    tref.register_token(regex(r"check-consistent"), move |_| { check_consistent_op.clone() });
+    let space_to_expr_op = Atom::gnd(stdlib::SpaceToExprOp{});
+    tref.register_token(regex(r"space->expr"), move |_| { space_to_expr_op.clone() });
+    let expr_to_space_op = Atom::gnd(stdlib::ExprToSpaceOp{});
+    tref.register_token(regex(r"expr->space"), move |_| { expr_to_space_op.clone() });
+    let new_state_op = Atom::gnd(stdlib::NewStateOp{});
+    tref.register_token(regex(r"new-state"), move |_| { new_state_op.clone() });
+    let change_state_op = Atom::gnd(stdlib::ChangeStateOp{});
+    tref.register_token(regex(r"change-state!"), move |_| { change_state_op.clone() });
+    let get_state_op = Atom::gnd(stdlib::GetStateOp{});
+    tref.register_token(regex(r"get-state"), move |_| { get_state_op.clone() });
+    let nop_op = Atom::gnd(stdlib::NopOp{});
+    tref.register_token(regex(r"nop"), move |_| { nop_op.clone() });
+    let match_op = Atom::gnd(stdlib::MatchOp{});
+    tref.register_token(regex(r"match"), move |_| { match_op.clone() });
+    let rewrite_op = Atom::gnd(stdlib::RewriteOp{});
+    tref.register_token(regex(r"rewrite"), move |_| { rewrite_op.clone() });
+    let rewrite_first_op = Atom::gnd(stdlib::RewriteFirstOp{});
+    tref.register_token(regex(r"rewrite-first"), move |_| { rewrite_first_op.clone() });
+    let get_definitions_op = Atom::gnd(stdlib::GetDefinitionsOp{});
+    tref.register_token(regex(r"get-definitions"), move |_| { get_definitions_op.clone() });
+    let query_dict_op = Atom::gnd(stdlib::QueryDictOp{});
+    tref.register_token(regex(r"query-dict"), move |_| { query_dict_op.clone() });
+    let provable_op = Atom::gnd(stdlib::ProvableOp{});
+    tref.register_token(regex(r"provable"), move |_| { provable_op.clone() });
+    let index_atom_op = Atom::gnd(stdlib::IndexAtomOp{});
+    tref.register_token(regex(r"index-atom"), move |_| { index_atom_op.clone() });
+    let update_at_op = Atom::gnd(stdlib::UpdateAtOp{});
+    tref.register_token(regex(r"update-at"), move |_| { update_at_op.clone() });
+    let mod_space_op = Atom::gnd(stdlib::ModSpaceOp::new(metta.clone()));
+    tref.register_token(regex(r"mod-space!"), move |_| { mod_space_op.clone() });
+    let print_mods_op = Atom::gnd(stdlib::PrintModsOp::new(metta.clone()));
+    tref.register_token(regex(r"print-mods!"), move |_| { print_mods_op.clone() });
+    let unique_op = Atom::gnd(stdlib::UniqueAtomOp{});
+    tref.register_token(regex(r"unique-atom"), move |_| { unique_op.clone() });
+    let subtraction_op = Atom::gnd(stdlib::SubtractionAtomOp{});
+    tref.register_token(regex(r"subtraction-atom"), move |_| { subtraction_op.clone() });
+    let intersection_op = Atom::gnd(stdlib::IntersectionAtomOp{});
+    tref.register_token(regex(r"intersection-atom"), move |_| { intersection_op.clone() });
+    let union_op = Atom::gnd(stdlib::UnionAtomOp{});
+    tref.register_token(regex(r"union-atom"), move |_| { union_op.clone() });
+    let reverse_op = Atom::gnd(stdlib::ReverseAtomOp{});
+    tref.register_token(regex(r"reverse"), move |_| { reverse_op.clone() });
+    let zip_op = Atom::gnd(stdlib::ZipAtomOp{});
+    tref.register_token(regex(r"zip"), move |_| { zip_op.clone() });
+    let contains_op = Atom::gnd(stdlib::ContainsOp{});
+    tref.register_token(regex(r"contains"), move |_| { contains_op.clone() });
+    let contains_deep_op = Atom::gnd(stdlib::ContainsDeepOp{});
+    tref.register_token(regex(r"contains-deep"), move |_| { contains_deep_op.clone() });
+    let assoc_op = Atom::gnd(stdlib::AssocAtomOp{});
+    tref.register_token(regex(r"assoc"), move |_| { assoc_op.clone() });
+    let assoc_set_op = Atom::gnd(stdlib::AssocSetAtomOp{});
+    tref.register_token(regex(r"assoc-set"), move |_| { assoc_set_op.clone() });
+    let make_expr_op = Atom::gnd(stdlib::MakeExprOp{});
+    tref.register_token(regex(r"make-expr"), move |_| { make_expr_op.clone() });
+    let arity_op = Atom::gnd(stdlib::ArityOp{});
+    tref.register_token(regex(r"arity"), move |_| { arity_op.clone() });
+    let set_equal_op = Atom::gnd(stdlib::SetEqualOp{});
+    tref.register_token(regex(r"set-equal"), move |_| { set_equal_op.clone() });
+    let substitute_op = Atom::gnd(stdlib::SubstituteOp{});
+    tref.register_token(regex(r"substitute"), move |_| { substitute_op.clone() });
+    let replace_all_op = Atom::gnd(stdlib::ReplaceAllOp{});
+    tref.register_token(regex(r"replace-all"), move |_| { replace_all_op.clone() });
+    let atom_hash_op = Atom::gnd(stdlib::AtomHashOp{});
+    tref.register_token(regex(r"atom-hash"), move |_| { atom_hash_op.clone() });
+    let partial_op = Atom::gnd(stdlib::PartialOp{});
+    tref.register_token(regex(r"partial"), move |_| { partial_op.clone() });
+    let compose_op = Atom::gnd(stdlib::ComposeOp{});
+    tref.register_token(regex(r"compose"), move |_| { compose_op.clone() });
+    let get_head_op = Atom::gnd(stdlib::GetHeadOp{});
+    tref.register_token(regex(r"get-head"), move |_| { get_head_op.clone() });
+    let get_children_op = Atom::gnd(stdlib::GetChildrenOp{});
+    tref.register_token(regex(r"get-children"), move |_| { get_children_op.clone() });
+    let deep_copy_op = Atom::gnd(stdlib::DeepCopyOp{});
+    tref.register_token(regex(r"deep-copy"), move |_| { deep_copy_op.clone() });
+    let get_variables_op = Atom::gnd(stdlib::GetVariablesOp{});
+    tref.register_token(regex(r"get-variables"), move |_| { get_variables_op.clone() });
+    let windows_op = Atom::gnd(stdlib::WindowsOp{});
+    tref.register_token(regex(r"windows"), move |_| { windows_op.clone() });
+    let range_op = Atom::gnd(stdlib::RangeOp{});
+    tref.register_token(regex(r"range"), move |_| { range_op.clone() });
+    let current_bindings_op = Atom::gnd(stdlib::CurrentBindingsOp{});
+    tref.register_token(regex(r"current-bindings"), move |_| { current_bindings_op.clone() });
+    let parse_number_op = Atom::gnd(stdlib::ParseNumberOp{});
+    tref.register_token(regex(r"parse-number"), move |_| { parse_number_op.clone() });
+    let number_to_string_op = Atom::gnd(stdlib::NumberToStringOp{});
+    tref.register_token(regex(r"number->string"), move |_| { number_to_string_op.clone() });
+    let split_op = Atom::gnd(stdlib::SplitOp{});
+    tref.register_token(regex(r"split"), move |_| { split_op.clone() });
+    let join_op = Atom::gnd(stdlib::JoinOp{});
+    tref.register_token(regex(r"join"), move |_| { join_op.clone() });
+
+    #[cfg(feature = "pkg_mgmt")]
+    stdlib::pkg_mgmt_ops::register_pkg_mgmt_tokens(tref, metta);
+}
+
+/// Threads a value through a pipeline of operations: `(pipe 5 double inc)`
+/// is evaluated as `(inc (double 5))`. Operations are applied left to right.
+/// When an operation returns multiple results each of them is passed to the
+/// rest of the pipeline separately and all final results are returned.
+/// A branch which returns no results is dropped, a branch which returns an
+/// error is not evaluated further and the error is returned as a result.
+#[derive(Clone, Debug)]
+pub struct PipeOp {
+    space: DynSpace,
+}
+
+grounded_op!(PipeOp, "pipe");
+
+impl PipeOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for PipeOp {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_UNDEFINED
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for PipeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        log::debug!("PipeOp::execute: {:?}", args);
+        let arg_error = || ExecError::from("pipe expects initial value and sequence of operations as arguments");
+        let (value, ops) = args.split_first().ok_or_else(arg_error)?;
+
+        let mut values = vec![value.clone()];
+        for op in ops {
+            let mut next = Vec::new();
+            for value in values {
+                if atom_is_error(&value) {
+                    next.push(value);
+                } else {
+                    let call = Atom::expr([op.clone(), value]);
+                    next.extend(interpret(self.space.clone(), &call)?);
+                }
+            }
+            values = next;
+        }
+        Ok(values)
+    }
+}
+
+/// Deferred computation created by `delay`. Keeps an unevaluated atom and
+/// the space to evaluate it in. The atom is evaluated by `force` on the first
+/// call only, results are cached inside the promise and returned by the
+/// subsequent calls. Copies of the promise share the cache.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PromiseAtom {
+    atom: Atom,
+    space: DynSpace,
+    results: Rc<RefCell<Option<Vec<Atom>>>>,
+}
+
+impl PromiseAtom {
+    pub fn new(atom: Atom, space: DynSpace) -> Self {
+        Self{ atom, space, results: Rc::new(RefCell::new(None)) }
+    }
+
+    fn force(&self) -> Result<Vec<Atom>, ExecError> {
+        if let Some(results) = &*self.results.borrow() {
+            return Ok(results.clone());
+        }
+        let results = interpret(self.space.clone(), &self.atom)?;
+        *self.results.borrow_mut() = Some(results.clone());
+        Ok(results)
+    }
+}
+
+impl std::fmt::Display for PromiseAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Promise {})", self.atom)
+    }
+}
+
+impl Grounded for PromiseAtom {
+    fn type_(&self) -> Atom {
+        Atom::sym("Promise")
+    }
+}
+
+/// Operation created by `memoize`. Calls the wrapped operation and caches
+/// its results by arguments. Subsequent calls with equal arguments return
+/// cached results without evaluation, thus only pure operations should be
+/// memoized. Arguments are compared using `Atom::eq`. Copies of the
+/// operation share the cache.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MemoizedAtom {
+    op: Atom,
+    space: DynSpace,
+    cache: Rc<RefCell<Vec<MemoizedCall>>>,
+}
+
+/// Arguments of the call and its results.
+type MemoizedCall = (Vec<Atom>, Vec<Atom>);
+
+impl MemoizedAtom {
+    pub fn new(op: Atom, space: DynSpace) -> Self {
+        Self{ op, space, cache: Rc::new(RefCell::new(Vec::new())) }
+    }
+}
+
+impl std::fmt::Display for MemoizedAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(memoize {})", self.op)
+    }
+}
+
+impl Grounded for MemoizedAtom {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_UNDEFINED
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MemoizedAtom {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        if let Some((_, results)) = self.cache.borrow().iter().find(|(cached, _)| cached.as_slice() == args) {
+            return Ok(results.clone());
+        }
+        let call = Atom::expr(std::iter::once(self.op.clone()).chain(args.iter().cloned()).collect::<Vec<Atom>>());
+        let results = interpret(self.space.clone(), &call)?;
+        self.cache.borrow_mut().push((args.to_vec(), results.clone()));
+        Ok(results)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MemoizeOp {
+    space: DynSpace,
+}
+
+grounded_op!(MemoizeOp, "memoize");
+
+impl MemoizeOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for MemoizeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MemoizeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("memoize expects single operation as an argument");
+        let op = args.first().ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(MemoizedAtom::new(op.clone(), self.space.clone()))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DelayOp {
+    space: DynSpace,
+}
+
+grounded_op!(DelayOp, "delay");
+
+impl DelayOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for DelayOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, Atom::sym("Promise")])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for DelayOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("delay expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(PromiseAtom::new(atom.clone(), self.space.clone()))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ForceOp {}
+
+grounded_op!(ForceOp, "force");
+
+impl Grounded for ForceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, Atom::sym("Promise"), ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ForceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("force expects promise as an argument");
+        let promise = Atom::as_gnd::<PromiseAtom>(args.first().ok_or_else(arg_error)?).ok_or_else(arg_error)?;
+        promise.force()
+    }
+}
+
+/// Evaluates the argument and returns children of each resulting expression
+/// as separate results: `(flatten-results (superpose ((a b) (c))))` returns
+/// `a`, `b` and `c`. Results which are not expressions are returned as is,
+/// `Error` expressions are returned as is as well. Empty expression result
+/// produces no results.
+#[derive(Clone, Debug)]
+pub struct FlattenResultsOp {
+    space: DynSpace,
+}
+
+grounded_op!(FlattenResultsOp, "flatten-results");
+
+impl FlattenResultsOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Grounded for FlattenResultsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for FlattenResultsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("flatten-results expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        let results = interpret(self.space.clone(), atom)?;
+        let results = results.into_iter()
+            .flat_map(|result| match result {
+                Atom::Expression(_) if atom_is_error(&result) => vec![result],
+                Atom::Expression(expr) => expr.into_children(),
+                _ => vec![result],
+            })
+            .collect();
+        Ok(results)
+    }
+}
+
+//TODO: The additional arguments are a temporary hack on account of the way the operation atoms store references
+// to the runner & module state.  https://github.com/trueagi-io/hyperon-experimental/issues/410
+pub fn register_runner_tokens(tref: &mut Tokenizer, tokenizer: Shared<Tokenizer>, space: &DynSpace, metta: &Metta) {
+
+    let assert_equal_op = Atom::gnd(AssertEqualOp::new(space.clone()));
+    tref.register_token(regex(r"assertEqual"), move |_| { assert_equal_op.clone() });
+    let assert_equal_to_result_op = Atom::gnd(AssertEqualToResultOp::new(space.clone()));
+    tref.register_token(regex(r"assertEqualToResult"), move |_| { assert_equal_to_result_op.clone() });
+    let assert_alpha_equal_op = Atom::gnd(AssertAlphaEqualOp::new(space.clone()));
+    tref.register_token(regex(r"assert-alpha-equal"), move |_| { assert_alpha_equal_op.clone() });
+    let assert_not_equal_op = Atom::gnd(AssertNotEqualOp::new(space.clone()));
+    tref.register_token(regex(r"assert-not-equal"), move |_| { assert_not_equal_op.clone() });
+    let assert_reduces_to_op = Atom::gnd(AssertReducesToOp::new(space.clone()));
+    tref.register_token(regex(r"assert-reduces-to"), move |_| { assert_reduces_to_op.clone() });
+    let assert_subset_op = Atom::gnd(AssertSubsetOp{});
+    tref.register_token(regex(r"assert-subset"), move |_| { assert_subset_op.clone() });
+    let assert_acyclic_op = Atom::gnd(AssertAcyclicOp{});
+    tref.register_token(regex(r"assert-acyclic"), move |_| { assert_acyclic_op.clone() });
+    let assert_binding_op = Atom::gnd(AssertBindingOp{});
+    tref.register_token(regex(r"assert-binding"), move |_| { assert_binding_op.clone() });
+    let expect_error_op = Atom::gnd(ExpectErrorOp::new(space.clone()));
+    tref.register_token(regex(r"expect-error"), move |_| { expect_error_op.clone() });
+    let assert_throws_op = Atom::gnd(AssertThrowsOp::new(space.clone()));
+    tref.register_token(regex(r"assert-throws"), move |_| { assert_throws_op.clone() });
+    let assert_deterministic_op = Atom::gnd(AssertDeterministicOp::new(space.clone()));
+    tref.register_token(regex(r"assert-deterministic"), move |_| { assert_deterministic_op.clone() });
+    let assert_idempotent_op = Atom::gnd(AssertIdempotentOp::new(space.clone()));
+    tref.register_token(regex(r"assert-idempotent"), move |_| { assert_idempotent_op.clone() });
+    let assert_confluent_op = Atom::gnd(AssertConfluentOp::new(space.clone()));
+    tref.register_token(regex(r"assert-confluent"), move |_| { assert_confluent_op.clone() });
+    let assert_terminates_op = Atom::gnd(AssertTerminatesOp::new(space.clone()));
+    tref.register_token(regex(r"assert-terminates"), move |_| { assert_terminates_op.clone() });
+    let bench_op = Atom::gnd(BenchOp::new(space.clone()));
+    tref.register_token(regex(r"bench"), move |_| { bench_op.clone() });
+    let assert_matches_op = Atom::gnd(AssertMatchesOp::new(space.clone()));
+    tref.register_token(regex(r"assert-matches"), move |_| { assert_matches_op.clone() });
+    let assert_all_op = Atom::gnd(AssertAllOp::new(space.clone()));
+    tref.register_token(regex(r"assert-all"), move |_| { assert_all_op.clone() });
+    let assert_count_op = Atom::gnd(AssertCountOp::new(space.clone()));
+    tref.register_token(regex(r"assert-count"), move |_| { assert_count_op.clone() });
+    let assert_eventually_op = Atom::gnd(AssertEventuallyOp::new(space.clone()));
+    tref.register_token(regex(r"assert-eventually"), move |_| { assert_eventually_op.clone() });
+    let on_error_op = Atom::gnd(OnErrorOp::new(space.clone()));
+    tref.register_token(regex(r"on-error"), move |_| { on_error_op.clone() });
+    let superpose_op = Atom::gnd(SuperposeOp::new(space.clone()));
+    tref.register_token(regex(r"superpose"), move |_| { superpose_op.clone() });
+    let collapse_op = Atom::gnd(CollapseOp::new(space.clone()));
+    tref.register_token(regex(r"collapse"), move |_| { collapse_op.clone() });
+    let unique_op = Atom::gnd(UniqueOp::new(space.clone()));
+    tref.register_token(regex(r"unique"), move |_| { unique_op.clone() });
+    let limit_op = Atom::gnd(LimitOp::new(space.clone()));
+    tref.register_token(regex(r"limit"), move |_| { limit_op.clone() });
+    let foldr_op = Atom::gnd(FoldrOp::new(space.clone()));
+    tref.register_token(regex(r"foldr"), move |_| { foldr_op.clone() });
+    let zip_with_op = Atom::gnd(ZipWithOp::new(space.clone()));
+    tref.register_token(regex(r"zip-with"), move |_| { zip_with_op.clone() });
+    let destructuring_bind_op = Atom::gnd(DestructuringBindOp::new(space.clone()));
+    tref.register_token(regex(r"bind"), move |_| { destructuring_bind_op.clone() });
+    let case_op = Atom::gnd(CaseOp::new(space.clone()));
+    tref.register_token(regex(r"case"), move |_| { case_op.clone() });
+    let capture_op = Atom::gnd(CaptureOp::new(space.clone()));
+    tref.register_token(regex(r"capture"), move |_| { capture_op.clone() });
+    let pipe_op = Atom::gnd(PipeOp::new(space.clone()));
+    tref.register_token(regex(r"pipe"), move |_| { pipe_op.clone() });
+    let delay_op = Atom::gnd(DelayOp::new(space.clone()));
+    tref.register_token(regex(r"delay"), move |_| { delay_op.clone() });
+    let force_op = Atom::gnd(ForceOp{});
+    tref.register_token(regex(r"force"), move |_| { force_op.clone() });
+    let flatten_results_op = Atom::gnd(FlattenResultsOp::new(space.clone()));
+    tref.register_token(regex(r"flatten-results"), move |_| { flatten_results_op.clone() });
+    let collect_if_op = Atom::gnd(CollectIfOp::new(space.clone()));
+    tref.register_token(regex(r"collect-if"), move |_| { collect_if_op.clone() });
+    let take_while_op = Atom::gnd(TakeWhileOp::new(space.clone()));
+    tref.register_token(regex(r"take-while"), move |_| { take_while_op.clone() });
+    let drop_while_op = Atom::gnd(DropWhileOp::new(space.clone()));
+    tref.register_token(regex(r"drop-while"), move |_| { drop_while_op.clone() });
+    let max_by_op = Atom::gnd(MaxByOp::new(space.clone()));
+    tref.register_token(regex(r"max-by"), move |_| { max_by_op.clone() });
+    let min_by_op = Atom::gnd(MinByOp::new(space.clone()));
+    tref.register_token(regex(r"min-by"), move |_| { min_by_op.clone() });
+    let group_by_op = Atom::gnd(GroupByOp::new(space.clone()));
+    tref.register_token(regex(r"group-by"), move |_| { group_by_op.clone() });
+    let count_distinct_op = Atom::gnd(CountDistinctOp::new(space.clone()));
+    tref.register_token(regex(r"count-distinct"), move |_| { count_distinct_op.clone() });
+    let interleave_op = Atom::gnd(InterleaveOp::new(space.clone()));
+    tref.register_token(regex(r"interleave"), move |_| { interleave_op.clone() });
+    let map_space_op = Atom::gnd(MapSpaceOp::new(space.clone()));
+    tref.register_token(regex(r"map-space"), move |_| { map_space_op.clone() });
+    let partition_space_op = Atom::gnd(PartitionSpaceOp::new(space.clone()));
+    tref.register_token(regex(r"partition-space"), move |_| { partition_space_op.clone() });
+    let reduce_once_op = Atom::gnd(ReduceOnceOp::new(space.clone()));
+    tref.register_token(regex(r"reduce-once"), move |_| { reduce_once_op.clone() });
+    let trace_reductions_op = Atom::gnd(TraceReductionsOp::new(space.clone()));
+    tref.register_token(regex(r"trace-reductions"), move |_| { trace_reductions_op.clone() });
+    let with_space_op = Atom::gnd(WithSpaceOp::new(space.clone()));
+    tref.register_token(regex(r"with-space"), move |_| { with_space_op.clone() });
+    let apply_rules_op = Atom::gnd(ApplyRulesOp{});
+    tref.register_token(regex(r"apply-rules"), move |_| { apply_rules_op.clone() });
+    let memoize_op = Atom::gnd(MemoizeOp::new(space.clone()));
+    tref.register_token(regex(r"memoize"), move |_| { memoize_op.clone() });
+    let sample_op = Atom::gnd(SampleOp::new(space.clone()));
+    tref.register_token(regex(r"sample"), move |_| { sample_op.clone() });
+    let sample_seeded_op = Atom::gnd(SampleSeededOp::new(space.clone()));
+    tref.register_token(regex(r"sample-seeded"), move |_| { sample_seeded_op.clone() });
+    let pragma_op = Atom::gnd(stdlib::PragmaOp::new(metta.settings().clone()));
+    tref.register_token(regex(r"pragma!"), move |_| { pragma_op.clone() });
+    let import_op = Atom::gnd(stdlib::ImportOp::new(metta.clone()));
+    tref.register_token(regex(r"import!"), move |_| { import_op.clone() });
+    let include_op = Atom::gnd(stdlib::IncludeOp::new(metta.clone()));
+    tref.register_token(regex(r"include"), move |_| { include_op.clone() });
+    let bind_op = Atom::gnd(stdlib::BindOp::new(tokenizer.clone()));
+    tref.register_token(regex(r"bind!"), move |_| { bind_op.clone() });
+    let parse_op = Atom::gnd(stdlib::ParseOp::new(tokenizer.clone()));
+    tref.register_token(regex(r"parse"), move |_| { parse_op.clone() });
+    let load_string_op = Atom::gnd(stdlib::LoadStringOp::new(tokenizer.clone()));
+    tref.register_token(regex(r"load-string"), move |_| { load_string_op.clone() });
+    let repr_op = Atom::gnd(stdlib::ReprOp{});
+    tref.register_token(regex(r"repr"), move |_| { repr_op.clone() });
+    let trace_op = Atom::gnd(stdlib::TraceOp{});
+    tref.register_token(regex(r"trace!"), move |_| { trace_op.clone() });
+    let trace_value_op = Atom::gnd(TraceValueOp::new(space.clone()));
+    tref.register_token(regex(r"trace"), move |_| { trace_value_op.clone() });
+    let println_op = Atom::gnd(stdlib::PrintlnOp{});
+    tref.register_token(regex(r"println!"), move |_| { println_op.clone() });
+    let format_args_op = Atom::gnd(stdlib::FormatArgsOp{});
+    tref.register_token(regex(r"format-args"), move |_| { format_args_op.clone() });
+    let print_alternatives_op = Atom::gnd(PrintAlternativesOp{});
+    tref.register_token(regex(r"print-alternatives!"), move |_| { print_alternatives_op.clone() });
+    let sealed_op = Atom::gnd(stdlib::SealedOp{});
+    tref.register_token(regex(r"sealed"), move |_| { sealed_op.clone() });
+    // &self should be updated
+    // TODO: adding &self might be done not by stdlib, but by MeTTa itself.
+    // TODO: adding &self introduces self referencing and thus prevents space
+    // from being freed. There are two options to eliminate this. (1) use weak
+    // pointer and somehow use the same type to represent weak and strong
+    // pointers to the atomspace. (2) resolve &self in GroundingSpace::query
+    // method without adding it into container.
+    let self_atom = Atom::gnd(space.clone());
+    tref.register_token(regex(r"&self"), move |_| { self_atom.clone() });
+}
+
+pub fn register_rust_stdlib_tokens(target: &mut Tokenizer) {
+    let mut rust_tokens = Tokenizer::new();
+    let tref = &mut rust_tokens;
+
+    tref.register_fallible_token(regex(r"[\-\+]?\d+"),
+        |token| { Ok(Atom::gnd(Number::from_int_str(token)?)) });
+    tref.register_fallible_token(regex(r"[\-\+]?\d+\.\d+"),
+        |token| { Ok(Atom::gnd(Number::from_float_str(token)?)) });
+    tref.register_fallible_token(regex(r"[\-\+]?\d+(\.\d+)?[eE][\-\+]?\d+"),
+        |token| { Ok(Atom::gnd(Number::from_float_str(token)?)) });
+    tref.register_token(regex(r"True|False"),
+        |token| { Atom::gnd(Bool::from_str(token)) });
+    tref.register_token(regex(r#"(?s)^".*"$"#),
+        |token| { let mut s = String::from(token); s.remove(0); s.pop(); Atom::gnd(Str::from_string(s)) });
+    let sum_op = Atom::gnd(SumOp{});
+    tref.register_token(regex(r"\+"), move |_| { sum_op.clone() });
+    let sub_op = Atom::gnd(SubOp{});
+    tref.register_token(regex(r"\-"), move |_| { sub_op.clone() });
+    let mul_op = Atom::gnd(MulOp{});
+    tref.register_token(regex(r"\*"), move |_| { mul_op.clone() });
+    let div_op = Atom::gnd(DivOp{});
+    tref.register_token(regex(r"/"), move |_| { div_op.clone() });
+    let mod_op = Atom::gnd(ModOp{});
+    tref.register_token(regex(r"%"), move |_| { mod_op.clone() });
+    let lt_op = Atom::gnd(LessOp{});
+    tref.register_token(regex(r"<"), move |_| { lt_op.clone() });
+    let gt_op = Atom::gnd(GreaterOp{});
+    tref.register_token(regex(r">"), move |_| { gt_op.clone() });
+    let le_op = Atom::gnd(LessEqOp{});
+    tref.register_token(regex(r"<="), move |_| { le_op.clone() });
+    let ge_op = Atom::gnd(GreaterEqOp{});
+    tref.register_token(regex(r">="), move |_| { ge_op.clone() });
+    let eq_op = Atom::gnd(stdlib::EqualOp{});
+    tref.register_token(regex(r"=="), move |_| { eq_op.clone() });
+    let approx_equal_op = Atom::gnd(ApproxEqualOp{});
+    tref.register_token(regex(r"approx-equal"), move |_| { approx_equal_op.clone() });
+    let to_float_op = Atom::gnd(ToFloatOp{});
+    tref.register_token(regex(r"to-float"), move |_| { to_float_op.clone() });
+    let to_int_op = Atom::gnd(ToIntOp{});
+    tref.register_token(regex(r"to-int"), move |_| { to_int_op.clone() });
+    let and_op = Atom::gnd(AndOp{});
+    tref.register_token(regex(r"and"), move |_| { and_op.clone() });
+    let or_op = Atom::gnd(OrOp{});
+    tref.register_token(regex(r"or"), move |_| { or_op.clone() });
+    let not_op = Atom::gnd(NotOp{});
+    tref.register_token(regex(r"not"), move |_| { not_op.clone() });
+    // NOTE: xor and flip are absent in Python intentionally for conversion testing
+    let xor_op = Atom::gnd(XorOp{});
+    tref.register_token(regex(r"xor"), move |_| { xor_op.clone() });
+    let flip_op = Atom::gnd(FlipOp{});
+    tref.register_token(regex(r"flip"), move |_| { flip_op.clone() });
+
+    target.move_front(&mut rust_tokens);
+}
+
+pub static METTA_CODE: &'static str = include_str!("stdlib_minimal.metta");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::SExprParser;
+    use crate::metta::runner::EnvBuilder;
+    use crate::metta::runner::string::Str;
+    use crate::matcher::atoms_are_equivalent;
+    use crate::common::Operation;
+    use crate::common::test_utils::metta_space;
+
+    use std::convert::TryFrom;
+    use std::fmt::Display;
+    use regex::Regex;
+
+    fn run_program(program: &str) -> Result<Vec<Vec<Atom>>, String> {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        metta.run(SExprParser::new(program))
+    }
+
+    #[test]
+    fn get_type_op() {
+        let space = DynSpace::new(metta_space("
+            (: B Type)
+            (: C Type)
+            (: A B)
+            (: A C)
+        "));
+
+        let get_type_op = GetTypeOp::new(space.clone());
+        assert_eq_no_order!(get_type_op.execute(&mut vec![sym!("A"), expr!({space.clone()})]).unwrap(),
+            vec![sym!("B"), sym!("C")]);
+    }
+
+    #[test]
+    fn get_type_op_non_valid_atom() {
+        let space = DynSpace::new(metta_space("
+            (: f (-> Number String))
+            (: 42 Number)
+            (: \"test\" String)
+        "));
+
+        let get_type_op = GetTypeOp::new(space.clone());
+        assert_eq_no_order!(get_type_op.execute(&mut vec![expr!("f" "42"), expr!({space.clone()})]).unwrap(),
+            vec![sym!("String")]);
+        assert_eq_no_order!(get_type_op.execute(&mut vec![expr!("f" "\"test\""), expr!({space.clone()})]).unwrap(),
+            vec![EMPTY_SYMBOL]);
+    }
+
+
+    #[test]
+    fn metta_car_atom() {
+        let result = run_program("!(eval (car-atom (A $b)))");
+        assert_eq!(result, Ok(vec![vec![expr!("A")]]));
+        let result = run_program("!(eval (car-atom ($a B)))");
+        assert_eq!(result, Ok(vec![vec![expr!(a)]]));
         let result = run_program("!(eval (car-atom ()))");
         assert_eq!(result, Ok(vec![vec![expr!("Error" ("car-atom" ()) {Str::from_str("car-atom expects a non-empty expression as an argument")})]]));
         let result = run_program("!(eval (car-atom A))");
@@ -609,256 +3100,1706 @@ mod tests {
     }
 
     #[test]
-    fn metta_cdr_atom() {
-        assert_eq!(run_program(&format!("!(cdr-atom (a b c))")), Ok(vec![vec![expr!("b" "c")]]));
-        assert_eq!(run_program(&format!("!(cdr-atom ($a b $c))")), Ok(vec![vec![expr!("b" c)]]));
-        assert_eq!(run_program(&format!("!(cdr-atom ())")), Ok(vec![vec![expr!("Error" ("cdr-atom" ()) {Str::from_str("cdr-atom expects a non-empty expression as an argument")})]]));
-        assert_eq!(run_program(&format!("!(cdr-atom a)")), Ok(vec![vec![expr!("Error" ("cdr-atom" "a") {Str::from_str("cdr-atom expects a non-empty expression as an argument")})]]));
-        assert_eq!(run_program(&format!("!(cdr-atom $a)")), Ok(vec![vec![expr!("Error" ("cdr-atom" a) {Str::from_str("cdr-atom expects a non-empty expression as an argument")})]]));
+    fn metta_cdr_atom() {
+        assert_eq!(run_program(&format!("!(cdr-atom (a b c))")), Ok(vec![vec![expr!("b" "c")]]));
+        assert_eq!(run_program(&format!("!(cdr-atom ($a b $c))")), Ok(vec![vec![expr!("b" c)]]));
+        assert_eq!(run_program(&format!("!(cdr-atom ())")), Ok(vec![vec![expr!("Error" ("cdr-atom" ()) {Str::from_str("cdr-atom expects a non-empty expression as an argument")})]]));
+        assert_eq!(run_program(&format!("!(cdr-atom a)")), Ok(vec![vec![expr!("Error" ("cdr-atom" "a") {Str::from_str("cdr-atom expects a non-empty expression as an argument")})]]));
+        assert_eq!(run_program(&format!("!(cdr-atom $a)")), Ok(vec![vec![expr!("Error" ("cdr-atom" a) {Str::from_str("cdr-atom expects a non-empty expression as an argument")})]]));
+    }
+
+    #[test]
+    fn metta_index_atom() {
+        assert_eq!(run_program(&format!("!(index-atom (5 4 3 2 1) 2)")), Ok(vec![vec![expr!({Number::Integer(3)})]]));
+        assert_eq!(run_program(&format!("!(index-atom (A B C D E) 5)")), Ok(vec![vec![expr!("Error" ({ stdlib::IndexAtomOp{} } ("A" "B" "C" "D" "E") {Number::Integer(5)}) "Index is out of bounds")]]));
+    }
+
+    #[test]
+    fn metta_update_at() {
+        assert_eq!(run_program("!(update-at 1 x (a b c))"), Ok(vec![vec![expr!("a" "x" "c")]]));
+        assert_eq!(run_program("!(update-at 0 (x y) (a b c))"), Ok(vec![vec![expr!(("x" "y") "b" "c")]]));
+        assert_eq!(run_program("!(update-at 3 x (a b c))"), Ok(vec![vec![expr!("Error" ({stdlib::UpdateAtOp{}} {Number::Integer(3)} "x" ("a" "b" "c")) "Index is out of bounds")]]));
+        assert_eq!(run_program("!(update-at -1 x (a b c))"), Ok(vec![vec![expr!("Error" ({stdlib::UpdateAtOp{}} {Number::Integer(-1)} "x" ("a" "b" "c")) "Index is out of bounds")]]));
+    }
+
+    #[test]
+    fn metta_switch() {
+        let result = run_program("!(eval (switch (A $b) ( (($a B) ($b $a)) ((B C) (C B)) )))");
+        assert_eq!(result, Ok(vec![vec![expr!("B" "A")]]));
+        let result = run_program("!(eval (switch (A $b) ( ((B C) (C B)) (($a B) ($b $a)) )))");
+        assert_eq!(result, Ok(vec![vec![expr!("B" "A")]]));
+        let result = run_program("!(eval (switch (A $b) ( ((B C) (C B)) ((D E) (E B)) )))");
+        assert_eq!(result, Ok(vec![vec![]]));
+    }
+
+    #[test]
+    fn metta_case_empty() {
+        let result = run_program("!(case Empty ( (ok ok) (Empty nok) ))");
+        assert_eq!(result, Ok(vec![vec![expr!("nok")]]));
+        let result = run_program("!(case (unify (C B) (C B) ok Empty) ( (ok ok) (Empty nok) ))");
+        assert_eq!(result, Ok(vec![vec![expr!("ok")]]));
+        let result = run_program("!(case (unify (B C) (C B) ok nok) ( (ok ok) (nok nok) ))");
+        assert_eq!(result, Ok(vec![vec![expr!("nok")]]));
+        let result = run_program("!(case (unify (B C) (C B) ok Empty) ( (ok ok) (Empty nok) ))");
+        assert_eq!(result, Ok(vec![vec![expr!("nok")]]));
+    }
+
+    #[test]
+    fn metta_is_function() {
+        let result = run_program("!(eval (is-function (-> $t)))");
+        assert_eq!(result, Ok(vec![vec![expr!({Bool(true)})]]));
+        let result = run_program("!(eval (is-function (A $t)))");
+        assert_eq!(result, Ok(vec![vec![expr!({Bool(false)})]]));
+        let result = run_program("!(eval (is-function %Undefined%))");
+        assert_eq!(result, Ok(vec![vec![expr!({Bool(false)})]]));
+    }
+
+    #[test]
+    fn metta_type_cast() {
+        assert_eq!(run_program("(: a A) !(eval (type-cast a A &self))"), Ok(vec![vec![expr!("a")]]));
+        assert_eq!(run_program("(: a A) !(eval (type-cast a B &self))"), Ok(vec![vec![expr!("Error" "a" "BadType")]]));
+        assert_eq!(run_program("(: a A) !(eval (type-cast a %Undefined% &self))"), Ok(vec![vec![expr!("a")]]));
+        assert_eq!(run_program("!(eval (type-cast a B &self))"), Ok(vec![vec![expr!("a")]]));
+        assert_eq!(run_program("!(eval (type-cast 42 Number &self))"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+        assert_eq!(run_program("!(eval (type-cast 42 %Undefined% &self))"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+        assert_eq!(run_program("(: a A) !(eval (type-cast a Atom &self))"), Ok(vec![vec![expr!("a")]]));
+        assert_eq!(run_program("(: a A) !(eval (type-cast a Symbol &self))"), Ok(vec![vec![expr!("a")]]));
+        assert_eq!(run_program("!(eval (type-cast 42 Grounded &self))"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+        assert_eq!(run_program("!(eval (type-cast () Expression &self))"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(eval (type-cast (a b) Expression &self))"), Ok(vec![vec![expr!("a" "b")]]));
+        assert_eq!(run_program("!(eval (type-cast $v Variable &self))"), Ok(vec![vec![expr!(v)]]));
+        assert_eq!(run_program("(: a A) (: b B) !(eval (type-cast (a b) (A B) &self))"), Ok(vec![vec![expr!("a" "b")]]));
+        assert_eq!(run_program("(: a A) (: a B) !(eval (type-cast a A &self))"), Ok(vec![vec![expr!("a")]]));
+    }
+
+    #[test]
+    fn metta_filter_atom() {
+        assert_eq!(run_program("!(eval (filter-atom () $x (eval (if-error $x False True))))"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(eval (filter-atom (a (b) $c) $x (eval (if-error $x False True))))"), Ok(vec![vec![expr!("a" ("b") c)]]));
+        assert_eq!(run_program("!(eval (filter-atom (a (Error (b) \"Test error\") $c) $x (eval (if-error $x False True))))"), Ok(vec![vec![expr!("a" c)]]));
+    }
+
+    #[test]
+    fn metta_map_atom() {
+        assert_eq!(run_program("!(eval (map-atom () $x ($x mapped)))"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(eval (map-atom (a (b) $c) $x (mapped $x)))"), Ok(vec![vec![expr!(("mapped" "a") ("mapped" ("b")) ("mapped" c))]]));
+    }
+
+    #[test]
+    fn metta_foldl_atom() {
+        assert_eq!(run_program("!(eval (foldl-atom () 1 $a $b (eval (+ $a $b))))"), Ok(vec![vec![expr!({Number::Integer(1)})]]));
+        assert_eq!(run_program("!(eval (foldl-atom (1 2 3) 0 $a $b (eval (+ $a $b))))"), Ok(vec![vec![expr!({Number::Integer(6)})]]));
+    }
+
+    #[test]
+    fn metta_foldr() {
+        let program = "
+            (= (cons $x $xs) ($x $xs))
+
+            !(foldr cons nil (a b c))
+            !(foldr + 1 ())
+            !(foldr - 0 (1 2 3))
+            !(eval (foldl-atom (1 2 3) 0 $a $b (eval (- $a $b))))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("a" ("b" ("c" "nil")))],
+            vec![expr!({Number::Integer(1)})],
+            vec![expr!({Number::Integer(2)})],
+            vec![expr!({Number::Integer(-6)})],
+        ]));
+    }
+
+    #[test]
+    fn metta_zip_with() {
+        let program = "
+            (= (both $x $y) $x)
+            (= (both $x $y) $y)
+
+            !(zip-with + (1 2 3) (10 20 30))
+            !(zip-with + (1 2 3) (10 20))
+            !(zip-with + () (10 20))
+            !(zip-with both (a b) (c d))
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[0], vec![expr!({Number::Integer(11)} {Number::Integer(22)} {Number::Integer(33)})]);
+        assert_eq!(result[1], vec![expr!({Number::Integer(11)} {Number::Integer(22)})]);
+        assert_eq!(result[2], vec![expr!()]);
+        assert_eq_no_order!(result[3], vec![expr!("a" "b"), expr!("a" "d"), expr!("c" "b"), expr!("c" "d")]);
+    }
+
+    #[test]
+    fn metta_interpret_single_atom_as_atom() {
+        let result = run_program("!(metta A Atom &self)");
+        assert_eq!(result, Ok(vec![vec![expr!("A")]]));
+    }
+
+    #[test]
+    fn metta_interpret_single_atom_as_meta_type() {
+        assert_eq!(run_program("!(metta A Symbol &self)"), Ok(vec![vec![expr!("A")]]));
+        assert_eq!(run_program("!(metta $x Variable &self)"), Ok(vec![vec![expr!(x)]]));
+        assert_eq!(run_program("!(metta (A B) Expression &self)"), Ok(vec![vec![expr!("A" "B")]]));
+        assert_eq!(run_program("!(metta 42 Grounded &self)"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+    }
+
+    #[test]
+    fn metta_interpret_symbol_or_grounded_value_as_type() {
+        assert_eq!(run_program("(: a A) !(metta a A &self)"), Ok(vec![vec![expr!("a")]]));
+        assert_eq!(run_program("(: a A) !(metta a B &self)"), Ok(vec![vec![expr!("Error" "a" "BadType")]]));
+        assert_eq!(run_program("!(metta 42 Number &self)"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+    }
+
+    #[test]
+    fn metta_interpret_variable_as_type() {
+        assert_eq!(run_program("!(metta $x %Undefined% &self)"), Ok(vec![vec![expr!(x)]]));
+        assert_eq!(run_program("!(metta $x SomeType &self)"), Ok(vec![vec![expr!(x)]]));
+    }
+
+    #[test]
+    fn metta_interpret_empty_expression_as_type() {
+        assert_eq!(run_program("!(metta () %Undefined% &self)"), Ok(vec![vec![expr!(())]]));
+        assert_eq!(run_program("!(metta () SomeType &self)"), Ok(vec![vec![expr!(())]]));
+    }
+
+    #[test]
+    fn metta_interpret_single_atom_as_variable_type() {
+        let result = run_program("
+            (: S Int)
+            !(chain (metta S $t &self) $res (: $res $t))
+        ");
+        assert_eq!(result, Ok(vec![vec![expr!(":" "S" "Int")]]));
+    }
+
+    #[test]
+    fn metta_interpret_func() {
+        let result = run_program("
+            (: a T)
+            (: foo (-> T T))
+            (= (foo $x) $x)
+            (= (bar $x) $x)
+            !(metta (foo (bar a)) %Undefined% &self)
+        ");
+        assert_eq!(result, Ok(vec![vec![expr!("a")]]));
+        let result = run_program("
+            (: b B)
+            (: foo (-> T T))
+            (= (foo $x) $x)
+            !(metta (foo b) %Undefined% &self)
+        ");
+        assert_eq!(result, Ok(vec![vec![expr!("Error" "b" "BadType")]]));
+        let result = run_program("
+            (: Nil (List $t))
+            (: Z Nat)
+            (: S (-> Nat Nat))
+            (: Cons (-> $t (List $t) (List $t)))
+            !(metta (Cons S (Cons Z Nil)) %Undefined% &self)
+        ");
+        assert_eq!(result, Ok(vec![vec![expr!("Error" ("Cons" "Z" "Nil") "BadType")]]));
+    }
+
+    #[test]
+    fn metta_interpret_tuple() {
+        assert_eq!(run_program("!(metta () %Undefined% &self)"), Ok(vec![vec![expr!(())]]));
+        assert_eq!(run_program("!(metta (a) %Undefined% &self)"), Ok(vec![vec![expr!(("a"))]]));
+        assert_eq!(run_program("!(metta (a b) %Undefined% &self)"), Ok(vec![vec![expr!(("a" "b"))]]));
+        assert_eq!(run_program("
+            (= (foo $x) (bar $x))
+            (= (bar $x) (baz $x))
+            (= (baz $x) $x)
+            !(metta ((foo A) (foo B)) %Undefined% &self)
+        "), Ok(vec![vec![expr!("A" "B")]]));
+    }
+
+    #[test]
+    fn metta_interpret_expression_as_type() {
+        assert_eq!(run_program("(= (foo $x) $x) !(metta (foo a) %Undefined% &self)"), Ok(vec![vec![expr!("a")]]));
+        assert_eq!(run_program("!(metta (foo a) %Undefined% &self)"), Ok(vec![vec![expr!("foo" "a")]]));
+        assert_eq!(run_program("!(metta () SomeType &self)"), Ok(vec![vec![expr!(())]]));
+    }
+
+    #[test]
+    fn metta_interpret_single_atom_with_two_types() {
+        let result = run_program("(: a A) (: a B) !(metta a %Undefined% &self)");
+        assert_eq!(result, Ok(vec![vec![expr!("a")]]));
+    }
+
+    #[test]
+    fn metta_assert_equal_op() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let assert = AssertEqualOp::new(metta.space().clone());
+        let program = "
+            (= (foo $x) $x)
+            (= (bar $x) $x)
+        ";
+        assert_eq!(metta.run(SExprParser::new(program)), Ok(vec![]));
+        assert_eq!(metta.run(SExprParser::new("!(assertEqual (foo A) (bar A))")), Ok(vec![
+            vec![UNIT_ATOM()],
+        ]));
+        assert_eq!(metta.run(SExprParser::new("!(assertEqual (foo A) (bar B))")), Ok(vec![
+            vec![expr!("Error" ({assert.clone()} ("foo" "A") ("bar" "B")) "\nExpected: [B]\nGot: [A]\nMissed result: B")],
+        ]));
+        assert_eq!(metta.run(SExprParser::new("!(assertEqual (foo A) Empty)")), Ok(vec![
+            vec![expr!("Error" ({assert.clone()} ("foo" "A") "Empty") "\nExpected: []\nGot: [A]\nExcessive result: A")]
+        ]));
+    }
+
+    #[test]
+    fn metta_assert_not_equal_op() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let assert = AssertNotEqualOp::new(metta.space().clone());
+        let program = "
+            (= (foo $x) $x)
+            (= (bar $x) $x)
+        ";
+        assert_eq!(metta.run(SExprParser::new(program)), Ok(vec![]));
+        assert_eq!(metta.run(SExprParser::new("!(assert-not-equal (foo A) (bar B))")), Ok(vec![
+            vec![UNIT_ATOM()],
+        ]));
+        assert_eq!(metta.run(SExprParser::new("!(assert-not-equal (foo A) Empty)")), Ok(vec![
+            vec![UNIT_ATOM()],
+        ]));
+        assert_eq!(metta.run(SExprParser::new("!(assert-not-equal (foo A) (bar A))")), Ok(vec![
+            vec![expr!("Error" ({assert.clone()} ("foo" "A") ("bar" "A")) "\nExpected not equal results\nBoth got: [A]")],
+        ]));
+    }
+
+    #[test]
+    fn metta_assert_equal_to_result_op() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let assert = AssertEqualToResultOp::new(metta.space().clone());
+        let program = "
+            (= (foo) A)
+            (= (foo) B)
+            (= (bar) C)
+            (= (baz) D)
+            (= (baz) D)
+        ";
+        assert_eq!(metta.run(SExprParser::new(program)), Ok(vec![]));
+        assert_eq!(metta.run(SExprParser::new("!(assertEqualToResult (foo) (A B))")), Ok(vec![
+            vec![UNIT_ATOM()],
+        ]));
+        assert_eq!(metta.run(SExprParser::new("!(assertEqualToResult (bar) (A))")), Ok(vec![
+            vec![expr!("Error" ({assert.clone()} ("bar") ("A")) "\nExpected: [A]\nGot: [C]\nMissed result: A")],
+        ]));
+        assert_eq!(metta.run(SExprParser::new("!(assertEqualToResult (baz) (D))")), Ok(vec![
+            vec![expr!("Error" ({assert.clone()} ("baz") ("D")) "\nExpected: [D]\nGot: [D, D]\nExcessive result: D")]
+        ]));
+    }
+
+    #[test]
+    fn metta_expect_error_op() {
+        assert_eq!(run_program("!(expect-error (+ 1 \"a\"))"), Ok(vec![vec![]]));
+        assert_eq!(run_program("!(expect-error (Error foo \"failed\"))"), Ok(vec![vec![]]));
+        assert_eq!(run_program("!(expect-error (+ 1 2))"), Ok(vec![vec![
+            expr!("Error" ({ExpectErrorOp::new(DynSpace::new(GroundingSpace::new()))} ({SumOp{}} {Number::Integer(1)} {Number::Integer(2)})) "\nExpected error\nGot: [3]")
+        ]]));
+    }
+
+    #[test]
+    fn metta_assert_throws_op() {
+        let assert = AssertThrowsOp::new(DynSpace::new(GroundingSpace::new()));
+        assert_eq!(run_program("!(assert-throws (Error foo \"arity mismatch\") \"arity\")"), Ok(vec![vec![]]));
+        assert_eq!(run_program("!(assert-throws (Error foo BadType) \"Bad\")"), Ok(vec![vec![]]));
+        assert_eq!(run_program("!(assert-throws (Error foo \"failed\") \"arity\")"), Ok(vec![vec![
+            expr!("Error" ({assert.clone()} ("Error" "foo" {Str::from_str("failed")}) {Str::from_str("arity")}) "\nExpected error containing \"arity\"\nGot errors: [\"failed\"]")
+        ]]));
+        assert_eq!(run_program("!(assert-throws (+ 1 2) \"arity\")"), Ok(vec![vec![
+            expr!("Error" ({assert.clone()} ({SumOp{}} {Number::Integer(1)} {Number::Integer(2)}) {Str::from_str("arity")}) "\nExpected error containing \"arity\"\nGot: [3]")
+        ]]));
+        assert_eq!(run_program("!(assert-throws (index-atom (a b) 5) \"out of bounds\")"), Ok(vec![vec![]]));
+        assert_eq!(run_program("!(assert-throws (index-atom (a b) 5) \"arity\")"), Ok(vec![vec![
+            expr!("Error" ({assert.clone()} ({stdlib::IndexAtomOp{}} ("a" "b") {Number::Integer(5)}) {Str::from_str("arity")}) "\nExpected error containing \"arity\"\nGot errors: [\"Index is out of bounds\"]")
+        ]]));
+    }
+
+    #[test]
+    fn metta_assert_deterministic_op() {
+        let assert = AssertDeterministicOp::new(DynSpace::new(GroundingSpace::new()));
+        let program = "
+            (= (f) A)
+            (= (g) B)
+            (= (g) B)
+            !(assert-deterministic (f))
+            !(assert-deterministic (g))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("A")],
+            vec![expr!("Error" ({assert.clone()} ("g")) "\nExpected single result\nGot 2 results: [B, B]")],
+        ]));
+        assert_eq!(run_program("!(assert-deterministic Empty)"), Ok(vec![vec![
+            expr!("Error" ({assert} "Empty") "\nExpected single result\nGot 0 results: []")
+        ]]));
+    }
+
+    #[test]
+    fn metta_assert_idempotent_op() {
+        let program = "
+            (= (plus Z $y) $y)
+            (= (plus (S $k) $y) (S (plus $k $y)))
+            (= (g) (f $x))
+            !(assert-idempotent (plus (S Z) Z))
+            !(assert-idempotent (g))
+        ";
+        assert_eq!(run_program(program), Ok(vec![vec![UNIT_ATOM()], vec![UNIT_ATOM()]]));
+    }
+
+    #[derive(Clone, Debug)]
+    struct ReadyAfterFirstCall {
+        called: Rc<std::cell::Cell<bool>>,
+    }
+
+    impl PartialEq for ReadyAfterFirstCall {
+        fn eq(&self, other: &Self) -> bool {
+            Rc::ptr_eq(&self.called, &other.called)
+        }
+    }
+
+    impl Grounded for ReadyAfterFirstCall {
+        fn type_(&self) -> Atom {
+            expr!("->" "Atom")
+        }
+        fn as_execute(&self) -> Option<&dyn CustomExecute> {
+            Some(self)
+        }
+    }
+
+    impl CustomExecute for ReadyAfterFirstCall {
+        fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+            match self.called.replace(true) {
+                false => Err(ExecError::NoReduce),
+                true => Ok(vec![Atom::sym("done")]),
+            }
+        }
+    }
+
+    impl Display for ReadyAfterFirstCall {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "ready-after-first-call")
+        }
+    }
+
+    #[test]
+    fn metta_assert_idempotent_op_changing_result() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let ready = ReadyAfterFirstCall{ called: Rc::new(std::cell::Cell::new(false)) };
+        let ready_atom = Atom::gnd(ready.clone());
+        metta.tokenizer().borrow_mut().register_token(Regex::new("ready-after-first-call").unwrap(),
+            move |_| ready_atom.clone());
+        let assert = AssertIdempotentOp::new(DynSpace::new(GroundingSpace::new()));
+
+        assert_eq!(metta.run(SExprParser::new("!(assert-idempotent (ready-after-first-call))")),
+            Ok(vec![vec![expr!("Error" ({assert} ({ready})) "\nExpected result to be unchanged: (ready-after-first-call)\nGot: [done]")]]));
+    }
+
+    #[test]
+    fn metta_assert_confluent_op() {
+        let program = "
+            (= (plus Z $y) $y)
+            (= (plus (S $k) $y) (S (plus $k $y)))
+            !(assert-confluent (plus (S Z) (plus (S Z) Z)))
+        ";
+        assert_eq!(run_program(program), Ok(vec![vec![UNIT_ATOM()]]));
+
+        let program = "
+            (= (f $x) (g $x))
+            (= (f A) B)
+            !(assert-confluent (f A))
+        ";
+        let result = run_program(program).unwrap();
+        match result[0].as_slice() {
+            [Atom::Expression(error)] => {
+                let message = error.children()[2].to_string();
+                assert!(message == "\nExpected unique normal form\nGot: [(g A), B]" || message == "\nExpected unique normal form\nGot: [B, (g A)]",
+                    "Unexpected message: {}", message);
+            },
+            _ => panic!("Error is expected, found: {:?}", result[0]),
+        }
+    }
+
+    #[test]
+    fn metta_assert_confluent_recursive_rules() {
+        let program = "
+            (= (plus Z $y) $y)
+            (= (plus (S $k) $y) (S (plus $k $y)))
+            (= (mul Z $y) Z)
+            (= (mul (S $k) $y) (plus $y (mul $k $y)))
+            !(assert-confluent (mul (S (S Z)) (S (S Z))))
+        ";
+        assert_eq!(run_program(program), Ok(vec![vec![UNIT_ATOM()]]));
+
+        let program = "
+            (= (fact $n) (if (== $n 0) 1 (* $n (fact (- $n 1)))))
+            !(assert-confluent (fact 3))
+        ";
+        let result = run_program(program).unwrap();
+        match result[0].as_slice() {
+            [Atom::Expression(error)] => assert_eq!(error.children()[2].to_string(),
+                "Step limit 10000 is exceeded while reducing (fact 3)"),
+            _ => panic!("Error is expected, found: {:?}", result[0]),
+        }
+    }
+
+    #[test]
+    fn metta_assert_terminates_op() {
+        let assert = AssertTerminatesOp::new(DynSpace::new(GroundingSpace::new()));
+        let program = "
+            (= (loop) (loop))
+            (= (fact $n) (if (== $n 0) 1 (* $n (fact (- $n 1)))))
+            !(assert-terminates (fact 3) 10000)
+            !(assert-terminates (loop) 10000)
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!({Number::Integer(6)})],
+            vec![expr!("Error" ({assert} ("loop") {Number::Integer(10000)}) "Step limit 10000 is exceeded while interpreting (loop)")],
+        ]));
+
+        let program = "
+            (= (loop) (loop))
+            !(assert-terminates (collapse (loop)) 1000)
+        ";
+        let result = run_program(program).unwrap();
+        match result[0].as_slice() {
+            [Atom::Expression(error)] => assert_eq!(error.children()[2].to_string(),
+                "Step limit 1000 is exceeded while interpreting (collapse (loop))"),
+            _ => panic!("Error is expected, found: {:?}", result[0]),
+        }
+    }
+
+    #[test]
+    fn bench_op() {
+        fn fixed_clock() -> std::time::Duration {
+            static TICKS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+            let ticks = TICKS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            std::time::Duration::from_millis(7 * ticks)
+        }
+        let space = DynSpace::new(metta_space("
+            (= (plus Z $y) $y)
+            (= (plus (S $k) $y) (S (plus $k $y)))
+            (= (color) red)
+            (= (color) green)
+        "));
+        let bench = BenchOp::with_clock(space.clone(), fixed_clock);
+
+        let stat = |steps: i64, results: i64| expr!(("time" {Number::Integer(7)})
+            ("steps" {Number::Integer(steps)}) ("results" {Number::Integer(results)}));
+
+        assert_eq!(bench.execute(&mut vec![expr!("plus" ("S" ("S" "Z")) "Z")]), Ok(vec![stat(634, 1)]));
+        assert_eq!(bench.execute(&mut vec![expr!(("color"))]), Ok(vec![stat(100, 2)]));
+        // steps of the (color) evaluation started by collapse are included
+        assert_eq!(bench.execute(&mut vec![expr!({CollapseOp::new(space.clone())} ("color"))]), Ok(vec![stat(237, 1)]));
+    }
+
+    #[test]
+    fn metta_assert_matches_op() {
+        let assert = AssertMatchesOp::new(DynSpace::new(GroundingSpace::new()));
+        let program = "
+            (= (plus Z $y) $y)
+            (= (plus (S $k) $y) (S (plus $k $y)))
+            !(assert-matches (S $n) (plus (S Z) Z))
+            !(assert-matches (S (S $n)) (plus (S Z) Z))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![UNIT_ATOM()],
+            vec![expr!("Error" ({assert} ("S" ("S" n)) ("plus" ("S" "Z") "Z")) "\nExpected result matching: (S (S $n))\nGot: [(S Z)]")],
+        ]));
+    }
+
+    #[test]
+    fn metta_assert_all_op() {
+        let assert = AssertAllOp::new(DynSpace::new(GroundingSpace::new()));
+        let program = "
+            (= (is-positive $x) (> $x 0))
+            (= (nums) (superpose (1 -2 3)))
+            !(assert-all is-positive (superpose (1 2 3)))
+            !(assert-all is-positive (superpose ()))
+            !(assert-all is-positive (nums))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![UNIT_ATOM()],
+            vec![UNIT_ATOM()],
+            vec![expr!("Error" ({assert} "is-positive" ("nums")) "Predicate is-positive does not hold for result: -2")],
+        ]));
+    }
+
+    #[test]
+    fn metta_assert_count_op() {
+        let assert = AssertCountOp::new(DynSpace::new(GroundingSpace::new()));
+        let program = "
+            (= (color) red)
+            (= (color) green)
+            (= (color) blue)
+            !(assert-count 3 (color))
+            !(assert-count 0 (superpose ()))
+            !(assert-count 2 (color))
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[0], vec![UNIT_ATOM()]);
+        assert_eq!(result[1], vec![UNIT_ATOM()]);
+        match result[2].as_slice() {
+            [Atom::Expression(error)] => {
+                assert_eq!(error.children()[1], expr!({assert} {Number::Integer(2)} ("color")));
+                let message = error.children()[2].to_string();
+                assert!(message.starts_with("\nExpected number of results: 2\nGot: 3 "), "Unexpected message: {}", message);
+            },
+            _ => panic!("Error is expected, found: {:?}", result[2]),
+        }
+    }
+
+    #[test]
+    fn metta_assert_eventually_op() {
+        let assert = AssertEventuallyOp::new(DynSpace::new(GroundingSpace::new()));
+        let program = "
+            (= (inc $x) (+ $x 1))
+            (= (is-ten $x) (== $x 10))
+            !(assert-eventually inc 0 is-ten 10)
+            !(assert-eventually inc 0 is-ten 5)
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![UNIT_ATOM()],
+            vec![expr!("Error" ({assert} "inc" {Number::Integer(0)} "is-ten" {Number::Integer(5)}) "Goal is-ten is not reached within 5 steps, last state: 5")],
+        ]));
+    }
+
+    #[test]
+    fn metta_assert_alpha_equal_op() {
+        assert_eq!(run_program("!(assert-alpha-equal (f $x) (f $y))"), Ok(vec![vec![UNIT_ATOM()]]));
+        assert_eq!(run_program("!(assert-alpha-equal (f $x $x) (f $y $y))"), Ok(vec![vec![UNIT_ATOM()]]));
+        let result = run_program("!(assert-alpha-equal (f $x $x) (f $x $y))").unwrap();
+        assert!(result.len() == 1 && result[0].len() == 1 && atom_is_error(&result[0][0]),
+            "Error is expected, found: {:?}", result);
+    }
+
+    #[test]
+    fn metta_assert_reduces_to() {
+        let program = "
+            (= (add Z $n) $n)
+            (= (add (S $m) $n) (S (add $m $n)))
+            !(assert-reduces-to (add (S Z) (S Z)) (S (S Z)))
+            !(assert-reduces-to (add (S Z) Z) (S (S Z)))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![UNIT_ATOM()],
+            vec![expr!("Error" ({AssertReducesToOp::new(DynSpace::new(GroundingSpace::new()))} ("add" ("S" "Z") "Z") ("S" ("S" "Z")))
+                "\nInput: (add (S Z) Z)\nExpected: (S (S Z))\nGot: [(S Z)]")],
+        ]));
+        assert_eq!(run_program("!(assert-reduces-to (superpose (Z Z)) Z)"), Ok(vec![
+            vec![expr!("Error" ({AssertReducesToOp::new(DynSpace::new(GroundingSpace::new()))} ({SuperposeOp::new(DynSpace::new(GroundingSpace::new()))} ("Z" "Z")) "Z")
+                "\nInput: (superpose (Z Z))\nExpected: Z\nGot: [Z, Z]")],
+        ]));
+    }
+
+    #[test]
+    fn metta_assert_space_equal() {
+        let program = "
+            !(bind! &a (new-space))
+            !(bind! &b (new-space))
+            !(add-atom &a (likes Sam pizza))
+            !(add-atom &a (likes Sam pasta))
+            !(add-atom &b (likes Sam pasta))
+            !(add-atom &b (likes Sam pizza))
+            !(assert-space-equal &a &b)
+            !(add-atom &a (likes Sam soup))
+            !(assert-space-equal &a &b)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[6], vec![UNIT_ATOM()]);
+        match result[8].as_slice() {
+            [Atom::Expression(error)] => assert_eq!(error.children().last(),
+                Some(&Atom::sym("\nSpaces are not equal\nMissed atoms: ()\nExcessive atoms: ((likes Sam soup))"))),
+            _ => panic!("Error is expected, found: {:?}", result[8]),
+        }
+    }
+
+    #[test]
+    fn metta_assert_subset() {
+        let program = "
+            (= (f) A)
+            (= (f) B)
+            (= (f) C)
+            !(assert-subset (A C) (collapse (f)))
+            !(assert-subset (A D (g E)) (collapse (f)))
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[0], vec![UNIT_ATOM()]);
+        match result[1].as_slice() {
+            [Atom::Expression(error)] => assert_eq!(error.children().last(),
+                Some(&Atom::sym("\nExpected atoms are not found\nMissed atoms: (D (g E))"))),
+            _ => panic!("Error is expected, found: {:?}", result[1]),
+        }
+    }
+
+    #[test]
+    fn metta_assert_pure() {
+        assert_eq!(run_program("!(assert-pure +)"), Ok(vec![vec![UNIT_ATOM()]]));
+        assert_eq!(run_program("!(assert-pure to-int)"), Ok(vec![vec![UNIT_ATOM()]]));
+        assert_eq!(run_program("!(assert-pure contains)"), Ok(vec![vec![UNIT_ATOM()]]));
+        assert_eq!(run_program("!(assert-pure make-expr)"), Ok(vec![vec![UNIT_ATOM()]]));
+        assert_eq!(run_program("!(assert-pure println!)"), Ok(vec![vec![expr!("Error" ({AssertPureOp{}} {stdlib::PrintlnOp{}}) "Operation println! is not declared pure")]]));
+        assert_eq!(run_program("!(assert-pure foo)"), Ok(vec![vec![expr!("Error" ({AssertPureOp{}} "foo") "assert-pure expects grounded operation as an argument")]]));
+    }
+
+    #[test]
+    fn metta_assert_acyclic() {
+        let program = "
+            !(bind! &kb (new-space))
+            !(add-atom &kb (= (double $x) (+ $x $x)))
+            !(add-atom &kb (= (quad $x) (double (double $x))))
+            !(add-atom &kb (= answer (quad 10)))
+            !(assert-acyclic &kb)
+            !(add-atom &kb (= (even Z) True))
+            !(add-atom &kb (= (even (S $n)) (odd $n)))
+            !(add-atom &kb (= (odd Z) False))
+            !(add-atom &kb (= (odd (S $n)) (even $n)))
+            !(assert-acyclic &kb)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[4], vec![UNIT_ATOM()]);
+        match <[Atom; 3]>::try_from(result[9][0].clone()) {
+            Ok([error, _, message]) => {
+                assert_eq!(error, ERROR_SYMBOL);
+                assert_eq!(message, Atom::sym("Cyclic definitions: even -> odd -> even"));
+            },
+            _ => panic!("Error is expected: {:?}", result[9]),
+        }
+    }
+
+    #[test]
+    fn metta_assert_binding() {
+        let header = "
+            (rel a foo)
+            (rel b foo)
+            (rel c bar)
+        ";
+        let message = |program: &str| match run_program(&format!("{header} {program}")).unwrap().as_slice() {
+            [result] => match <[Atom; 3]>::try_from(result[0].clone()) {
+                Ok([error, _, message]) if error == ERROR_SYMBOL => message.to_string(),
+                _ => panic!("Error is expected: {:?}", result),
+            },
+            results => panic!("Single result is expected: {:?}", results),
+        };
+        assert_eq!(run_program(&format!("{header} !(assert-binding &self (rel $x foo) $x b)")), Ok(vec![vec![UNIT_ATOM()]]));
+        assert_eq!(message("!(assert-binding &self (rel $x bar) $x a)"), "\nExpected binding: $x = a\nGot: [c]");
+        assert_eq!(message("!(assert-binding &self (rel $x baz) $x a)"), "Variable $x is not bound by query (rel $x baz)");
+        assert_eq!(message("!(assert-binding &self (rel $x foo) $y a)"), "Variable $y is not bound by query (rel $x foo)");
+    }
+
+    #[test]
+    fn metta_pipe() {
+        let program = "
+            (= (double $x) (* $x 2))
+            (= (inc $x) (+ $x 1))
+            (= (branch $x) (superpose ($x (* $x 10))))
+            !(pipe 5 double inc)
+            !(pipe 1 branch inc)
+            !(pipe 5)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[0], vec![expr!({Number::Integer(11)})]);
+        assert_eq_no_order!(result[1], vec![expr!({Number::Integer(2)}), expr!({Number::Integer(11)})]);
+        assert_eq!(result[2], vec![expr!({Number::Integer(5)})]);
+    }
+
+    #[test]
+    fn metta_delay_force() {
+        let program = "
+            !(bind! &p (delay (add-atom &self (evaluated))))
+            !(match &self (evaluated) yes)
+            !(force &p)
+            !(force &p)
+            !(collapse (match &self (evaluated) yes))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![UNIT_ATOM()],
+            vec![],
+            vec![UNIT_ATOM()],
+            vec![UNIT_ATOM()],
+            vec![expr!(("yes"))],
+        ]));
+    }
+
+    #[test]
+    fn metta_memoize() {
+        let program = "
+            (= (double $x) (let $_ (add-atom &self (called $x)) (* $x 2)))
+            !(bind! &double (memoize double))
+            !(&double 3)
+            !(&double 3)
+            !(&double 4)
+            !(collapse (match &self (called $x) $x))
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[1], vec![expr!({Number::Integer(6)})]);
+        assert_eq!(result[2], vec![expr!({Number::Integer(6)})]);
+        assert_eq!(result[3], vec![expr!({Number::Integer(8)})]);
+        match result[4].as_slice() {
+            [Atom::Expression(called)] => assert_eq_no_order!(called.children(),
+                vec![expr!({Number::Integer(3)}), expr!({Number::Integer(4)})]),
+            _ => panic!("Single expression is expected, found: {:?}", result[4]),
+        }
+    }
+
+    #[test]
+    fn metta_flatten_results() {
+        let program = "
+            (= (pairs) (superpose ((a b) (c))))
+            !(flatten-results (superpose ((a b) (c))))
+            !(flatten-results (superpose (((superpose (a b)) c) d ())))
+            !(flatten-results (pairs))
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq_no_order!(result[0], vec![expr!("a"), expr!("b"), expr!("c")]);
+        assert_eq_no_order!(result[1], vec![expr!("a"), expr!("c"), expr!("b"), expr!("c"), expr!("d")]);
+        assert_eq_no_order!(result[2], vec![expr!("a"), expr!("b"), expr!("c")]);
+    }
+
+    #[test]
+    fn metta_collect_if() {
+        let program = "
+            (= (is-even $x) (== (% $x 2) 0))
+            !(collect-if is-even (range 1 6))
+            !(collect-if is-even (superpose (1 3)))
+        ";
+        let result = run_program(program);
+        match result {
+            Ok(results) => {
+                assert_eq_no_order!(results[0], vec![expr!({Number::Integer(2)}), expr!({Number::Integer(4)})]);
+                assert_eq!(results[1], vec![]);
+            },
+            _ => panic!("Results are expected, found: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn metta_take_while_drop_while() {
+        let program = "
+            (= (is-small $x) (< $x 4))
+            !(take-while is-small (range 1 10))
+            !(drop-while is-small (range 1 6))
+            !(take-while is-small (range 5 7))
+            !(drop-while is-small (range 1 3))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!({Number::Integer(1)}), expr!({Number::Integer(2)}), expr!({Number::Integer(3)})],
+            vec![expr!({Number::Integer(4)}), expr!({Number::Integer(5)})],
+            vec![],
+            vec![],
+        ]));
+    }
+
+    #[test]
+    fn metta_max_by_min_by() {
+        let program = "
+            (= (cost a) 3)
+            (= (cost b) 7)
+            (= (cost c) 1.5)
+            (= (cost d) 7)
+            !(max-by cost (superpose (a b c d)))
+            !(min-by cost (superpose (a b c d)))
+            !(max-by cost (superpose ()))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("b")],
+            vec![expr!("c")],
+            vec![],
+        ]));
+    }
+
+    #[test]
+    fn metta_group_by() {
+        let program = "
+            (= (parity $x) (if (== (% $x 2) 0) even odd))
+            !(group-by parity (superpose (1 2 3 4 5)))
+            !(group-by parity (superpose ()))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![
+                expr!("odd" ({Number::Integer(1)} {Number::Integer(3)} {Number::Integer(5)})),
+                expr!("even" ({Number::Integer(2)} {Number::Integer(4)})),
+            ],
+            vec![],
+        ]));
+    }
+
+    #[test]
+    fn metta_sample() {
+        let program = "
+            !(sample 2 (superpose (a b c d a)))
+            !(sample 10 (superpose (a b a)))
+            !(sample-seeded 42 3 (superpose (a b c d e)))
+            !(sample-seeded 42 3 (superpose (a b c d e)))
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[0].len(), 2);
+        assert_ne!(result[0][0], result[0][1]);
+        assert_eq_no_order!(result[1], vec![expr!("a"), expr!("b")]);
+        assert_eq!(result[2].len(), 3);
+        assert_eq!(result[2], result[3]);
+    }
+
+    #[test]
+    fn metta_count_distinct() {
+        let program = "
+            (color apple red)
+            (color cherry red)
+            (color banana yellow)
+            (color lime green)
+            !(count-distinct (match &self (color $fruit $color) $color))
+            !(count-distinct (superpose ((f $x) (f $y) (g $x))))
+            !(count-distinct (superpose ()))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!({Number::Integer(3)})],
+            vec![expr!({Number::Integer(2)})],
+            vec![expr!({Number::Integer(0)})],
+        ]));
+    }
+
+    #[test]
+    fn metta_interleave() {
+        assert_eq!(run_program("!(interleave (superpose (a b c)) (superpose (1 2)))"),
+            Ok(vec![vec![expr!("a"), expr!({Number::Integer(1)}), expr!("b"), expr!({Number::Integer(2)}), expr!("c")]]));
+        assert_eq!(run_program("!(interleave (superpose (a)) (superpose (1 2 3)))"),
+            Ok(vec![vec![expr!("a"), expr!({Number::Integer(1)}), expr!({Number::Integer(2)}), expr!({Number::Integer(3)})]]));
+        assert_eq!(run_program("!(interleave (superpose ()) (superpose ()))"), Ok(vec![vec![]]));
+    }
+
+    #[test]
+    fn metta_map_space() {
+        let program = "
+            (= (swap (parent $x $y)) (child $y $x))
+            (= (swap (age $x $a)) (superpose ((age $x $a) (adult $x))))
+            (= (swap (unknown $x)) Empty)
+            !(bind! &kb (new-space))
+            !(add-atom &kb (parent Tom Bob))
+            !(add-atom &kb (age Bob 30))
+            !(add-atom &kb (unknown Sam))
+            !(bind! &mapped (map-space swap &kb))
+            !(get-atoms &mapped)
+            !(get-atoms &kb)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq_no_order!(result[5], vec![expr!("child" "Bob" "Tom"),
+            expr!("age" "Bob" {Number::Integer(30)}), expr!("adult" "Bob")]);
+        assert_eq!(result[6].len(), 3);
+    }
+
+    #[test]
+    fn metta_map_space_error() {
+        let program = "
+            (= (fail $x) (Error $x \"failed\"))
+            !(bind! &kb (new-space))
+            !(add-atom &kb (a 1))
+            !(map-space fail &kb)
+        ";
+        assert_eq!(run_program(program), Ok(vec![vec![UNIT_ATOM()], vec![UNIT_ATOM()],
+            vec![expr!("Error" ("a" {Number::Integer(1)}) {Str::from_str("failed")})]]));
+    }
+
+    #[test]
+    fn metta_partition_space() {
+        let program = "
+            (= (is-parent (parent $x $y)) True)
+            (= (is-parent (age $x $a)) False)
+            !(bind! &kb (new-space))
+            !(add-atom &kb (parent Tom Bob))
+            !(add-atom &kb (parent Bob Ann))
+            !(add-atom &kb (age Bob 30))
+            !(add-atom &kb (likes Ann pizza))
+            !(let ($parents $rest) (partition-space is-parent &kb)
+                (let $p (collapse (get-atoms $parents))
+                    (let $r (collapse (get-atoms $rest)) ($p $r))))
+        ";
+        let result = run_program(program).unwrap();
+        match result[5].as_slice() {
+            [Atom::Expression(pair)] => match pair.children().as_slice() {
+                [Atom::Expression(parents), Atom::Expression(rest)] => {
+                    assert_eq_no_order!(parents.children(), vec![expr!("parent" "Tom" "Bob"), expr!("parent" "Bob" "Ann")]);
+                    assert_eq_no_order!(rest.children(), vec![expr!("age" "Bob" {Number::Integer(30)}), expr!("likes" "Ann" "pizza")]);
+                },
+                _ => panic!("Pair of expressions is expected, found: {}", pair),
+            },
+            _ => panic!("Single result is expected, found: {:?}", result[5]),
+        }
+    }
+
+    #[test]
+    fn metta_reduce_once() {
+        let program = "
+            (= (plus Z $y) $y)
+            (= (plus (S $k) $y) (S (plus $k $y)))
+            !(reduce-once (plus (S Z) Z))
+            !(reduce-once (plus Z (S Z)))
+            !(reduce-once (S Z))
+            !(reduce-once (+ 1 2))
+            !(let $x (reduce-once (plus (S Z) Z)) (unquote $x))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("quote" ("S" ("plus" "Z" "Z")))],
+            vec![expr!("quote" ("S" "Z"))],
+            vec![expr!("quote" ("S" "Z"))],
+            vec![expr!("quote" {Number::Integer(3)})],
+            vec![expr!("S" "Z")],
+        ]));
+    }
+
+    #[test]
+    fn metta_trace_reductions() {
+        let program = "
+            (= (is Fritz croaks) True)
+            (= (is Fritz eats-flies) True)
+            (= (is Tweety chirps) True)
+            (= (is $x frog) (and (is $x croaks) (is $x eats-flies)))
+            !(trace-reductions (is Fritz frog))
+            !(trace-reductions (is Tweety chirps))
+            !(trace-reductions (S Z))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("quote" ({Bool(true)} (
+                ("=" ("is" "Fritz" "frog") ({AndOp{}} ("is" "Fritz" "croaks") ("is" "Fritz" "eats-flies")))
+                ("=" ("is" "Fritz" "croaks") {Bool(true)})
+                ("=" ("is" "Fritz" "eats-flies") {Bool(true)})
+            )))],
+            vec![expr!("quote" ({Bool(true)} (("=" ("is" "Tweety" "chirps") {Bool(true)}))))],
+            vec![expr!("quote" (("S" "Z") ()))],
+        ]));
+    }
+
+    #[test]
+    fn metta_trace_reductions_recursive_if() {
+        let program = "
+            (= (fact $n) (if (== $n 0) 1 (* $n (fact (- $n 1)))))
+            !(let (quote ($value $rules)) (trace-reductions (fact 3)) $value)
+        ";
+        assert_eq!(run_program(program), Ok(vec![vec![expr!({Number::Integer(6)})]]));
+    }
+
+    #[test]
+    fn metta_with_space() {
+        let program = "
+            (= (h) global)
+            !(with-space ((= (f $x) ($x $x)) (= (g) 1)) (f (g)))
+            !(with-space ((= (h) local)) (h))
+            !(f (g))
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[0], vec![expr!({Number::Integer(1)} {Number::Integer(1)})]);
+        assert_eq_no_order!(result[1], vec![expr!("global"), expr!("local")]);
+        assert_eq!(result[2], vec![expr!("f" ("g"))]);
+    }
+
+    #[test]
+    fn metta_apply_rules() {
+        let program = "
+            (= (g $x) (h $x))
+            !(apply-rules ((= (f $x) (g $x))) (f a))
+            !(apply-rules ((= (f $x) (g $x)) (= (g $x) (k $x))) (f a))
+            !(apply-rules ((= (f $x) (g $x))) (p a))
+            !(apply-rules ((= (f $x) (g $x))) (f (+ 1 2)))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("quote" ("g" "a"))],
+            vec![expr!("quote" ("k" "a"))],
+            vec![expr!("quote" ("p" "a"))],
+            vec![expr!("quote" ("g" {Number::Integer(3)}))],
+        ]));
+    }
+
+    #[test]
+    fn metta_on_error() {
+        let program = "
+            (= (fallback) recovered)
+            !(on-error (parse-number \"abc\") (fallback))
+            !(on-error (parse-number \"42\") (add-atom &self (fallback-called)))
+            !(match &self (fallback-called) called)
+            !(on-error (Error x \"Test error\") (fallback))
+            !(on-error (superpose ()) (fallback))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("recovered")],
+            vec![expr!({Number::Integer(42)})],
+            vec![],
+            vec![expr!("recovered")],
+            vec![],
+        ]));
+    }
+
+    #[test]
+    fn on_error_op() {
+        let space = DynSpace::new(metta_space("
+            (= (fallback) recovered)
+        "));
+        let op = OnErrorOp::new(space);
+        assert_eq!(op.execute(&mut vec![expr!("Error" "x" "y"), expr!(("fallback"))]),
+            Ok(vec![expr!(("fallback"))]));
+        assert_eq!(op.execute(&mut vec![expr!("a"), expr!(("fallback"))]),
+            Ok(vec![expr!("a")]));
+    }
+
+    #[test]
+    fn metta_superpose() {
+        assert_eq_metta_results!(run_program("!(superpose (red yellow green))"),
+            Ok(vec![vec![expr!("red"), expr!("yellow"), expr!("green")]]));
+        let program = "
+            (= (foo) FOO)
+            (= (bar) BAR)
+            !(superpose ((foo) (bar) BAZ))
+        ";
+        assert_eq_metta_results!(run_program(program),
+            Ok(vec![vec![expr!("FOO"), expr!("BAR"), expr!("BAZ")]]));
+    }
+
+    #[test]
+    fn metta_collapse() {
+        let program = "
+            (= (color) red)
+            (= (color) green)
+            (= (color) blue)
+            !(collapse (color))
+        ";
+        let result = run_program(program).expect("Successful result is expected");
+        assert_eq!(result.len(), 1);
+        let result = result.get(0).unwrap();
+        assert_eq!(result.len(), 1);
+        let result = result.get(0).unwrap();
+        let actual = <&ExpressionAtom>::try_from(result)
+            .expect("Expression atom is expected").children();
+        assert_eq_no_order!(actual, vec![expr!("red"), expr!("green"), expr!("blue")]);
+    }
+
+    #[test]
+    fn metta_reverse() {
+        assert_eq!(run_program("!(reverse (a b c))"), Ok(vec![vec![expr!("c" "b" "a")]]));
+        assert_eq!(run_program("!(reverse ((f x) y))"), Ok(vec![vec![expr!("y" ("f" "x"))]]));
+        assert_eq!(run_program("!(reverse ())"), Ok(vec![vec![expr!()]]));
+    }
+
+    #[test]
+    fn metta_zip() {
+        assert_eq!(run_program("!(zip (a b c) (1 2 3))"), Ok(vec![vec![expr!(("a" {Number::Integer(1)}) ("b" {Number::Integer(2)}) ("c" {Number::Integer(3)}))]]));
+        assert_eq!(run_program("!(zip (a b c) (d))"), Ok(vec![vec![Atom::expr([expr!("a" "d")])]]));
+        assert_eq!(run_program("!(zip () (a b))"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(zip a (b))"), Ok(vec![vec![expr!("Error" ({stdlib::ZipAtomOp{}} "a" ("b")) "zip expects two expression atoms as arguments")]]));
+    }
+
+    #[test]
+    fn metta_contains() {
+        assert_eq!(run_program("!(contains (a b c) b)"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(contains (a (b c)) (b c))"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(contains (a (b c)) c)"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(contains () a)"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(contains a a)"), Ok(vec![vec![expr!("Error" ({stdlib::ContainsOp{}} "a" "a") "contains expects two arguments: expression and atom")]]));
+    }
+
+    #[test]
+    fn metta_contains_deep() {
+        assert_eq!(run_program("!(contains-deep (a (b (c d))) c)"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(contains-deep (a (b (c d))) (c d))"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(contains-deep (a (b (c d))) e)"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(contains-deep (a b) (a b))"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(contains-deep a a)"), Ok(vec![vec![expr!("Error" ({stdlib::ContainsDeepOp{}} "a" "a") "contains-deep expects two arguments: expression and atom")]]));
+    }
+
+    #[test]
+    fn metta_assoc() {
+        assert_eq!(run_program("!(assoc b ((a 1) (b 2) (b 3)))"), Ok(vec![vec![expr!({Number::Integer(2)})]]));
+        assert_eq!(run_program("!(assoc (k 1) (((k 1) found) ((k 2) other)))"), Ok(vec![vec![expr!("found")]]));
+        assert_eq!(run_program("!(assoc c ((a 1) (b 2)))"), Ok(vec![vec![]]));
+        assert_eq!(run_program("!(assoc a ((a 1) b))"), Ok(vec![vec![expr!("Error" ({stdlib::AssocAtomOp{}} "a" (("a" {Number::Integer(1)}) "b")) "assoc expects (key value) pair, found: b")]]));
+        assert_eq!(run_program("!(assoc a ((a 1 2)))"), Ok(vec![vec![expr!("Error" ({stdlib::AssocAtomOp{}} "a" (("a" {Number::Integer(1)} {Number::Integer(2)}))) "assoc expects (key value) pair, found: (a 1 2)")]]));
+    }
+
+    #[test]
+    fn metta_make_expr() {
+        let program = "
+            (= (two) 2)
+            (= (point $x $y) ($x $y))
+            !(make-expr point (+ 0 1) (two))
+            !(make-expr)
+            !(let $e (make-expr point 1 2) (unquote $e))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("quote" ("point" {Number::Integer(1)} {Number::Integer(2)}))],
+            vec![expr!("quote" ())],
+            vec![expr!({Number::Integer(1)} {Number::Integer(2)})],
+        ]));
+    }
+
+    #[test]
+    fn metta_arity() {
+        assert_eq!(run_program("!(arity (f a b))"), Ok(vec![vec![expr!({Number::Integer(2)})]]));
+        assert_eq!(run_program("!(arity (f))"), Ok(vec![vec![expr!({Number::Integer(0)})]]));
+        assert_eq!(run_program("!(arity ())"), Ok(vec![vec![expr!({Number::Integer(0)})]]));
+        assert_eq!(run_program("!(arity a)"), Ok(vec![vec![expr!("Error" ({stdlib::ArityOp{}} "a") "arity expects expression as an argument")]]));
+    }
+
+    #[test]
+    fn metta_set_equal() {
+        assert_eq!(run_program("!(set-equal (a b c) (c a b))"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(set-equal (a b b) (a a b))"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(set-equal (a b) (a b c))"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(set-equal (f a b) (g b a))"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(set-equal (f a (g b)) ((g b) a f))"), Ok(vec![vec![expr!({Bool(true)})]]));
+    }
+
+    #[test]
+    fn metta_substitute() {
+        assert_eq!(run_program("!(substitute a b (f a (g a c)))"), Ok(vec![vec![expr!("f" "b" ("g" "b" "c"))]]));
+        assert_eq!(run_program("!(substitute (g a) c (f a (h (g a))))"), Ok(vec![vec![expr!("f" "a" ("h" "c"))]]));
+        assert_eq!(run_program("!(substitute a (a a) (f a))"), Ok(vec![vec![expr!("f" ("a" "a"))]]));
+        assert_eq!(run_program("!(substitute $x b (f $x $y))"), Ok(vec![vec![expr!("f" "b" y)]]));
+    }
+
+    #[test]
+    fn metta_replace_all() {
+        assert_eq!(run_program("!(replace-all $x a (f $x (g $x $y)))"), Ok(vec![vec![expr!("f" "a" ("g" "a" y))]]));
+        assert_eq!(run_program("!(replace-all $x (h $y) (f $x))"), Ok(vec![vec![expr!("f" ("h" y))]]));
+        assert_eq!(run_program("!(replace-all $z a (f $x))"), Ok(vec![vec![expr!("f" x)]]));
+        assert_eq!(run_program("!(replace-all $x (h $x) (f $x))"), Ok(vec![vec![expr!("Error" ({stdlib::ReplaceAllOp{}} x ("h" x) ("f" x)) "replace-all cannot replace variable by value which contains this variable")]]));
+        assert_eq!(run_program("!(replace-all a b (f a))"), Ok(vec![vec![expr!("Error" ({stdlib::ReplaceAllOp{}} "a" "b" ("f" "a")) "replace-all expects three arguments: variable, value and template")]]));
+    }
+
+    #[test]
+    fn metta_atom_hash() {
+        assert_eq!(run_program("!(== (atom-hash (f 1 2)) (atom-hash (f 1 2)))"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(== (atom-hash (f $x $y $x)) (atom-hash (f $a $b $a)))"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(== (atom-hash (f 1 2)) (atom-hash (f 2 1)))"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(== (atom-hash (f $x $y)) (atom-hash (f $x $x)))"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(== (atom-hash a) (atom-hash \"a\"))"), Ok(vec![vec![expr!({Bool(false)})]]));
+    }
+
+    #[test]
+    fn metta_try_match() {
+        assert_eq!(run_program("!(try-match (point $x $y) (point 1 2) $y none)"), Ok(vec![vec![expr!({Number::Integer(2)})]]));
+        assert_eq!(run_program("!(try-match (point $x $x) (point 1 2) $x none)"), Ok(vec![vec![expr!("none")]]));
+        assert_eq!(run_program("!(try-match (point $x) (line 1) $x (failed $x))"), Ok(vec![vec![expr!("failed" x)]]));
+    }
+
+    #[test]
+    fn metta_partial() {
+        assert_eq!(run_program("!((partial + 10) 5)"), Ok(vec![vec![expr!({Number::Integer(15)})]]));
+        assert_eq!(run_program("!(let $f (partial - 10) ($f 4))"), Ok(vec![vec![expr!({Number::Integer(6)})]]));
+        assert_eq!(run_program("(= (f $x $y $z) ($x $y $z)) !((partial f a b) c)"), Ok(vec![vec![expr!("a" "b" "c")]]));
+        assert_eq!(run_program("!((partial + 10) 5 6)"), Ok(vec![vec![expr!("Error" ({SumOp{}} {Number::Integer(10)} {Number::Integer(5)} {Number::Integer(6)}) "IncorrectNumberOfArguments")]]));
+    }
+
+    #[test]
+    fn metta_compose() {
+        assert_eq!(run_program("!((compose (partial + 1) (partial * 2)) 5)"), Ok(vec![vec![expr!({Number::Integer(11)})]]));
+        assert_eq!(run_program("(= (inc $x) (+ $x 1)) (= (double $x) (* $x 2)) !((compose double inc) 5)"), Ok(vec![vec![expr!({Number::Integer(12)})]]));
+        assert_eq!(run_program("!((compose g h) a)"), Ok(vec![vec![expr!("g" ("h" "a"))]]));
+    }
+
+    #[test]
+    fn metta_get_head_get_children() {
+        assert_eq!(run_program("(= (f a b) c) !(get-head (f a b))"), Ok(vec![vec![expr!("f")]]));
+        assert_eq!(run_program("(= (f a b) c) !(get-children (f a b))"), Ok(vec![vec![expr!("a" "b")]]));
+        assert_eq!(run_program("!(get-children (f))"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(get-children ())"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(get-head ())"), Ok(vec![vec![expr!("Error" ({stdlib::GetHeadOp{}} ()) "get-head expects non-empty expression as an argument")]]));
+        assert_eq!(run_program("!(get-head a)"), Ok(vec![vec![expr!("Error" ({stdlib::GetHeadOp{}} "a") "get-head expects non-empty expression as an argument")]]));
+        assert_eq!(run_program("!(get-children a)"), Ok(vec![vec![expr!("Error" ({stdlib::GetChildrenOp{}} "a") "get-children expects expression as an argument")]]));
+    }
+
+    #[test]
+    fn metta_deep_copy() {
+        assert_eq!(run_program("!(deep-copy (f 1 \"a\" $x))"), Ok(vec![vec![expr!("quote" ("f" {Number::Integer(1)} {Str::from_str("a")} x))]]));
+        assert_eq!(run_program("(= (f 1) two) !(deep-copy (f 1))"), Ok(vec![vec![expr!("quote" ("f" {Number::Integer(1)}))]]));
+
+        let atom = expr!("f" {Number::Integer(1)} ("g" "a"));
+        let result = stdlib::DeepCopyOp{}.execute(&[atom.clone()]).unwrap().pop();
+        let copy = match result {
+            Some(Atom::Expression(quoted)) => quoted.into_children().pop().unwrap(),
+            result => panic!("Quoted expression is expected, found: {:?}", result),
+        };
+        assert_eq!(copy, atom);
+        fn children(atom: &Atom) -> &[Atom] {
+            match atom {
+                Atom::Expression(expr) => expr.children().as_slice(),
+                _ => panic!("Expression is expected, found: {}", atom),
+            }
+        }
+        let (atom, copy) = (children(&atom), children(&copy));
+        assert_ne!(atom.as_ptr(), copy.as_ptr());
+        assert_ne!(children(&atom[2]).as_ptr(), children(&copy[2]).as_ptr());
+        assert!(!std::ptr::eq(atom[1].as_gnd::<Number>().unwrap(), copy[1].as_gnd::<Number>().unwrap()));
+    }
+
+    #[test]
+    fn metta_get_variables() {
+        assert_eq!(run_program("!(get-variables (f $x (g $y $x)))"), Ok(vec![vec![expr!(x y)]]));
+        assert_eq!(run_program("!(get-variables $x)"), Ok(vec![vec![expr!((x))]]));
+        assert_eq!(run_program("!(get-variables (f a (g b)))"), Ok(vec![vec![expr!()]]));
+    }
+
+    #[test]
+    fn metta_windows() {
+        assert_eq!(run_program("!(windows 2 (a b c d))"), Ok(vec![vec![expr!(("a" "b") ("b" "c") ("c" "d"))]]));
+        assert_eq!(run_program("!(windows 4 (a b c d))"), Ok(vec![vec![expr!((("a" "b" "c" "d")))]]));
+        assert_eq!(run_program("!(windows 5 (a b c d))"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(windows 0 (a b c d))"), Ok(vec![vec![expr!("Error" ({stdlib::WindowsOp{}} {Number::Integer(0)} ("a" "b" "c" "d")) "windows expects positive integer size and expression as arguments")]]));
+    }
+
+    #[test]
+    fn metta_assoc_set() {
+        assert_eq!(run_program("!(assoc-set b 3 ((a 1) (b 2)))"), Ok(vec![vec![expr!(("a" {Number::Integer(1)}) ("b" {Number::Integer(3)}))]]));
+        assert_eq!(run_program("!(assoc-set c 3 ((a 1) (b 2)))"), Ok(vec![vec![expr!(("a" {Number::Integer(1)}) ("b" {Number::Integer(2)}) ("c" {Number::Integer(3)}))]]));
+        assert_eq!(run_program("!(assoc-set a 3 ())"), Ok(vec![vec![Atom::expr([expr!("a" {Number::Integer(3)})])]]));
+        assert_eq!(run_program("!(assoc-set b 3 ((b 1) (b 2)))"), Ok(vec![vec![expr!(("b" {Number::Integer(3)}) ("b" {Number::Integer(2)}))]]));
+        assert_eq!(run_program("!(assoc-set a 3 ((a 1) b))"), Ok(vec![vec![expr!("Error" ({stdlib::AssocSetAtomOp{}} "a" {Number::Integer(3)} (("a" {Number::Integer(1)}) "b")) "assoc-set expects (key value) pair, found: b")]]));
+    }
+
+    #[test]
+    fn metta_current_bindings() {
+        assert_eq!(run_program("!(let $x 5 (current-bindings))"), Ok(vec![vec![Atom::expr([expr!("$x" {Number::Integer(5)})])]]));
+        assert_eq!(run_program("!(let ($y $x) (B A) (current-bindings))"), Ok(vec![vec![expr!(("$x" "A") ("$y" "B"))]]));
+        assert_eq!(run_program("!(current-bindings)"), Ok(vec![vec![expr!()]]));
+    }
+
+    #[test]
+    fn metta_range() {
+        let int = |n| Atom::gnd(Number::Integer(n));
+        assert_eq!(run_program("!(collapse (range 1 4))"), Ok(vec![vec![Atom::expr([int(1), int(2), int(3)])]]));
+        assert_eq!(run_program("!(collapse (range 0 7 3))"), Ok(vec![vec![Atom::expr([int(0), int(3), int(6)])]]));
+        assert_eq!(run_program("!(collapse (range 3 0 -1))"), Ok(vec![vec![Atom::expr([int(3), int(2), int(1)])]]));
+        assert_eq!(run_program("!(collapse (range (+ 1 1) (* 2 2)))"), Ok(vec![vec![Atom::expr([int(2), int(3)])]]));
+        assert_eq!(run_program("!(collapse (range 2 2))"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(collapse (range 4 1))"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(range 1 4 0)"), Ok(vec![vec![expr!("Error" ({stdlib::RangeOp{}} {Number::Integer(1)} {Number::Integer(4)} {Number::Integer(0)}) "range expects two or three integer numbers: start, end and optional non-zero step")]]));
     }
 
     #[test]
-    fn metta_index_atom() {
-        assert_eq!(run_program(&format!("!(index-atom (5 4 3 2 1) 2)")), Ok(vec![vec![expr!({Number::Integer(3)})]]));
-        assert_eq!(run_program(&format!("!(index-atom (A B C D E) 5)")), Ok(vec![vec![expr!("Error" ({ stdlib::IndexAtomOp{} } ("A" "B" "C" "D" "E") {Number::Integer(5)}) "Index is out of bounds")]]));
+    fn metta_approx_equal() {
+        assert_eq!(run_program("!(approx-equal 0.1 0.1000001 0.001)"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(approx-equal 0.1 0.2 0.001)"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(approx-equal (+ 0.1 0.2) 0.3 1e-9)"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(approx-equal 0.1 a 0.001)"), Ok(vec![vec![expr!("Error" ({ApproxEqualOp{}} {Number::Float(0.1)} "a" {Number::Float(0.001)}) "approx-equal expects three number arguments: two numbers to compare and non-negative tolerance")]]));
+        assert_eq!(run_program("!(approx-equal 0.1 \"a\" 0.001)"), Ok(vec![vec![expr!("Error" {Str::from_str("a")} "BadType")]]));
+        assert_eq!(run_program("!(approx-equal 0.1 0.1)"), Ok(vec![vec![expr!("Error" ({ApproxEqualOp{}} {Number::Float(0.1)} {Number::Float(0.1)}) "IncorrectNumberOfArguments")]]));
     }
 
     #[test]
-    fn metta_switch() {
-        let result = run_program("!(eval (switch (A $b) ( (($a B) ($b $a)) ((B C) (C B)) )))");
-        assert_eq!(result, Ok(vec![vec![expr!("B" "A")]]));
-        let result = run_program("!(eval (switch (A $b) ( ((B C) (C B)) (($a B) ($b $a)) )))");
-        assert_eq!(result, Ok(vec![vec![expr!("B" "A")]]));
-        let result = run_program("!(eval (switch (A $b) ( ((B C) (C B)) ((D E) (E B)) )))");
-        assert_eq!(result, Ok(vec![vec![]]));
+    fn metta_to_float_to_int() {
+        assert_eq!(run_program("!(to-float 3)"), Ok(vec![vec![expr!({Number::Float(3.0)})]]));
+        assert_eq!(run_program("!(to-int 3.7)"), Ok(vec![vec![expr!({Number::Integer(3)})]]));
+        assert_eq!(run_program("!(to-int (to-float 5))"), Ok(vec![vec![expr!({Number::Integer(5)})]]));
+        assert_eq!(run_program("!(to-int a)"), Ok(vec![vec![expr!("Error" ({ToIntOp{}} "a") "to-int expects one finite number argument in range of integer numbers")]]));
+        assert_eq!(run_program("!(to-float \"a\")"), Ok(vec![vec![expr!("Error" {Str::from_str("a")} "BadType")]]));
     }
 
     #[test]
-    fn metta_case_empty() {
-        let result = run_program("!(case Empty ( (ok ok) (Empty nok) ))");
-        assert_eq!(result, Ok(vec![vec![expr!("nok")]]));
-        let result = run_program("!(case (unify (C B) (C B) ok Empty) ( (ok ok) (Empty nok) ))");
-        assert_eq!(result, Ok(vec![vec![expr!("ok")]]));
-        let result = run_program("!(case (unify (B C) (C B) ok nok) ( (ok ok) (nok nok) ))");
-        assert_eq!(result, Ok(vec![vec![expr!("nok")]]));
-        let result = run_program("!(case (unify (B C) (C B) ok Empty) ( (ok ok) (Empty nok) ))");
-        assert_eq!(result, Ok(vec![vec![expr!("nok")]]));
+    fn metta_parse_repr() {
+        assert_eq!(run_program("!(repr (foo (bar 1) \"s\"))"), Ok(vec![vec![expr!({Str::from_str("(foo (bar 1) \"s\")")})]]));
+        assert_eq!(run_program("!(parse \"(foo (bar 1) \\\"s\\\")\")"), Ok(vec![vec![expr!("foo" ("bar" {Number::Integer(1)}) {Str::from_str("s")})]]));
+        assert_eq!(run_program("!(parse (repr (foo (bar 1) \"s\")))"), Ok(vec![vec![expr!("foo" ("bar" {Number::Integer(1)}) {Str::from_str("s")})]]));
+        assert_eq!(run_program("!(parse \"(foo\")"), Ok(vec![vec![expr!("Error" ({stdlib::ParseOp::new(Shared::new(Tokenizer::new()))} {Str::from_str("(foo")}) "Unexpected end of expression")]]));
     }
 
     #[test]
-    fn metta_is_function() {
-        let result = run_program("!(eval (is-function (-> $t)))");
-        assert_eq!(result, Ok(vec![vec![expr!({Bool(true)})]]));
-        let result = run_program("!(eval (is-function (A $t)))");
-        assert_eq!(result, Ok(vec![vec![expr!({Bool(false)})]]));
-        let result = run_program("!(eval (is-function %Undefined%))");
-        assert_eq!(result, Ok(vec![vec![expr!({Bool(false)})]]));
+    fn metta_load_string() {
+        let program = "
+            !(load-string &self \"(= (f $x) ($x $x)) (= (g) B)\")
+            !(f A)
+            !(g)
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!({Number::Integer(2)})],
+            vec![expr!("A" "A")],
+            vec![expr!("B")],
+        ]));
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = metta.run(SExprParser::new("!(load-string &self \"(= (g) B) (= (f $x)\")")).unwrap();
+        match result[0].as_slice() {
+            [Atom::Expression(error)] => assert_eq!(error.children().last(),
+                Some(&Atom::sym("Unexpected end of expression"))),
+            _ => panic!("Error is expected, found: {:?}", result[0]),
+        }
+        assert_eq!(metta.run(SExprParser::new("!(g)")), Ok(vec![vec![expr!(("g"))]]));
     }
 
     #[test]
-    fn metta_type_cast() {
-        assert_eq!(run_program("(: a A) !(eval (type-cast a A &self))"), Ok(vec![vec![expr!("a")]]));
-        assert_eq!(run_program("(: a A) !(eval (type-cast a B &self))"), Ok(vec![vec![expr!("Error" "a" "BadType")]]));
-        assert_eq!(run_program("(: a A) !(eval (type-cast a %Undefined% &self))"), Ok(vec![vec![expr!("a")]]));
-        assert_eq!(run_program("!(eval (type-cast a B &self))"), Ok(vec![vec![expr!("a")]]));
-        assert_eq!(run_program("!(eval (type-cast 42 Number &self))"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
-        assert_eq!(run_program("!(eval (type-cast 42 %Undefined% &self))"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
-        assert_eq!(run_program("(: a A) !(eval (type-cast a Atom &self))"), Ok(vec![vec![expr!("a")]]));
-        assert_eq!(run_program("(: a A) !(eval (type-cast a Symbol &self))"), Ok(vec![vec![expr!("a")]]));
-        assert_eq!(run_program("!(eval (type-cast 42 Grounded &self))"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
-        assert_eq!(run_program("!(eval (type-cast () Expression &self))"), Ok(vec![vec![expr!()]]));
-        assert_eq!(run_program("!(eval (type-cast (a b) Expression &self))"), Ok(vec![vec![expr!("a" "b")]]));
-        assert_eq!(run_program("!(eval (type-cast $v Variable &self))"), Ok(vec![vec![expr!(v)]]));
-        assert_eq!(run_program("(: a A) (: b B) !(eval (type-cast (a b) (A B) &self))"), Ok(vec![vec![expr!("a" "b")]]));
-        assert_eq!(run_program("(: a A) (: a B) !(eval (type-cast a A &self))"), Ok(vec![vec![expr!("a")]]));
+    fn metta_parse_number() {
+        assert_eq!(run_program("!(parse-number \"42\")"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+        assert_eq!(run_program("!(parse-number \"3.14\")"), Ok(vec![vec![expr!({Number::Float(3.14)})]]));
+        assert_eq!(run_program("!(number->string 42)"), Ok(vec![vec![expr!({Str::from_str("42")})]]));
+        assert_eq!(run_program("!(number->string 3.14)"), Ok(vec![vec![expr!({Str::from_str("3.14")})]]));
+        assert_eq!(run_program("!(number->string 3.0)"), Ok(vec![vec![expr!({Str::from_str("3.0")})]]));
+        assert_eq!(run_program("!(parse-number (number->string -7))"), Ok(vec![vec![expr!({Number::Integer(-7)})]]));
+        assert_eq!(run_program("!(parse-number \"abc\")"), Ok(vec![vec![expr!("Error" ({stdlib::ParseNumberOp{}} {Str::from_str("abc")}) "parse-number expects numeric string, found: \"abc\"")]]));
     }
 
     #[test]
-    fn metta_filter_atom() {
-        assert_eq!(run_program("!(eval (filter-atom () $x (eval (if-error $x False True))))"), Ok(vec![vec![expr!()]]));
-        assert_eq!(run_program("!(eval (filter-atom (a (b) $c) $x (eval (if-error $x False True))))"), Ok(vec![vec![expr!("a" ("b") c)]]));
-        assert_eq!(run_program("!(eval (filter-atom (a (Error (b) \"Test error\") $c) $x (eval (if-error $x False True))))"), Ok(vec![vec![expr!("a" c)]]));
+    fn metta_split_join() {
+        let abc = expr!({Str::from_str("a")} {Str::from_str("b")} {Str::from_str("c")});
+        assert_eq!(run_program("!(split \",\" \"a,b,c\")"), Ok(vec![vec![abc]]));
+        assert_eq!(run_program("!(split \",\" \"abc\")"), Ok(vec![vec![expr!(({Str::from_str("abc")}))]]));
+        assert_eq!(run_program("!(split \",\" \"\")"), Ok(vec![vec![expr!()]]));
+        assert_eq!(run_program("!(split \", \" \"a, , b\")"), Ok(vec![vec![expr!({Str::from_str("a")} {Str::from_str("")} {Str::from_str("b")})]]));
+        assert_eq!(run_program("!(join \",\" (\"a\" \"b\" \"c\"))"), Ok(vec![vec![expr!({Str::from_str("a,b,c")})]]));
+        assert_eq!(run_program("!(join \",\" ())"), Ok(vec![vec![expr!({Str::from_str("")})]]));
+        assert_eq!(run_program("!(let $parts (split \",\" \"a,b,,c\") (join \"-\" $parts))"), Ok(vec![vec![expr!({Str::from_str("a-b--c")})]]));
+        assert_eq!(run_program("!(let $parts (split \",\" \"\") (join \",\" $parts))"), Ok(vec![vec![expr!({Str::from_str("")})]]));
     }
 
     #[test]
-    fn metta_map_atom() {
-        assert_eq!(run_program("!(eval (map-atom () $x ($x mapped)))"), Ok(vec![vec![expr!()]]));
-        assert_eq!(run_program("!(eval (map-atom (a (b) $c) $x (mapped $x)))"), Ok(vec![vec![expr!(("mapped" "a") ("mapped" ("b")) ("mapped" c))]]));
+    fn metta_subtract_space() {
+        let program = "
+            !(bind! &a (new-space))
+            !(bind! &b (new-space))
+            !(add-atom &a (likes Sam pizza))
+            !(add-atom &a (likes Sam pasta))
+            !(add-atom &a (likes Ann pizza))
+            !(add-atom &b (likes Sam pasta))
+            !(add-atom &b (likes Bob pasta))
+            !(subtract &a &b)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq_no_order!(result[7], vec![expr!("likes" "Sam" "pizza"), expr!("likes" "Ann" "pizza")]);
     }
 
     #[test]
-    fn metta_foldl_atom() {
-        assert_eq!(run_program("!(eval (foldl-atom () 1 $a $b (eval (+ $a $b))))"), Ok(vec![vec![expr!({Number::Integer(1)})]]));
-        assert_eq!(run_program("!(eval (foldl-atom (1 2 3) 0 $a $b (eval (+ $a $b))))"), Ok(vec![vec![expr!({Number::Integer(6)})]]));
+    fn metta_intersect_space() {
+        let program = "
+            !(bind! &a (new-space))
+            !(bind! &b (new-space))
+            !(add-atom &a (likes Sam pizza))
+            !(add-atom &a (likes Sam pasta))
+            !(add-atom &a (likes Ann pizza))
+            !(add-atom &b (likes Ann pizza))
+            !(add-atom &b (likes Sam pasta))
+            !(add-atom &b (likes Bob pasta))
+            !(intersect &a &b)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq_no_order!(result[8], vec![expr!("likes" "Sam" "pasta"), expr!("likes" "Ann" "pizza")]);
     }
 
     #[test]
-    fn metta_interpret_single_atom_as_atom() {
-        let result = run_program("!(metta A Atom &self)");
-        assert_eq!(result, Ok(vec![vec![expr!("A")]]));
+    fn metta_merge_dedup() {
+        let program = "
+            !(bind! &a (new-space))
+            !(bind! &b (new-space))
+            !(add-atom &a (likes Sam pizza))
+            !(add-atom &a (likes Ann pizza))
+            !(add-atom &a (likes Sam pizza))
+            !(add-atom &b (likes Bob pasta))
+            !(add-atom &b (likes Ann pizza))
+            !(bind! &merged (merge-dedup &a &b))
+            !(get-atoms &merged)
+            !(add-atom &merged (likes Bob soup))
+            !(match &b (likes Bob $x) $x)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[8], vec![
+            expr!("likes" "Sam" "pizza"),
+            expr!("likes" "Ann" "pizza"),
+            expr!("likes" "Bob" "pasta"),
+        ]);
+        assert_eq!(result[10], vec![expr!("pasta")]);
     }
 
     #[test]
-    fn metta_interpret_single_atom_as_meta_type() {
-        assert_eq!(run_program("!(metta A Symbol &self)"), Ok(vec![vec![expr!("A")]]));
-        assert_eq!(run_program("!(metta $x Variable &self)"), Ok(vec![vec![expr!(x)]]));
-        assert_eq!(run_program("!(metta (A B) Expression &self)"), Ok(vec![vec![expr!("A" "B")]]));
-        assert_eq!(run_program("!(metta 42 Grounded &self)"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+    fn metta_clone_space() {
+        let program = "
+            !(bind! &a (new-space))
+            !(add-atom &a (likes Sam pizza))
+            !(bind! &b (clone-space &a))
+            !(add-atom &b (likes Sam pasta))
+            !(remove-atom &b (likes Sam pizza))
+            !(match &a (likes Sam $x) $x)
+            !(match &b (likes Sam $x) $x)
+            !(add-atom &a (likes Ann pizza))
+            !(match &b (likes Ann $x) $x)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[5], vec![expr!("pizza")]);
+        assert_eq!(result[6], vec![expr!("pasta")]);
+        assert_eq!(result[8], vec![]);
     }
 
     #[test]
-    fn metta_interpret_symbol_or_grounded_value_as_type() {
-        assert_eq!(run_program("(: a A) !(metta a A &self)"), Ok(vec![vec![expr!("a")]]));
-        assert_eq!(run_program("(: a A) !(metta a B &self)"), Ok(vec![vec![expr!("Error" "a" "BadType")]]));
-        assert_eq!(run_program("!(metta 42 Number &self)"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+    fn metta_filter_space() {
+        let program = "
+            !(bind! &kb (new-space))
+            !(add-atom &kb (likes Sam pizza))
+            !(add-atom &kb (likes Ann pasta))
+            !(add-atom &kb (age Sam 30))
+            !(add-atom &kb Sam)
+            !(bind! &likes (filter-space &kb (likes $x $y)))
+            !(get-atoms &likes)
+            !(add-atom &kb (likes Bob soup))
+            !(match &likes (likes Bob $x) $x)
+            !(match &kb (age Sam $x) $x)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq_no_order!(result[6], vec![expr!("likes" "Sam" "pizza"), expr!("likes" "Ann" "pasta")]);
+        assert_eq!(result[8], vec![]);
+        assert_eq!(result[9], vec![expr!({Number::Integer(30)})]);
     }
 
     #[test]
-    fn metta_interpret_variable_as_type() {
-        assert_eq!(run_program("!(metta $x %Undefined% &self)"), Ok(vec![vec![expr!(x)]]));
-        assert_eq!(run_program("!(metta $x SomeType &self)"), Ok(vec![vec![expr!(x)]]));
+    fn metta_rename_symbol() {
+        let program = "
+            !(bind! &kb (new-space))
+            !(add-atom &kb (parent Tom Bob))
+            !(add-atom &kb (= (grandparent Ann) (parent (parent Ann))))
+            !(add-atom &kb (likes Tom (parent of Bob)))
+            !(bind! &renamed (rename-symbol &kb parent father))
+            !(get-atoms &renamed)
+            !(match &kb (parent $x $y) ($x $y))
+            !(rename-symbol &kb (parent) father)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq_no_order!(result[5], vec![
+            expr!("father" "Tom" "Bob"),
+            expr!("=" ("grandparent" "Ann") ("father" ("father" "Ann"))),
+            expr!("likes" "Tom" ("father" "of" "Bob")),
+        ]);
+        assert_eq!(result[6], vec![expr!("Tom" "Bob")]);
+        assert_eq!(result[7].len(), 1);
+        assert!(result[7][0].to_string().ends_with("rename-symbol expects space, old symbol and new symbol as arguments)"));
     }
 
     #[test]
-    fn metta_interpret_empty_expression_as_type() {
-        assert_eq!(run_program("!(metta () %Undefined% &self)"), Ok(vec![vec![expr!(())]]));
-        assert_eq!(run_program("!(metta () SomeType &self)"), Ok(vec![vec![expr!(())]]));
+    fn metta_space_stats() {
+        let program = "
+            !(bind! &kb (new-space))
+            !(space-stats &kb)
+            !(add-atom &kb (= (parent Tom) Bob))
+            !(add-atom &kb (= (parent Bob) Ann))
+            !(add-atom &kb (= (grandparent $x) (parent (parent $x))))
+            !(add-atom &kb (= answer 42))
+            !(add-atom &kb (likes Sam pizza))
+            !(add-atom &kb (likes Ann pasta))
+            !(add-atom &kb Fact)
+            !(add-atom &kb ())
+            !(space-stats &kb)
+        ";
+        let result = run_program(program).unwrap();
+        let stats = |atoms, rules, heads| vec![expr!(("atoms" {Number::Integer(atoms)}) ("rules" {Number::Integer(rules)}) ("heads" {Number::Integer(heads)}))];
+        assert_eq!(result[1], stats(0, 0, 0));
+        assert_eq!(result[10], stats(8, 4, 2));
     }
 
     #[test]
-    fn metta_interpret_single_atom_as_variable_type() {
-        let result = run_program("
-            (: S Int)
-            !(chain (metta S $t &self) $res (: $res $t))
-        ");
-        assert_eq!(result, Ok(vec![vec![expr!(":" "S" "Int")]]));
+    fn metta_check_consistent() {
+        let program = "
+            !(bind! &kb (new-space))
+            !(add-atom &kb (= (frog $x) (and (croaks $x) (eats-flies $x))))
+            !(add-atom &kb (= (green Fritz) True))
+            !(add-atom &kb (= (color) red))
+            !(add-atom &kb (= (color) green))
+            !(add-atom &kb (= (green $x) False))
+            !(add-atom &kb (= (green Fritz) Yes))
+            !(check-consistent &kb)
+            !(remove-atom &kb (= (green $x) False))
+            !(add-atom &kb (= (green Frederick) False))
+            !(check-consistent &kb)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[7], vec![expr!(((("=" ("green" "Fritz") {Bool(true)}) ("=" ("green" x) {Bool(false)}))))]);
+        assert_eq!(result[8], vec![UNIT_ATOM()]);
+        assert_eq!(result[10], vec![expr!()]);
     }
 
     #[test]
-    fn metta_interpret_func() {
-        let result = run_program("
-            (: a T)
-            (: foo (-> T T))
-            (= (foo $x) $x)
-            (= (bar $x) $x)
-            !(metta (foo (bar a)) %Undefined% &self)
-        ");
-        assert_eq!(result, Ok(vec![vec![expr!("a")]]));
-        let result = run_program("
-            (: b B)
-            (: foo (-> T T))
-            (= (foo $x) $x)
-            !(metta (foo b) %Undefined% &self)
-        ");
-        assert_eq!(result, Ok(vec![vec![expr!("Error" "b" "BadType")]]));
-        let result = run_program("
-            (: Nil (List $t))
-            (: Z Nat)
-            (: S (-> Nat Nat))
-            (: Cons (-> $t (List $t) (List $t)))
-            !(metta (Cons S (Cons Z Nil)) %Undefined% &self)
-        ");
-        assert_eq!(result, Ok(vec![vec![expr!("Error" ("Cons" "Z" "Nil") "BadType")]]));
+    fn rewrite_op() {
+        let space = DynSpace::new(metta_space("
+            (= (add Z $y) $y)
+            (= (add (S $x) $y) (S (add $x $y)))
+        "));
+        let rewrite_op = stdlib::RewriteOp{};
+
+        assert_eq!(rewrite_op.execute(&mut vec![expr!({space.clone()}), expr!("add" ("S" "Z") ("S" "Z"))]),
+            Ok(vec![expr!("quote" ("S" ("add" "Z" ("S" "Z"))))]));
+        assert_eq!(rewrite_op.execute(&mut vec![expr!({space.clone()}), expr!("add" "Z" ("S" "Z"))]),
+            Ok(vec![expr!("quote" ("S" "Z"))]));
+        assert_eq!(rewrite_op.execute(&mut vec![expr!({space.clone()}), expr!("mul" "Z" ("S" "Z"))]),
+            Ok(vec![expr!("quote" ("mul" "Z" ("S" "Z")))]));
     }
 
     #[test]
-    fn metta_interpret_tuple() {
-        assert_eq!(run_program("!(metta () %Undefined% &self)"), Ok(vec![vec![expr!(())]]));
-        assert_eq!(run_program("!(metta (a) %Undefined% &self)"), Ok(vec![vec![expr!(("a"))]]));
-        assert_eq!(run_program("!(metta (a b) %Undefined% &self)"), Ok(vec![vec![expr!(("a" "b"))]]));
-        assert_eq!(run_program("
-            (= (foo $x) (bar $x))
-            (= (bar $x) (baz $x))
-            (= (baz $x) $x)
-            !(metta ((foo A) (foo B)) %Undefined% &self)
-        "), Ok(vec![vec![expr!("A" "B")]]));
+    fn metta_rewrite() {
+        let program = "
+            (= (add Z $y) $y)
+            (= (add (S $x) $y) (S (add $x $y)))
+            !(rewrite &self (add (S Z) (S Z)))
+            !(let $x (rewrite &self (add (S Z) (S Z))) (unquote $x))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("quote" ("S" ("add" "Z" ("S" "Z"))))],
+            vec![expr!("S" ("S" "Z"))],
+        ]));
     }
 
     #[test]
-    fn metta_interpret_expression_as_type() {
-        assert_eq!(run_program("(= (foo $x) $x) !(metta (foo a) %Undefined% &self)"), Ok(vec![vec![expr!("a")]]));
-        assert_eq!(run_program("!(metta (foo a) %Undefined% &self)"), Ok(vec![vec![expr!("foo" "a")]]));
-        assert_eq!(run_program("!(metta () SomeType &self)"), Ok(vec![vec![expr!(())]]));
+    fn metta_rewrite_first() {
+        let program = "
+            (= (f $x) first)
+            (= (f a) second)
+            !(rewrite-first &self (f a))
+            !(bind! &reversed (new-space))
+            !(add-atom &reversed (= (f a) second))
+            !(add-atom &reversed (= (f $x) first))
+            !(rewrite-first &reversed (f a))
+            !(rewrite-first &self (g a))
+            (= (add Z $y) $y)
+            (= (add (S $x) $y) (S (add $x $y)))
+            !(rewrite-first &self (add (S Z) (S Z)))
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[0], vec![expr!("quote" "first")]);
+        assert_eq!(result[4], vec![expr!("quote" "second")]);
+        assert_eq!(result[5], vec![expr!("quote" ("g" "a"))]);
+        assert_eq!(result[6], vec![expr!("quote" ("S" ("add" "Z" ("S" "Z"))))]);
     }
 
     #[test]
-    fn metta_interpret_single_atom_with_two_types() {
-        let result = run_program("(: a A) (: a B) !(metta a %Undefined% &self)");
-        assert_eq!(result, Ok(vec![vec![expr!("a")]]));
+    fn metta_get_definitions() {
+        let program = "
+            (= (f) zero)
+            (= (f $x) one)
+            (= (g $x) (f $x))
+            (= (f $x $y) two)
+            (= f three)
+            (f a)
+            !(get-definitions &self f)
+            !(get-definitions &self h)
+        ";
+        let result = run_program(program).unwrap();
+        assert_eq!(result[0], vec![
+            expr!("quote" ("=" ("f") "zero")),
+            expr!("quote" ("=" ("f" x) "one")),
+            expr!("quote" ("=" ("f" x y) "two")),
+        ]);
+        assert_eq!(result[1], vec![]);
     }
 
     #[test]
-    fn metta_assert_equal_op() {
-        let metta = Metta::new(Some(EnvBuilder::test_env()));
-        let assert = AssertEqualOp::new(metta.space().clone());
+    fn metta_query_dict() {
         let program = "
-            (= (foo $x) $x)
-            (= (bar $x) $x)
+            (parent Tom Bob)
+            (parent Bob Ann)
+            (age Tom 60)
+
+            !(query-dict &self (parent $p $c))
+            !(query-dict &self (parent $_ $c))
+            !(query-dict &self (parent $p Ann))
+            !(query-dict &self (parent Ann $c))
+
+            (= (children $p) (query-dict &self (parent $p $child)))
+            !(children Tom)
         ";
-        assert_eq!(metta.run(SExprParser::new(program)), Ok(vec![]));
-        assert_eq!(metta.run(SExprParser::new("!(assertEqual (foo A) (bar A))")), Ok(vec![
-            vec![UNIT_ATOM()],
-        ]));
-        assert_eq!(metta.run(SExprParser::new("!(assertEqual (foo A) (bar B))")), Ok(vec![
-            vec![expr!("Error" ({assert.clone()} ("foo" "A") ("bar" "B")) "\nExpected: [B]\nGot: [A]\nMissed result: B")],
-        ]));
-        assert_eq!(metta.run(SExprParser::new("!(assertEqual (foo A) Empty)")), Ok(vec![
-            vec![expr!("Error" ({assert.clone()} ("foo" "A") "Empty") "\nExpected: []\nGot: [A]\nExcessive result: A")]
-        ]));
+
+        let result = run_program(program).unwrap();
+        assert_eq_no_order!(result[0], vec![expr!(("p" "Tom") ("c" "Bob")), expr!(("p" "Bob") ("c" "Ann"))]);
+        assert_eq_no_order!(result[1], vec![Atom::expr([expr!("c" "Bob")]), Atom::expr([expr!("c" "Ann")])]);
+        assert_eq!(result[2], vec![Atom::expr([expr!("p" "Bob")])]);
+        assert_eq!(result[3], Vec::<Atom>::new());
+        assert_eq!(result[4], vec![Atom::expr([expr!("child" "Bob")])]);
     }
 
     #[test]
-    fn metta_assert_equal_to_result_op() {
-        let metta = Metta::new(Some(EnvBuilder::test_env()));
-        let assert = AssertEqualToResultOp::new(metta.space().clone());
+    fn metta_provable() {
         let program = "
-            (= (foo) A)
-            (= (foo) B)
-            (= (bar) C)
-            (= (baz) D)
-            (= (baz) D)
+            (bird Tweety)
+            (bird Pingu)
+            (penguin Pingu)
+            (= (flies $x) (if (not (provable &self (penguin $x))) True False))
+
+            !(provable &self (bird Tweety))
+            !(not (provable &self (penguin Tweety)))
+            !(not (provable &self (penguin Pingu)))
+            !(flies Tweety)
+            !(flies Pingu)
         ";
-        assert_eq!(metta.run(SExprParser::new(program)), Ok(vec![]));
-        assert_eq!(metta.run(SExprParser::new("!(assertEqualToResult (foo) (A B))")), Ok(vec![
-            vec![UNIT_ATOM()],
-        ]));
-        assert_eq!(metta.run(SExprParser::new("!(assertEqualToResult (bar) (A))")), Ok(vec![
-            vec![expr!("Error" ({assert.clone()} ("bar") ("A")) "\nExpected: [A]\nGot: [C]\nMissed result: A")],
+
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!({Bool(true)})],
+            vec![expr!({Bool(true)})],
+            vec![expr!({Bool(false)})],
+            vec![expr!({Bool(true)})],
+            vec![expr!({Bool(false)})],
         ]));
-        assert_eq!(metta.run(SExprParser::new("!(assertEqualToResult (baz) (D))")), Ok(vec![
-            vec![expr!("Error" ({assert.clone()} ("baz") ("D")) "\nExpected: [D]\nGot: [D, D]\nExcessive result: D")]
+    }
+
+    #[test]
+    fn metta_unique() {
+        let program = "
+            (= (color) red)
+            (= (color) green)
+            (= (color) red)
+            (= (color) blue)
+            (= (color) green)
+
+            !(unique (superpose (a a b)))
+            !(unique (color))
+            !(unique (superpose ()))
+        ";
+        assert_eq_metta_results!(run_program(program), Ok(vec![
+            vec![expr!("a"), expr!("b")],
+            vec![expr!("red"), expr!("green"), expr!("blue")],
+            vec![],
         ]));
     }
 
     #[test]
-    fn metta_superpose() {
-        assert_eq_metta_results!(run_program("!(superpose (red yellow green))"),
-            Ok(vec![vec![expr!("red"), expr!("yellow"), expr!("green")]]));
+    fn metta_limit() {
+        assert_eq!(run_program("!(limit 2 (superpose (a b c)))"), Ok(vec![vec![expr!("a"), expr!("b")]]));
+        assert_eq!(run_program("!(limit 5 (superpose (a b c)))"), Ok(vec![vec![expr!("a"), expr!("b"), expr!("c")]]));
+        assert_eq!(run_program("!(limit 0 (superpose (a b c)))"), Ok(vec![vec![]]));
+        assert_eq!(run_program("!(limit -1 (superpose (a b c)))"), Ok(vec![vec![expr!("Error" ({LimitOp::new(DynSpace::new(GroundingSpace::new()))} {Number::Integer(-1)} ({SuperposeOp::new(DynSpace::new(GroundingSpace::new()))} ("a" "b" "c"))) "limit expects two arguments: non-negative integer number and executable atom")]]));
+    }
+
+    #[test]
+    fn metta_space_to_expr() {
         let program = "
-            (= (foo) FOO)
-            (= (bar) BAR)
-            !(superpose ((foo) (bar) BAZ))
+            !(bind! &a (new-space))
+            !(add-atom &a (likes Sam pizza))
+            !(add-atom &a Sam)
+            !(add-atom &a (likes Ann (italian pasta)))
+            !(space->expr &a)
+            !(space->expr (expr->space (a (b c) d)))
+            !(space->expr (new-space))
         ";
-        assert_eq_metta_results!(run_program(program),
-            Ok(vec![vec![expr!("FOO"), expr!("BAR"), expr!("BAZ")]]));
+        let result = run_program(program).unwrap();
+        assert_eq!(result[4], vec![expr!(("likes" "Sam" "pizza") "Sam" ("likes" "Ann" ("italian" "pasta")))]);
+        assert_eq!(result[5], vec![expr!("a" ("b" "c") "d")]);
+        assert_eq!(result[6], vec![expr!()]);
     }
 
     #[test]
-    fn metta_collapse() {
+    fn metta_destructuring_bind() {
         let program = "
+            (= (point) (pair 1 2))
+
+            !(bind ($x $y) (pair 1 2) (+ $x $y))
+            !(bind ($x $y) (point) (* $x $y))
+            !(bind ($x $x) (pair 1 2) (+ $x $x))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!({Number::Integer(3)})],
+            vec![expr!({Number::Integer(2)})],
+            vec![],
+        ]));
+    }
+
+    #[test]
+    fn trace_value_op() {
+        let space = DynSpace::new(metta_space("
             (= (color) red)
             (= (color) green)
-            (= (color) blue)
-            !(collapse (color))
-        ";
-        let result = run_program(program).expect("Successful result is expected");
-        assert_eq!(result.len(), 1);
-        let result = result.get(0).unwrap();
-        assert_eq!(result.len(), 1);
-        let result = result.get(0).unwrap();
-        let actual = <&ExpressionAtom>::try_from(result)
-            .expect("Expression atom is expected").children();
-        assert_eq_no_order!(actual, vec![expr!("red"), expr!("green"), expr!("blue")]);
+        "));
+        let output = Shared::new(Vec::new());
+        let trace_op = TraceValueOp::with_output(space, output.clone());
+
+        let actual = trace_op.execute(&mut vec![expr!({Str::from_str("colors")}), expr!(("color"))]).unwrap();
+
+        assert_eq_no_order!(actual, vec![sym!("red"), sym!("green")]);
+        assert_eq_no_order!(output.borrow().clone(), vec!["colors: red".to_string(), "colors: green".to_string()]);
+    }
+
+    #[test]
+    fn metta_trace() {
+        assert_eq!(run_program("!(+ 1 (trace \"sum\" (+ 1 2)))"), Ok(vec![vec![expr!({Number::Integer(4)})]]));
     }
 
     #[test]
@@ -935,6 +4876,28 @@ mod tests {
             Ok(vec![vec![expr!("blue"), expr!("red"), expr!("green")]]));
     }
 
+    #[test]
+    fn test_grounded_error_is_dropped_when_other_branches_succeed() {
+        let program = "
+            (= (index) 0)
+            (= (index) 5)
+            (= (index) 1)
+
+            !(index-atom (a b) (index))
+            !(index-atom (a b) 5)
+        ";
+
+        let result = run_program(program);
+        // error of the (index-atom (a b) 5) branch is dropped because other
+        // branches are successful, it is returned only when no branch succeeds
+        assert!(result.as_ref().unwrap()[0].iter().all(|atom| !atom_is_error(atom)));
+        assert_eq_metta_results!(result,
+            Ok(vec![
+                vec![expr!("a"), expr!("b")],
+                vec![expr!("Error" ({stdlib::IndexAtomOp{}} ("a" "b") {Number::Integer(5)}) "Index is out of bounds")],
+            ]));
+    }
+
     #[test]
     fn test_variable_keeps_value_in_different_sub_expressions() {
         let program = "
@@ -1209,6 +5172,26 @@ mod tests {
         ]));
     }
 
+    #[test]
+    fn test_get_doc_builtin_gnd_func() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let parser = SExprParser::new(r#"
+            !(get-doc +)
+        "#);
+
+        assert_eq_metta_results!(metta.run(parser), Ok(vec![
+            vec![expr!("@doc-formal"
+                ("@item" {SumOp{}})
+                ("@kind" "function")
+                ("@type" ("->" "Number" "Number" "Number"))
+                ("@desc" {Str::from_str("Sums two numbers")})
+                ("@params" (
+                    ("@param" ("@type" "Number") ("@desc" {Str::from_str("Addend")}))
+                    ("@param" ("@type" "Number") ("@desc" {Str::from_str("Augend")})) ))
+                ("@return" ("@type" "Number") ("@desc" {Str::from_str("Sum")})) )],
+        ]));
+    }
+
     #[test]
     fn test_get_doc_function_call() {
         let metta = Metta::new(Some(EnvBuilder::test_env()));
@@ -4,7 +4,6 @@ use crate::*;
 
 use core::ops::Range;
 use std::str::CharIndices;
-use std::iter::Peekable;
 use regex::Regex;
 use std::rc::Rc;
 
@@ -127,56 +126,128 @@ impl SyntaxNodeType {
     }
 }
 
+/// Position of a char in the input text.  Both line and column start from 1, column is counted
+///   in chars, not in bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl TextPosition {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self{ line, column }
+    }
+}
+
+impl Default for TextPosition {
+    fn default() -> Self {
+        Self::new(1, 1)
+    }
+}
+
+impl std::fmt::Display for TextPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Error found while parsing the input text
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// Description of the error
+    pub message: String,
+    /// Position of the unexpected input in the text
+    pub position: TextPosition,
+    /// Unexpected char or token, `None` when the end of the text is reached
+    pub found: Option<String>,
+    /// Short description of the input which is expected at the position
+    pub expected: Option<String>,
+}
+
+impl ParseError {
+    fn new(message: &str, position: TextPosition, found: Option<String>, expected: Option<&str>) -> Self {
+        Self{ message: message.to_string(), position, found, expected: expected.map(str::to_string) }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.position, self.message)?;
+        if let Some(expected) = &self.expected {
+            match &self.found {
+                Some(found) => write!(f, ": expected {}, found '{}'", expected, found),
+                None => write!(f, ": expected {}, found end of text", expected),
+            }
+        } else {
+            Ok(())
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SyntaxNode {
     pub node_type: SyntaxNodeType,
     pub src_range: Range<usize>,
+    pub src_position: TextPosition,
     pub sub_nodes: Vec<SyntaxNode>,
     pub parsed_text: Option<String>,
     pub message: Option<String>,
+    pub error: Option<ParseError>,
     pub is_complete: bool,
 }
 
 impl SyntaxNode {
-    fn new(node_type: SyntaxNodeType, src_range: Range<usize>, sub_nodes: Vec<SyntaxNode>) -> SyntaxNode {
+    fn new(node_type: SyntaxNodeType, src_range: Range<usize>, src_position: TextPosition, sub_nodes: Vec<SyntaxNode>) -> SyntaxNode {
         Self {
             node_type,
             src_range,
+            src_position,
             parsed_text: None,
             sub_nodes,
             message: None,
+            error: None,
             is_complete: true
         }
     }
 
-    fn new_token_node(node_type: SyntaxNodeType, src_range: Range<usize>, parsed_text: String) -> SyntaxNode {
-        let mut node = SyntaxNode::new(node_type, src_range, vec![]);
+    fn new_token_node(node_type: SyntaxNodeType, src_range: Range<usize>, src_position: TextPosition, parsed_text: String) -> SyntaxNode {
+        let mut node = SyntaxNode::new(node_type, src_range, src_position, vec![]);
         node.parsed_text = Some(parsed_text);
         node
     }
 
-    fn incomplete_with_message(node_type: SyntaxNodeType, src_range: Range<usize>, sub_nodes: Vec<SyntaxNode>, message: String) -> SyntaxNode {
-        let mut node = SyntaxNode::new(node_type, src_range, sub_nodes);
-        node.message = Some(message);
+    fn incomplete_with_error(node_type: SyntaxNodeType, src_range: Range<usize>, src_position: TextPosition, sub_nodes: Vec<SyntaxNode>, error: ParseError) -> SyntaxNode {
+        let mut node = SyntaxNode::new(node_type, src_range, src_position, sub_nodes);
+        node.message = Some(error.message.clone());
+        node.error = Some(error);
         node.is_complete = false;
         node
     }
 
-    /// Creates a new error group.  Gets the error message associated with the last node
-    fn new_error_group(src_range: Range<usize>, sub_nodes: Vec<SyntaxNode>) -> SyntaxNode {
+    /// Creates a new error group.  Gets the error associated with the last node
+    fn new_error_group(src_range: Range<usize>, src_position: TextPosition, sub_nodes: Vec<SyntaxNode>) -> SyntaxNode {
         let message = sub_nodes[sub_nodes.len()-1].message.clone();
-        let mut node = SyntaxNode::new(SyntaxNodeType::ErrorGroup, src_range, sub_nodes);
+        let error = sub_nodes[sub_nodes.len()-1].error.clone();
+        let mut node = SyntaxNode::new(SyntaxNodeType::ErrorGroup, src_range, src_position, sub_nodes);
         node.message = message;
+        node.error = error;
         node.is_complete = false;
         node
     }
 
     /// Transforms a root SyntaxNode into an [Atom]
     pub fn as_atom(&self, tokenizer: &Tokenizer) -> Result<Option<Atom>, String> {
+        self.as_atom_with_error(tokenizer).map_err(|err| err.message)
+    }
+
+    /// Transforms a root SyntaxNode into an [Atom], returns [ParseError] with the
+    ///   position of the problem in case of failure
+    pub fn as_atom_with_error(&self, tokenizer: &Tokenizer) -> Result<Option<Atom>, ParseError> {
 
         //If we have an incomplete node, it's an error
         if !self.is_complete {
-            return Err(self.message.clone().unwrap())
+            return Err(self.error.clone().unwrap())
         }
 
         match self.node_type {
@@ -195,7 +266,8 @@ impl SyntaxNode {
                 let constr = tokenizer.find_token(token_text);
                 if let Some(constr) = constr {
                     let new_atom = constr(token_text)
-                        .map_err(|e| format!("byte range = ({:?}) | {e}", self.src_range))?;
+                        .map_err(|e| ParseError::new(&format!("byte range = ({:?}) | {e}", self.src_range),
+                            self.src_position, Some(token_text.clone()), None))?;
                     Ok(Some(new_atom))
                 } else {
                     let new_atom = Atom::sym(token_text);
@@ -205,7 +277,7 @@ impl SyntaxNode {
             SyntaxNodeType::ExpressionGroup => {
                 let mut err_encountered = Ok(());
                 let expr_children: Vec<Atom> = self.sub_nodes.iter().filter_map(|node| {
-                    match node.as_atom(tokenizer) {
+                    match node.as_atom_with_error(tokenizer) {
                         Err(err) => {
                             err_encountered = Err(err);
                             None
@@ -261,6 +333,51 @@ impl Parser for &mut (dyn Parser + '_) {
     }
 }
 
+/// Iterator over the chars of the text which keeps track of the line and column
+///   of the next char
+#[derive(Clone)]
+struct TextIter<'a> {
+    chars: CharIndices<'a>,
+    next: Option<(usize, char)>,
+    position: TextPosition,
+}
+
+impl<'a> TextIter<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut chars = text.char_indices();
+        let next = chars.next();
+        Self{ chars, next, position: TextPosition::default() }
+    }
+
+    fn peek(&self) -> Option<&(usize, char)> {
+        self.next.as_ref()
+    }
+
+    /// Returns the position of the next char, or the position after the last char
+    ///   when the end of the text is reached
+    fn position(&self) -> TextPosition {
+        self.position
+    }
+}
+
+impl Iterator for TextIter<'_> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cur = self.next.take();
+        if let Some((_idx, c)) = cur {
+            if c == '\n' {
+                self.position.line += 1;
+                self.position.column = 1;
+            } else {
+                self.position.column += 1;
+            }
+            self.next = self.chars.next();
+        }
+        cur
+    }
+}
+
 /// Provides a parser for MeTTa code written in S-Expression Syntax
 ///
 /// NOTE: The SExprParser type is short-lived, and can be created cheaply to evaluate a specific block
@@ -268,19 +385,25 @@ impl Parser for &mut (dyn Parser + '_) {
 #[derive(Clone)]
 pub struct SExprParser<'a> {
     text: &'a str,
-    it: Peekable<CharIndices<'a>>,
+    it: TextIter<'a>,
 }
 
 impl<'a> SExprParser<'a> {
     pub fn new(text: &'a str) -> Self {
-        Self{ text, it: text.char_indices().peekable() }
+        Self{ text, it: TextIter::new(text) }
     }
 
     pub fn parse(&mut self, tokenizer: &Tokenizer) -> Result<Option<Atom>, String> {
+        self.parse_with_error(tokenizer).map_err(|err| err.message)
+    }
+
+    /// Parses the next atom like [SExprParser::parse] does, but returns [ParseError]
+    ///   which contains the line and column of the problem in case of failure
+    pub fn parse_with_error(&mut self, tokenizer: &Tokenizer) -> Result<Option<Atom>, ParseError> {
         loop {
             match self.parse_to_syntax_tree() {
                 Some(node) => {
-                    if let Some(atom) = node.as_atom(tokenizer)? {
+                    if let Some(atom) = node.as_atom_with_error(tokenizer)? {
                         return Ok(Some(atom))
                     }
                 },
@@ -293,13 +416,14 @@ impl<'a> SExprParser<'a> {
 
     pub fn parse_to_syntax_tree(&mut self) -> Option<SyntaxNode> {
         if let Some((idx, c)) = self.it.peek().cloned() {
+            let pos = self.it.position();
             match c {
                 ';' => {
                     let comment_node = self.parse_comment().unwrap();
                     return Some(comment_node);
                 },
                 _ if c.is_whitespace() => {
-                    let whispace_node = SyntaxNode::new(SyntaxNodeType::Whitespace, idx..idx+1, vec![]);
+                    let whispace_node = SyntaxNode::new(SyntaxNodeType::Whitespace, idx..idx+1, pos, vec![]);
                     self.it.next();
                     return Some(whispace_node);
                 },
@@ -312,10 +436,11 @@ impl<'a> SExprParser<'a> {
                     return Some(expr_node);
                 },
                 ')' => {
-                    let close_paren_node = SyntaxNode::new(SyntaxNodeType::CloseParen, idx..idx+1, vec![]);
+                    let close_paren_node = SyntaxNode::new(SyntaxNodeType::CloseParen, idx..idx+1, pos, vec![]);
                     self.it.next();
-                    let leftover_text_node = self.parse_leftovers("Unexpected right bracket".to_string());
-                    let error_group_node = SyntaxNode::new_error_group(idx..self.cur_idx(), vec![close_paren_node, leftover_text_node]);
+                    let error = ParseError::new("Unexpected right bracket", pos, Some(")".to_string()), Some("atom or '('"));
+                    let leftover_text_node = self.parse_leftovers(error);
+                    let error_group_node = SyntaxNode::new_error_group(idx..self.cur_idx(), pos, vec![close_paren_node, leftover_text_node]);
                     return Some(error_group_node);
                 },
                 _ => {
@@ -336,9 +461,15 @@ impl<'a> SExprParser<'a> {
         }
     }
 
+    /// Returns the next char as a string, or `None` at the end of the text
+    fn cur_char(&self) -> Option<String> {
+        self.it.peek().map(|(_idx, c)| c.to_string())
+    }
+
     /// Parse to the next `\n` newline
     fn parse_comment(&mut self) -> Option<SyntaxNode> {
         if let Some((start_idx, _c)) = self.it.peek().cloned() {
+            let start_pos = self.it.position();
             while let Some((_idx, c)) = self.it.peek() {
                 match c {
                     '\n' => break,
@@ -346,44 +477,47 @@ impl<'a> SExprParser<'a> {
                 }
             }
             let range = start_idx..self.cur_idx();
-            Some(SyntaxNode::new(SyntaxNodeType::Comment, range, vec![]))
+            Some(SyntaxNode::new(SyntaxNodeType::Comment, range, start_pos, vec![]))
         } else {
             None
         }
     }
 
-    fn parse_leftovers(&mut self, message: String) -> SyntaxNode {
+    fn parse_leftovers(&mut self, error: ParseError) -> SyntaxNode {
         let start_idx = self.cur_idx();
+        let start_pos = self.it.position();
         while let Some(_) = self.it.next() {}
         let range = start_idx..self.cur_idx();
-        SyntaxNode::incomplete_with_message(SyntaxNodeType::LeftoverText, range, vec![], message)
+        SyntaxNode::incomplete_with_error(SyntaxNodeType::LeftoverText, range, start_pos, vec![], error)
     }
 
     fn parse_expr(&mut self) -> SyntaxNode {
         let start_idx = self.cur_idx();
+        let start_pos = self.it.position();
         let mut child_nodes: Vec<SyntaxNode> = Vec::new();
 
-        let open_paren_node = SyntaxNode::new(SyntaxNodeType::OpenParen, start_idx..start_idx+1, vec![]);
+        let open_paren_node = SyntaxNode::new(SyntaxNodeType::OpenParen, start_idx..start_idx+1, start_pos, vec![]);
         child_nodes.push(open_paren_node);
         self.it.next();
 
         while let Some((idx, c)) = self.it.peek().cloned() {
+            let pos = self.it.position();
             match c {
                 ';' => {
                     let comment_node = self.parse_comment().unwrap();
                     child_nodes.push(comment_node);
                 },
                 _ if c.is_whitespace() => {
-                    let whitespace_node = SyntaxNode::new(SyntaxNodeType::Whitespace, idx..idx+1, vec![]);
+                    let whitespace_node = SyntaxNode::new(SyntaxNodeType::Whitespace, idx..idx+1, pos, vec![]);
                     child_nodes.push(whitespace_node);
                     self.it.next();
                 },
                 ')' => {
-                    let close_paren_node = SyntaxNode::new(SyntaxNodeType::CloseParen, idx..idx+1, vec![]);
+                    let close_paren_node = SyntaxNode::new(SyntaxNodeType::CloseParen, idx..idx+1, pos, vec![]);
                     child_nodes.push(close_paren_node);
                     self.it.next();
 
-                    let expr_node = SyntaxNode::new(SyntaxNodeType::ExpressionGroup, start_idx..self.cur_idx(), child_nodes);
+                    let expr_node = SyntaxNode::new(SyntaxNodeType::ExpressionGroup, start_idx..self.cur_idx(), start_pos, child_nodes);
                     return expr_node;
                 },
                 _ => {
@@ -393,17 +527,19 @@ impl<'a> SExprParser<'a> {
 
                         //If we hit an error parsing a child, then bubble it up
                         if is_err {
-                            let error_group_node = SyntaxNode::new_error_group(start_idx..self.cur_idx(), child_nodes);
+                            let error_group_node = SyntaxNode::new_error_group(start_idx..self.cur_idx(), start_pos, child_nodes);
                             return error_group_node;
                         }
                     } else {
-                        let leftover_node = SyntaxNode::incomplete_with_message(SyntaxNodeType::ErrorGroup, start_idx..self.cur_idx(), child_nodes, "Unexpected end of expression member".to_string());
+                        let error = ParseError::new("Unexpected end of expression member", self.it.position(), self.cur_char(), Some("')' or atom"));
+                        let leftover_node = SyntaxNode::incomplete_with_error(SyntaxNodeType::ErrorGroup, start_idx..self.cur_idx(), start_pos, child_nodes, error);
                         return leftover_node;
                     }
                 },
             }
         }
-        let leftover_node = SyntaxNode::incomplete_with_message(SyntaxNodeType::ErrorGroup, start_idx..self.cur_idx(), child_nodes, "Unexpected end of expression".to_string());
+        let error = ParseError::new("Unexpected end of expression", self.it.position(), None, Some("')' or atom"));
+        let leftover_node = SyntaxNode::incomplete_with_error(SyntaxNodeType::ErrorGroup, start_idx..self.cur_idx(), start_pos, child_nodes, error);
         leftover_node
     }
 
@@ -424,21 +560,31 @@ impl<'a> SExprParser<'a> {
     fn parse_string(&mut self) -> SyntaxNode {
         let mut token = String::new();
         let start_idx = self.cur_idx();
+        let start_pos = self.it.position();
 
-        if let Some((_idx, '"')) = self.it.next() {
+        if let Some((_idx, '"')) = self.it.peek() {
+            self.it.next();
             token.push('"');
         } else {
-            let leftover_text_node = SyntaxNode::incomplete_with_message(SyntaxNodeType::LeftoverText, start_idx..self.cur_idx(), vec![], "Double quote expected".to_string());
+            let error = ParseError::new("Double quote expected", start_pos, self.cur_char(), Some("'\"'"));
+            self.it.next();
+            let leftover_text_node = SyntaxNode::incomplete_with_error(SyntaxNodeType::LeftoverText, start_idx..self.cur_idx(), start_pos, vec![], error);
             return leftover_text_node;
         }
-        while let Some((char_idx, c)) = self.it.next() {
+        loop {
+            let char_pos = self.it.position();
+            let Some((char_idx, c)) = self.it.next() else { break };
             if c == '"' {
                 token.push('"');
-                let string_node = SyntaxNode::new_token_node(SyntaxNodeType::StringToken, start_idx..self.cur_idx(), token);
+                let string_node = SyntaxNode::new_token_node(SyntaxNodeType::StringToken, start_idx..self.cur_idx(), start_pos, token);
                 return string_node;
             }
             if c == '\\' {
-                let escape_err = |cur_idx| { SyntaxNode::incomplete_with_message(SyntaxNodeType::StringToken, char_idx..cur_idx, vec![], "Invalid escape sequence".to_string()) };
+                let escape_err = |parser: &mut Self| {
+                    let cur_idx = parser.cur_idx();
+                    let error = ParseError::new("Invalid escape sequence", char_pos, Some(parser.text[char_idx..cur_idx].to_string()), Some("escape sequence"));
+                    SyntaxNode::incomplete_with_error(SyntaxNodeType::StringToken, char_idx..cur_idx, char_pos, vec![], error)
+                };
 
                 match self.it.next() {
                     Some((_idx, c)) => {
@@ -450,17 +596,18 @@ impl<'a> SExprParser<'a> {
                             'x' => { // hex sequence
                                 match self.parse_2_digit_radix_value(16) {
                                     Some(code_val) => code_val.into(),
-                                    None => {return escape_err(self.cur_idx()); }
+                                    None => {return escape_err(self); }
                                 }
                             },
                             _ => {
-                                return escape_err(self.cur_idx());
+                                return escape_err(self);
                             }
                         };
                         token.push(val);
                     },
                     None => {
-                        let leftover_text_node = SyntaxNode::incomplete_with_message(SyntaxNodeType::StringToken, start_idx..self.cur_idx(), vec![], "Escaping sequence is not finished".to_string());
+                        let error = ParseError::new("Escaping sequence is not finished", self.it.position(), None, Some("escape sequence"));
+                        let leftover_text_node = SyntaxNode::incomplete_with_error(SyntaxNodeType::StringToken, start_idx..self.cur_idx(), start_pos, vec![], error);
                         return leftover_text_node;
                     },
                 }
@@ -468,7 +615,8 @@ impl<'a> SExprParser<'a> {
                 token.push(c);
             }
         }
-        let unclosed_string_node = SyntaxNode::incomplete_with_message(SyntaxNodeType::StringToken, start_idx..self.cur_idx(), vec![], "Unclosed String Literal".to_string());
+        let error = ParseError::new("Unclosed String Literal", self.it.position(), None, Some("'\"'"));
+        let unclosed_string_node = SyntaxNode::incomplete_with_error(SyntaxNodeType::StringToken, start_idx..self.cur_idx(), start_pos, vec![], error);
         unclosed_string_node
     }
 
@@ -489,6 +637,7 @@ impl<'a> SExprParser<'a> {
     fn parse_word(&mut self) -> SyntaxNode {
         let mut token = String::new();
         let start_idx = self.cur_idx();
+        let start_pos = self.it.position();
 
         while let Some((_idx, c)) = self.it.peek() {
             if c.is_whitespace() || *c == '(' || *c == ')' {
@@ -498,12 +647,13 @@ impl<'a> SExprParser<'a> {
             self.it.next();
         }
 
-        let word_node = SyntaxNode::new_token_node(SyntaxNodeType::WordToken, start_idx..self.cur_idx(), token);
+        let word_node = SyntaxNode::new_token_node(SyntaxNodeType::WordToken, start_idx..self.cur_idx(), start_pos, token);
         word_node
     }
 
     fn parse_variable(&mut self) -> SyntaxNode {
         let (start_idx, _c) = self.it.peek().cloned().unwrap();
+        let start_pos = self.it.position();
         let mut tmp_it = self.it.clone();
         tmp_it.next();
 
@@ -513,14 +663,15 @@ impl<'a> SExprParser<'a> {
                 break;
             }
             if *c == '#' {
-                let leftover_node = self.parse_leftovers("'#' char is reserved for internal usage".to_string());
+                let error = ParseError::new("'#' char is reserved for internal usage", tmp_it.position(), Some("#".to_string()), None);
+                let leftover_node = self.parse_leftovers(error);
                 return leftover_node;
             }
             token.push(*c);
             tmp_it.next();
         }
         self.it = tmp_it;
-        let var_token_node = SyntaxNode::new_token_node(SyntaxNodeType::VariableToken, start_idx..self.cur_idx(), token);
+        let var_token_node = SyntaxNode::new_token_node(SyntaxNodeType::VariableToken, start_idx..self.cur_idx(), start_pos, token);
         var_token_node
     }
 
@@ -664,6 +815,41 @@ mod tests {
         assert_eq!(Err(String::from("Unexpected right bracket")), parser.parse(&Tokenizer::new()));
     }
 
+    #[test]
+    fn test_unbalanced_brackets_error_position() {
+        let mut parser = SExprParser::new("(a b)\n  (c))");
+        let tokenizer = Tokenizer::new();
+        assert_eq!(Ok(Some(expr!("a" "b"))), parser.parse_with_error(&tokenizer));
+        assert_eq!(Ok(Some(expr!(("c")))), parser.parse_with_error(&tokenizer));
+        let err = parser.parse_with_error(&tokenizer).unwrap_err();
+        assert_eq!(err, ParseError{ message: "Unexpected right bracket".into(),
+            position: TextPosition::new(2, 6), found: Some(")".into()),
+            expected: Some("atom or '('".into()) });
+        assert_eq!(err.to_string(), "2:6: Unexpected right bracket: expected atom or '(', found ')'");
+
+        let mut parser = SExprParser::new("(a\n(b c)");
+        let err = parser.parse_with_error(&tokenizer).unwrap_err();
+        assert_eq!(err, ParseError{ message: "Unexpected end of expression".into(),
+            position: TextPosition::new(2, 6), found: None,
+            expected: Some("')' or atom".into()) });
+        assert_eq!(err.to_string(), "2:6: Unexpected end of expression: expected ')' or atom, found end of text");
+    }
+
+    #[test]
+    fn test_unterminated_string_error_position() {
+        let mut parser = SExprParser::new("(a \"b\n\u{3bb}c)");
+        let err = parser.parse_with_error(&Tokenizer::new()).unwrap_err();
+        assert_eq!(err, ParseError{ message: "Unclosed String Literal".into(),
+            position: TextPosition::new(2, 4), found: None,
+            expected: Some("'\"'".into()) });
+
+        let mut parser = SExprParser::new("\"a\\qb\"");
+        let err = parser.parse_with_error(&Tokenizer::new()).unwrap_err();
+        assert_eq!(err, ParseError{ message: "Invalid escape sequence".into(),
+            position: TextPosition::new(1, 3), found: Some("\\q".into()),
+            expected: Some("escape sequence".into()) });
+    }
+
     #[test]
     fn test_error_from_tokenizer() {
         //NOTE: This test relies on an intentional bug in the regex, so that it will accept an invalid
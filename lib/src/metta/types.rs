@@ -21,6 +21,7 @@
 use super::*;
 use crate::atom::matcher::{Bindings, BindingsSet, apply_bindings_to_atom_move};
 use crate::space::Space;
+use crate::common::gensym::gensym;
 
 fn typeof_query(atom: &Atom, typ: &Atom) -> Atom {
     Atom::expr(vec![HAS_TYPE_SYMBOL, atom.clone(), typ.clone()])
@@ -36,7 +37,7 @@ fn query_has_type(space: &dyn Space, sub_type: &Atom, super_type: &Atom) -> Bind
 
 fn query_super_types(space: &dyn Space, sub_type: &Atom) -> Vec<Atom> {
     // TODO: query should check that sub type is a type and not another typed symbol
-    let var_x = VariableAtom::new("X").make_unique();
+    let var_x = gensym("X");
     let mut super_types = space.query(&isa_query(&sub_type, &Atom::Variable(var_x.clone())));
     let atom_x = Atom::Variable(var_x);
     super_types.drain(0..).map(|bindings| { apply_bindings_to_atom_move(atom_x.clone(), &bindings) }).collect()
@@ -104,7 +105,7 @@ pub fn is_func(typ: &Atom) -> bool {
 }
 
 fn query_types(space: &dyn Space, atom: &Atom) -> Vec<Atom> {
-    let var_x = VariableAtom::new("X").make_unique();
+    let var_x = gensym("X");
     let mut types = query_has_type(space, atom, &Atom::Variable(var_x.clone()));
     let atom_x = Atom::Variable(var_x);
     let mut types = types.drain(0..).filter_map(|bindings| {
@@ -86,6 +86,7 @@ pub struct GroundingSpace {
     free: BTreeSet<usize>,
     common: SpaceCommon,
     name: Option<String>,
+    data_constructors: HashSet<SymbolAtom>,
 }
 
 impl GroundingSpace {
@@ -98,6 +99,7 @@ impl GroundingSpace {
             free: BTreeSet::new(),
             common: SpaceCommon::default(),
             name: None,
+            data_constructors: HashSet::new(),
         }
     }
 
@@ -113,6 +115,7 @@ impl GroundingSpace {
             free: BTreeSet::new(),
             common: SpaceCommon::default(),
             name: None,
+            data_constructors: HashSet::new(),
         }
     }
 
@@ -270,6 +273,36 @@ impl GroundingSpace {
         }
     }
 
+    /// Executes each of the `patterns` on the space and returns the union of
+    /// the variable bindings found. Results are returned in the order of
+    /// `patterns`; bindings which are equal to a binding already returned
+    /// for a previous pattern are merged into a single result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::{expr, bind, sym};
+    /// use hyperon::space::grounding::GroundingSpace;
+    ///
+    /// let space = GroundingSpace::from_vec(vec![expr!("A" "B"), expr!("C" "D")]);
+    ///
+    /// let result = space.query_any(&[expr!("A" x), expr!("C" x)]);
+    ///
+    /// assert_eq!(result, vec![bind!{x: sym!("B")}, bind!{x: sym!("D")}]);
+    /// ```
+    pub fn query_any(&self, patterns: &[Atom]) -> Vec<matcher::Bindings> {
+        let mut result: Vec<matcher::Bindings> = Vec::new();
+        for pattern in patterns {
+            for bindings in self.query(pattern) {
+                if !result.contains(&bindings) {
+                    result.push(bindings);
+                }
+            }
+        }
+        log::debug!("query_any: result: {:?}", result);
+        result
+    }
+
     /// Executes simple `query` without sub-queries on the space.
     fn single_query(&self, query: &Atom) -> BindingsSet {
         log::debug!("single_query: query: {}", query);
@@ -294,6 +327,29 @@ impl GroundingSpace {
         SpaceIter::new(GroundingSpaceIter::new(self))
     }
 
+    /// Registers `head` as a data constructor. Expressions headed by a data
+    /// constructor are considered to be data and the interpreter returns
+    /// them as is without trying to reduce them using `(= <lhs> <rhs>)`
+    /// rules from the space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::{expr, sym, SymbolAtom};
+    /// use hyperon::space::Space;
+    /// use hyperon::space::grounding::GroundingSpace;
+    ///
+    /// let mut space = GroundingSpace::new();
+    ///
+    /// space.add_data_constructor(SymbolAtom::new("Point".into()));
+    ///
+    /// assert!(space.is_data_constructor(&sym!("Point")));
+    /// assert!(!space.is_data_constructor(&sym!("norm")));
+    /// ```
+    pub fn add_data_constructor(&mut self, head: SymbolAtom) {
+        self.data_constructors.insert(head);
+    }
+
     /// Sets the name property for the `GroundingSpace` which can be useful for debugging
     pub fn set_name(&mut self, name: String) {
         self.name = Some(name);
@@ -312,6 +368,12 @@ impl Space for GroundingSpace {
     fn query(&self, query: &Atom) -> BindingsSet {
         GroundingSpace::query(self, query)
     }
+    fn is_data_constructor(&self, head: &Atom) -> bool {
+        match head {
+            Atom::Symbol(sym) => self.data_constructors.contains(sym),
+            _ => false,
+        }
+    }
     fn atom_count(&self) -> Option<usize> {
         Some(self.iter().count())
     }
@@ -601,6 +663,52 @@ mod test {
         assert_eq!(result, bind_set![{object: expr!("baloon"), color: expr!("blue")}]);
     }
 
+    #[test]
+    fn test_query_any() {
+        let mut space = GroundingSpace::new();
+        space.add(expr!("trigger" "fire" "alarm"));
+        space.add(expr!("trigger" "smoke" "fan"));
+
+        let result = space.query_any(&[
+            expr!("trigger" x "alarm"),
+            expr!("trigger" x y),
+        ]);
+        assert_eq_no_order!(result, vec![
+            bind!{x: sym!("fire")},
+            bind!{x: sym!("fire"), y: sym!("alarm")},
+            bind!{x: sym!("smoke"), y: sym!("fan")},
+        ]);
+
+        let result = space.query_any(&[
+            expr!("trigger" "fire" y),
+            expr!("trigger" x "alarm"),
+            expr!("trigger" "fire" "alarm"),
+        ]);
+        assert_eq!(result, vec![
+            bind!{y: sym!("alarm")},
+            bind!{x: sym!("fire")},
+            bind!{},
+        ]);
+    }
+
+    #[test]
+    fn test_query_any_merges_duplicates() {
+        let mut space = GroundingSpace::new();
+        space.add(expr!("trigger" "fire" "alarm"));
+        space.add(expr!("trigger" "smoke" "alarm"));
+
+        let result = space.query_any(&[
+            expr!("trigger" x "alarm"),
+            expr!("trigger" "fire" "alarm"),
+            expr!("trigger" x "alarm"),
+        ]);
+        assert_eq_no_order!(result, vec![
+            bind!{x: sym!("fire")},
+            bind!{x: sym!("smoke")},
+            bind!{},
+        ]);
+    }
+
     #[test]
     fn test_unify_variables_inside_conjunction_query() {
         let mut space = GroundingSpace::new();
@@ -207,6 +207,12 @@ pub trait Space: std::fmt::Debug + std::fmt::Display {
         None
     }
 
+    /// Returns true if expressions headed by `head` are data and should not
+    /// be reduced by the interpreter
+    fn is_data_constructor(&self, _head: &Atom) -> bool {
+        false
+    }
+
     /// Returns an `&dyn `[Any](std::any::Any) for spaces where this is possible
     fn as_any(&self) -> Option<&dyn std::any::Any>;
 
@@ -341,6 +347,9 @@ impl Space for DynSpace {
     fn atom_iter(&self) -> Option<SpaceIter> {
         None
     }
+    fn is_data_constructor(&self, head: &Atom) -> bool {
+        self.0.borrow().is_data_constructor(head)
+    }
     fn as_any(&self) -> Option<&dyn std::any::Any> {
         None
     }
@@ -387,6 +396,9 @@ impl<T: Space> Space for &T {
     fn atom_iter(&self) -> Option<SpaceIter> {
         T::atom_iter(*self)
     }
+    fn is_data_constructor(&self, head: &Atom) -> bool {
+        T::is_data_constructor(*self, head)
+    }
     fn as_any(&self) -> Option<&dyn std::any::Any> {
         None
     }